@@ -1,133 +1,502 @@
+use std::env;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
 use color_eyre::eyre::{Context, Result};
+use once_cell::sync::Lazy;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Response, StatusCode};
+use tokio::sync::Mutex;
+use tokio::time::{self, Instant};
 
+use crate::api::endpoints;
+use crate::api::metrics::METRICS;
 use crate::api::models::{
-    ChannelDetail, ChannelDetailResponse, Live, LiveDetail, LiveDetailResponse, LivesResponse,
-    PageNext,
+    ChannelDetail, ChannelDetailResponse, ChannelVideo, ChannelVideosResponse, Live, LiveDetail,
+    LiveDetailResponse, LivesResponse, PageNext, VideoChat, VideoChatsResponse,
 };
 use crate::utils::log;
 
-/// ====== HTTP 함수들 ======
+/// 재시도 대기 시간의 최소값
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+/// 재시도 대기 시간의 최대값 (Retry-After도 이 값으로 제한)
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
 
-pub async fn fetch_lives(next: Option<&PageNext>) -> Result<(Vec<Live>, Option<PageNext>)> {
-    let url = if let Some(next) = next {
-        format!(
-            "https://api.chzzk.naver.com/service/v1/lives?size=50&sortType=POPULAR&concurrentUserCount={}&liveId={}",
-            next.concurrent_user_count, next.live_id
-        )
-    } else {
-        "https://api.chzzk.naver.com/service/v1/lives?size=50&sortType=POPULAR".to_string()
-    };
-
-    let client = reqwest::Client::new();
-    let resp: LivesResponse = client
-        .get(&url)
-        .header("User-Agent", "Mozilla")
-        .send()
-        .await?
-        .json()
-        .await
-        .with_context(|| format!("Failed to parse lives response: {}", url))?;
-
-    Ok((resp.content.data, resp.content.page.next))
+/// 모든 API 호출이 공유하는 치지직 HTTP 클라이언트
+pub static CHZZK_CLIENT: Lazy<ChzzkClient> =
+    Lazy::new(|| ChzzkClient::new(ClientConfig::from_env()));
+
+/// HTTP 클라이언트 설정
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// HTTP API 기본 주소 (`None`이면 `endpoints` 설정을 따름)
+    pub api_base_url: Option<String>,
+    pub user_agent: String,
+    /// 요청 하나의 최대 시간
+    pub timeout: Duration,
+    /// 429/5xx/네트워크 오류 시 최대 재시도 횟수
+    pub max_retries: u32,
+    /// 초당 허용 요청 수
+    pub rate_per_sec: f64,
+    /// 한 번에 몰아서 보낼 수 있는 최대 요청 수
+    pub burst: u32,
 }
 
-pub async fn fetch_lives_pages(min_user: u64) -> Result<Vec<Live>> {
-    let mut valid_lives = Vec::new();
-    let mut next: Option<PageNext> = None;
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            api_base_url: None,
+            user_agent: "Mozilla".to_string(),
+            timeout: Duration::from_secs(10),
+            max_retries: 3,
+            rate_per_sec: 10.0,
+            burst: 10,
+        }
+    }
+}
 
-    loop {
-        let (lives, next_page) = fetch_lives(next.as_ref()).await?;
+impl ClientConfig {
+    /// 환경 변수에서 설정을 읽습니다. 지정하지 않은 값은 기본값을 사용합니다.
+    /// `CHZZK_USER_AGENT`, `CHZZK_HTTP_TIMEOUT_SECS`, `CHZZK_MAX_RETRIES`,
+    /// `CHZZK_RATE_PER_SEC`, `CHZZK_RATE_BURST`
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            env::var(name).ok().and_then(|s| s.parse().ok())
+        }
 
-        // let stop = filtered.len() < filtered.capacity(); // lives.len()와 같지 않으면 중단
-        let stop = lives.iter().any(|l| l.concurrent_user_count < min_user);
+        let default = Self::default();
+        Self {
+            api_base_url: None,
+            user_agent: env::var("CHZZK_USER_AGENT").unwrap_or(default.user_agent),
+            timeout: var("CHZZK_HTTP_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(default.timeout),
+            max_retries: var("CHZZK_MAX_RETRIES").unwrap_or(default.max_retries),
+            rate_per_sec: var("CHZZK_RATE_PER_SEC").unwrap_or(default.rate_per_sec),
+            burst: var("CHZZK_RATE_BURST").unwrap_or(default.burst),
+        }
+    }
+}
 
-        valid_lives.append(
-            &mut lives
-                .into_iter()
-                .filter(|l| l.concurrent_user_count >= min_user)
-                .collect(),
-        );
+/// 토큰 버킷 방식의 요청 속도 제한기
+struct RateLimiter {
+    rate_per_sec: f64,
+    capacity: f64,
+    /// (남은 토큰 수, 마지막 충전 시간)
+    state: Mutex<(f64, Instant)>,
+}
 
-        if stop {
-            break;
+impl RateLimiter {
+    fn new(rate_per_sec: f64, burst: u32) -> Self {
+        let capacity = burst.max(1) as f64;
+        Self {
+            rate_per_sec: rate_per_sec.max(0.001),
+            capacity,
+            state: Mutex::new((capacity, Instant::now())),
         }
+    }
 
-        if next_page.is_none() {
-            break;
-        }
+    /// 토큰 하나를 얻을 때까지 기다립니다.
+    async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().await;
+                let now = Instant::now();
+                let elapsed = now.duration_since(state.1).as_secs_f64();
+                state.0 = (state.0 + elapsed * self.rate_per_sec).min(self.capacity);
+                state.1 = now;
 
-        next = next_page;
-        println!("next: {:?}", next);
+                if state.0 >= 1.0 {
+                    state.0 -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - state.0) / self.rate_per_sec)
+            };
+            time::sleep(wait).await;
+        }
     }
+}
 
-    Ok(valid_lives)
+/// 연결 재사용, 속도 제한, 재시도, 타임아웃이 적용된 치지직 API 클라이언트
+pub struct ChzzkClient {
+    http: reqwest::Client,
+    config: ClientConfig,
+    limiter: RateLimiter,
 }
 
-pub async fn fetch_channel(channel_id: &str) -> Result<Option<ChannelDetail>> {
-    let url = format!(
-        "https://api.chzzk.naver.com/service/v1/channels/{}",
-        channel_id
-    );
-
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(&url)
-        .header("User-Agent", "Mozilla")
-        .send()
-        .await?;
-
-    let status = resp.status();
-    if !status.is_success() {
-        log(format!(
-            "fetchChannel() HTTP error {} for channel {}",
-            status, channel_id
-        ));
-        return Ok(None);
+impl ChzzkClient {
+    pub fn new(config: ClientConfig) -> Self {
+        let http = reqwest::Client::builder()
+            .user_agent(config.user_agent.clone())
+            .timeout(config.timeout)
+            .build()
+            .expect("Failed to build HTTP client");
+        let limiter = RateLimiter::new(config.rate_per_sec, config.burst);
+
+        Self {
+            http,
+            config,
+            limiter,
+        }
+    }
+
+    /// HTTP API 경로에 기본 주소를 붙여 전체 URL을 만듭니다.
+    pub fn api_url(&self, path: &str) -> String {
+        match &self.config.api_base_url {
+            Some(base) => format!("{}{}", base.trim_end_matches('/'), path),
+            None => endpoints::api_url(path),
+        }
+    }
+
+    /// GET 요청을 보냅니다.
+    /// 429/5xx 응답이나 네트워크 오류는 `Retry-After` 또는 지수 backoff만큼 기다린 뒤 재시도하고,
+    /// 재시도 횟수를 모두 쓰면 마지막 응답(또는 오류)을 그대로 반환합니다.
+    pub async fn get(&self, url: &str, query: &[(&str, &str)]) -> Result<Response> {
+        let mut attempt = 0;
+        loop {
+            self.limiter.acquire().await;
+
+            let result = self.http.get(url).query(query).send().await;
+            match &result {
+                Ok(resp) if !resp.status().is_success() => {
+                    METRICS.record_api_error(url, resp.status().as_str())
+                }
+                Ok(_) => {}
+                Err(e) => METRICS.record_api_error(url, error_kind(e)),
+            }
+            let retry_after = match &result {
+                Ok(resp) if is_retryable(resp.status()) => Some(retry_after(resp)),
+                Ok(_) => None,
+                Err(e) if e.is_timeout() || e.is_connect() => Some(None),
+                Err(_) => None,
+            };
+
+            let Some(retry_after) = retry_after.filter(|_| attempt < self.config.max_retries)
+            else {
+                return result.with_context(|| format!("HTTP request failed: {}", url));
+            };
+
+            let delay = retry_after
+                .unwrap_or_else(|| RETRY_BASE_DELAY.saturating_mul(1 << attempt.min(16)))
+                .min(RETRY_MAX_DELAY);
+            attempt += 1;
+            log(format!(
+                "Retrying {} in {:.1}s (attempt {}, {})",
+                url,
+                delay.as_secs_f64(),
+                attempt,
+                match &result {
+                    Ok(resp) => resp.status().to_string(),
+                    Err(e) => e.to_string(),
+                }
+            ));
+            time::sleep(delay).await;
+        }
+    }
+
+    /// ====== HTTP 함수들 ======
+
+    pub async fn fetch_lives(
+        &self,
+        next: Option<&PageNext>,
+    ) -> Result<(Vec<Live>, Option<PageNext>)> {
+        let url = if let Some(next) = next {
+            self.api_url(&format!(
+                "/service/v1/lives?size=50&sortType=POPULAR&concurrentUserCount={}&liveId={}",
+                next.concurrent_user_count, next.live_id
+            ))
+        } else {
+            self.api_url("/service/v1/lives?size=50&sortType=POPULAR")
+        };
+
+        let resp: LivesResponse = self
+            .get(&url, &[])
+            .await?
+            .json()
+            .await
+            .with_context(|| format!("Failed to parse lives response: {}", url))?;
+
+        Ok((resp.content.data, resp.content.page.next))
+    }
+
+    pub async fn fetch_lives_pages(&self, min_user: u64) -> Result<Vec<Live>> {
+        let mut valid_lives = Vec::new();
+        let mut next: Option<PageNext> = None;
+
+        loop {
+            let (lives, next_page) = self.fetch_lives(next.as_ref()).await?;
+
+            // let stop = filtered.len() < filtered.capacity(); // lives.len()와 같지 않으면 중단
+            let stop = lives.iter().any(|l| l.concurrent_user_count < min_user);
+
+            valid_lives.append(
+                &mut lives
+                    .into_iter()
+                    .filter(|l| l.concurrent_user_count >= min_user)
+                    .collect(),
+            );
+
+            if stop {
+                break;
+            }
+
+            if next_page.is_none() {
+                break;
+            }
+
+            next = next_page;
+            println!("next: {:?}", next);
+        }
+
+        Ok(valid_lives)
+    }
+
+    pub async fn fetch_channel(&self, channel_id: &str) -> Result<Option<ChannelDetail>> {
+        let url = self.api_url(&format!("/service/v1/channels/{}", channel_id));
+
+        let resp = self.get(&url, &[]).await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            log(format!(
+                "fetchChannel() HTTP error {} for channel {}",
+                status, channel_id
+            ));
+            return Ok(None);
+        }
+
+        let json: ChannelDetailResponse = resp.json().await.with_context(|| {
+            format!("fetchChannel() JSON parse error for channel {}", channel_id)
+        })?;
+
+        if json.content.is_none() {
+            log(format!(
+                "fetchChannel() JSON Error! channel_id={} (content is null)",
+                channel_id
+            ));
+        }
+
+        Ok(json.content)
     }
 
-    let json: ChannelDetailResponse = resp
-        .json()
-        .await
-        .with_context(|| format!("fetchChannel() JSON parse error for channel {}", channel_id))?;
+    pub async fn fetch_live_detail(&self, channel_id: &str) -> Result<Option<LiveDetail>> {
+        let url = self.api_url(&format!("/service/v3/channels/{}/live-detail", channel_id));
+
+        let resp = self.get(&url, &[]).await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            log(format!(
+                "fetchLiveDetail() HTTP error {} for channel {}",
+                status, channel_id
+            ));
+            return Ok(None);
+        }
+
+        let json: LiveDetailResponse = resp.json().await.with_context(|| {
+            format!(
+                "fetchLiveDetail() JSON parse error for channel {}",
+                channel_id
+            )
+        })?;
+
+        Ok(json.content)
+    }
+
+    /// 다시보기 채팅 한 페이지를 가져옵니다.
+    /// `player_message_time`부터 시작하는 채팅과 다음 페이지의 `playerMessageTime`을 반환합니다.
+    /// 다음 페이지가 없으면 `None`을 반환합니다.
+    pub async fn fetch_video_chats(
+        &self,
+        video_no: u64,
+        player_message_time: i64,
+    ) -> Result<(Vec<VideoChat>, Option<i64>)> {
+        let url = self.api_url(&format!(
+            "/service/v1/videos/{}/chats?playerMessageTime={}",
+            video_no, player_message_time
+        ));
+
+        let resp = self.get(&url, &[]).await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(color_eyre::eyre::eyre!(
+                "fetchVideoChats() HTTP error {} for video {}",
+                status,
+                video_no
+            ));
+        }
+
+        let json: VideoChatsResponse = resp.json().await.with_context(|| {
+            format!("fetchVideoChats() JSON parse error for video {}", video_no)
+        })?;
+
+        let Some(content) = json.content else {
+            return Ok((Vec::new(), None));
+        };
+
+        Ok((content.video_chats, content.next_player_message_time))
+    }
 
-    if json.content.is_none() {
-        log(format!(
-            "fetchChannel() JSON Error! channel_id={} (content is null)",
-            channel_id
+    /// 채널의 다시보기 목록 한 페이지를 최신순으로 가져옵니다.
+    /// 비디오 목록과 전체 페이지 수를 반환합니다.
+    pub async fn fetch_channel_videos(
+        &self,
+        channel_id: &str,
+        page: u32,
+        size: u32,
+    ) -> Result<(Vec<ChannelVideo>, u32)> {
+        let url = self.api_url(&format!(
+            "/service/v1/channels/{}/videos?sortType=LATEST&pagingType=PAGE&page={}&size={}&videoType=REPLAY",
+            channel_id, page, size
         ));
+
+        let resp = self.get(&url, &[]).await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(color_eyre::eyre::eyre!(
+                "fetchChannelVideos() HTTP error {} for channel {}",
+                status,
+                channel_id
+            ));
+        }
+
+        let json: ChannelVideosResponse = resp.json().await.with_context(|| {
+            format!(
+                "fetchChannelVideos() JSON parse error for channel {}",
+                channel_id
+            )
+        })?;
+
+        let Some(content) = json.content else {
+            return Ok((Vec::new(), 0));
+        };
+
+        Ok((content.data, content.total_pages))
     }
 
-    Ok(json.content)
+    /// 채널의 다시보기 목록을 페이지를 넘기며 모두 가져옵니다.
+    /// `max_pages`를 지정하면 최신 페이지부터 그 수만큼만 가져옵니다.
+    pub async fn fetch_channel_videos_pages(
+        &self,
+        channel_id: &str,
+        size: u32,
+        max_pages: Option<u32>,
+    ) -> Result<Vec<ChannelVideo>> {
+        let mut videos = Vec::new();
+        let mut page = 0;
+
+        loop {
+            let (mut data, total_pages) = self.fetch_channel_videos(channel_id, page, size).await?;
+            let empty = data.is_empty();
+            videos.append(&mut data);
+
+            page += 1;
+            if empty || page >= total_pages || max_pages.is_some_and(|max| page >= max) {
+                break;
+            }
+        }
+
+        Ok(videos)
+    }
 }
 
-pub async fn fetch_live_detail(channel_id: &str) -> Result<Option<LiveDetail>> {
-    let url = format!(
-        "https://api.chzzk.naver.com/service/v3/channels/{}/live-detail",
-        channel_id
-    );
-
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(&url)
-        .header("User-Agent", "Mozilla")
-        .send()
-        .await?;
-
-    let status = resp.status();
-    if !status.is_success() {
-        log(format!(
-            "fetchLiveDetail() HTTP error {} for channel {}",
-            status, channel_id
-        ));
-        return Ok(None);
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// `Retry-After` 헤더를 읽습니다. 초 단위 값과 HTTP-date(`Wed, 21 Oct 2015 07:28:00 GMT`)를 모두 받습니다.
+fn retry_after(resp: &Response) -> Option<Duration> {
+    parse_retry_after(resp.headers().get(RETRY_AFTER)?.to_str().ok()?, Utc::now())
+}
+
+/// `Retry-After` 값을 `now` 기준 대기 시간으로 바꿉니다. 이미 지난 시각이면 0을 반환합니다.
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse() {
+        return Some(Duration::from_secs(secs));
     }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (at.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// 지표 라벨로 쓸 요청 오류 종류
+fn error_kind(e: &reqwest::Error) -> &'static str {
+    if e.is_timeout() {
+        "timeout"
+    } else if e.is_connect() {
+        "connect"
+    } else {
+        "error"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mock_server::{channel_detail_response, MockChzzk};
 
-    let json: LiveDetailResponse = resp.json().await.with_context(|| {
-        format!(
-            "fetchLiveDetail() JSON parse error for channel {}",
-            channel_id
+    fn test_client(server: &MockChzzk) -> ChzzkClient {
+        ChzzkClient::new(ClientConfig {
+            api_base_url: Some(server.endpoints().api_base_url),
+            max_retries: 2,
+            ..ClientConfig::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_retries_on_429_and_5xx() {
+        let channel_id = "0123456789abcdef0123456789abcdef";
+        let path = format!("/service/v1/channels/{}", channel_id);
+        let server = MockChzzk::start(
+            vec![(path.clone(), channel_detail_response(channel_id, 10))],
+            Vec::new(),
+            Vec::new(),
         )
-    })?;
+        .await;
+        server.fail_next(&path, &[(429, Some(0)), (503, Some(0))]);
+
+        let client = test_client(&server);
+        let detail = client.fetch_channel(channel_id).await.unwrap().unwrap();
+        assert_eq!(detail.follower_count, Some(10));
+
+        // 재시도 횟수를 넘으면 마지막 응답을 그대로 돌려줌
+        server.fail_next(&path, &[(500, Some(0)), (500, Some(0)), (500, Some(0))]);
+        assert!(client.fetch_channel(channel_id).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
 
-    Ok(json.content)
+        assert_eq!(parse_retry_after(" 5 ", now), Some(Duration::from_secs(5)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        // 이미 지난 시각이면 바로 재시도
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_throttles_after_burst() {
+        let limiter = RateLimiter::new(20.0, 2);
+        let start = Instant::now();
+        for _ in 0..4 {
+            limiter.acquire().await;
+        }
+        // 2개는 바로, 나머지 2개는 0.05초 간격
+        assert!(start.elapsed() >= Duration::from_millis(95));
+    }
 }
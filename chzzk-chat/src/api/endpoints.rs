@@ -0,0 +1,51 @@
+use std::env;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+
+/// 치지직 HTTP API 기본 주소
+const DEFAULT_API_BASE_URL: &str = "https://api.chzzk.naver.com";
+/// 치지직 채팅 웹소켓 주소
+const DEFAULT_CHAT_WS_URL: &str = "wss://kr-ss1.chat.naver.com/chat";
+
+/// 치지직 HTTP/WebSocket 접속 주소
+#[derive(Debug, Clone)]
+pub struct Endpoints {
+    /// HTTP API 기본 주소 (끝에 `/` 없이)
+    pub api_base_url: String,
+    /// 채팅 웹소켓 주소
+    pub chat_ws_url: String,
+}
+
+impl Endpoints {
+    /// `CHZZK_API_BASE_URL`, `CHZZK_CHAT_WS_URL` 환경 변수에서 주소를 읽습니다.
+    /// 지정하지 않으면 실제 치지직 서버 주소를 사용합니다.
+    pub fn from_env() -> Self {
+        Self {
+            api_base_url: env::var("CHZZK_API_BASE_URL")
+                .unwrap_or_else(|_| DEFAULT_API_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            chat_ws_url: env::var("CHZZK_CHAT_WS_URL")
+                .unwrap_or_else(|_| DEFAULT_CHAT_WS_URL.to_string()),
+        }
+    }
+}
+
+static ENDPOINTS: Lazy<RwLock<Endpoints>> = Lazy::new(|| RwLock::new(Endpoints::from_env()));
+
+/// 접속 주소를 교체합니다. (테스트에서 mock 서버로 연결할 때 사용)
+#[cfg(test)]
+pub fn set_endpoints(endpoints: Endpoints) {
+    *ENDPOINTS.write().unwrap() = endpoints;
+}
+
+/// HTTP API 경로(`/service/...`)에 기본 주소를 붙여 전체 URL을 만듭니다.
+pub fn api_url(path: &str) -> String {
+    format!("{}{}", ENDPOINTS.read().unwrap().api_base_url, path)
+}
+
+/// 채팅 웹소켓 주소
+pub fn chat_ws_url() -> String {
+    ENDPOINTS.read().unwrap().chat_ws_url.clone()
+}
@@ -0,0 +1,591 @@
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use color_eyre::eyre::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::api::recorder::{kst_from_millis, live_chat_log_dir};
+use crate::data::chat::event::EventInterval;
+use crate::data::chat::event_config::{EventPreset, EventSettings};
+use crate::data::chat::online_event::{EventPhase, EventUpdate, OnlineEventDetector};
+use crate::utils::log;
+
+/// `auto` preset에서 채팅량을 재는 시간 (초)
+const AUTO_PRESET_WARMUP_SECS: usize = 60;
+/// 웹훅 요청 타임아웃
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+/// 처리 대기열에 쌓아 둘 최대 알림 묶음 수 (웹훅이 밀리면 넘친 알림은 버림)
+const PUBLISH_QUEUE_CAPACITY: usize = 1024;
+
+/// 실시간 이벤트 알림 설정 (None이면 이벤트를 탐지하지 않음)
+static LIVE_EVENT_CONFIG: Lazy<Mutex<Option<LiveEventConfig>>> = Lazy::new(|| Mutex::new(None));
+
+/// 알림 파일 기록과 웹훅 전송 작업 (`configure`에서 시작)
+static PUBLISHER: Lazy<Mutex<Option<EventPublisher>>> = Lazy::new(|| Mutex::new(None));
+
+static WEBHOOK_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .expect("Failed to build HTTP client")
+});
+
+/// 실시간 이벤트 알림 설정
+#[derive(Debug, Clone)]
+pub struct LiveEventConfig {
+    /// 알림을 추가할 JSONL 파일 경로
+    pub output: PathBuf,
+    /// 알림을 JSON으로 POST할 URL
+    pub webhook: Option<String>,
+    pub settings: EventSettings,
+    pub lateness_secs: i64,
+}
+
+/// 이벤트 알림 파일 기본 경로 (`LIVE_CHAT_LOG_DIR/liveEvents.jsonl`)
+pub fn live_events_path() -> PathBuf {
+    live_chat_log_dir().join("liveEvents.jsonl")
+}
+
+/// JSONL 파일과 웹훅으로 내보내는 이벤트 알림
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveEventNotification {
+    #[serde(rename = "channelId")]
+    pub channel_id: String,
+    #[serde(rename = "liveId")]
+    pub live_id: u64,
+    pub phase: EventPhase,
+    /// 알림 시간 (마지막으로 확정한 초, ISO 8601 형식)
+    pub time: String,
+    /// 이벤트 구간 기준 시간 (첫 채팅, ISO 8601 형식)
+    #[serde(rename = "firstMessageTime")]
+    pub first_message_time: String,
+    /// 첫 채팅 기준 초 단위 구간
+    pub event: EventInterval,
+}
+
+/// 라이브 하나의 채팅을 초 단위로 세어 이벤트를 탐지합니다.
+/// 채팅은 `lateness_secs`만큼 기다렸다가 초 단위로 확정하고, 확정한 초보다 늦게 온 채팅은 버립니다.
+/// 다음에 확정할 초보다 이른 채팅(처음 본 채팅보다 이른 채팅, 재연결 후 늦게 온 이전 채팅)은 다음에 확정할 초로 셉니다.
+pub struct LiveEventTracker {
+    channel_id: String,
+    live_id: u64,
+    settings: EventSettings,
+    lateness_secs: i64,
+    /// 아직 확정하지 않은 초(unix 초)별 채팅 수
+    pending: BTreeMap<i64, usize>,
+    /// 다음에 확정할 초 (첫 채팅 전이나 재연결 후 첫 채팅 전에는 None)
+    next_sec: Option<i64>,
+    /// 마지막으로 확정한 초
+    confirmed_until: Option<i64>,
+    /// 첫 채팅 초 (이벤트 구간 기준)
+    first_sec: Option<i64>,
+    detector: Option<OnlineEventDetector>,
+    /// detector를 만들기 전에 확정한 (초, 채팅 수) (`auto` preset 채팅량 측정용)
+    warmup: Vec<(i64, usize)>,
+}
+
+impl LiveEventTracker {
+    pub fn new(
+        channel_id: &str,
+        live_id: u64,
+        settings: EventSettings,
+        lateness_secs: i64,
+    ) -> Self {
+        Self {
+            channel_id: channel_id.to_string(),
+            live_id,
+            settings,
+            lateness_secs: lateness_secs.max(0),
+            pending: BTreeMap::new(),
+            next_sec: None,
+            confirmed_until: None,
+            first_sec: None,
+            detector: None,
+            warmup: Vec::new(),
+        }
+    }
+
+    /// 채팅 한 건(`msg_time`, ms)을 셉니다.
+    pub fn record(&mut self, msg_time: i64) {
+        let sec = msg_time.div_euclid(1000);
+        if self
+            .confirmed_until
+            .is_some_and(|confirmed| sec <= confirmed)
+        {
+            return;
+        }
+        self.first_sec.get_or_insert(sec);
+        // 확정은 `next_sec`부터 하므로 그보다 이른 초에 넣으면 확정되지 않고 남음
+        let next = *self.next_sec.get_or_insert(sec);
+        let sec = sec.max(next);
+        *self.pending.entry(sec).or_insert(0) += 1;
+    }
+
+    /// `now`(ms)에서 `lateness_secs` 이전까지의 초를 확정하고 생긴 알림을 반환합니다.
+    /// 채팅이 없는 초는 0개로 확정합니다.
+    pub fn advance(&mut self, now: i64) -> Vec<LiveEventNotification> {
+        let until = now.div_euclid(1000) - self.lateness_secs;
+        self.confirm_before(until)
+    }
+
+    /// 연결이 끊겼을 때 받은 채팅을 모두 확정하고, 다음 채팅이 올 때까지 확정을 멈춥니다.
+    /// 끊긴 동안의 초는 0개로 채우지 않고 건너뜁니다.
+    pub fn pause(&mut self) -> Vec<LiveEventNotification> {
+        let notifications = match self.pending.keys().next_back() {
+            Some(&last) => self.confirm_before(last + 1),
+            None => Vec::new(),
+        };
+        self.next_sec = None;
+        notifications
+    }
+
+    /// 라이브가 끝났을 때 남은 채팅을 확정하고 진행 중인 이벤트를 끝냅니다.
+    pub fn finish(&mut self) -> Vec<LiveEventNotification> {
+        let mut notifications = self.pause();
+        let Some(last) = self.confirmed_until else {
+            return notifications;
+        };
+        let mut updates = Vec::new();
+        if self.detector.is_none() && !self.warmup.is_empty() {
+            updates.extend(self.start_detector());
+        }
+        if let Some(detector) = &mut self.detector {
+            updates.extend(detector.finish());
+        }
+        notifications.extend(
+            updates
+                .into_iter()
+                .filter_map(|update| self.notification(update, last)),
+        );
+        notifications
+    }
+
+    /// `until` 이전의 초를 확정합니다.
+    fn confirm_before(&mut self, until: i64) -> Vec<LiveEventNotification> {
+        let Some(mut next) = self.next_sec else {
+            return Vec::new();
+        };
+        let mut notifications = Vec::new();
+        while next < until {
+            let count = self.pending.remove(&next).unwrap_or(0);
+            for update in self.push(next, count) {
+                notifications.extend(self.notification(update, next));
+            }
+            next += 1;
+        }
+        self.next_sec = Some(next);
+        notifications
+    }
+
+    fn push(&mut self, sec: i64, count: usize) -> Vec<EventUpdate> {
+        self.confirmed_until = Some(sec);
+        let first_sec = self.first_sec.unwrap_or(sec);
+        match &mut self.detector {
+            Some(detector) => detector.push(sec - first_sec, count),
+            None => {
+                self.warmup.push((sec, count));
+                if self.settings.preset == EventPreset::Auto
+                    && self.warmup.len() < AUTO_PRESET_WARMUP_SECS
+                {
+                    return Vec::new();
+                }
+                self.start_detector()
+            }
+        }
+    }
+
+    /// 확정한 채팅량으로 탐지 파라미터를 정해 detector를 만들고 지금까지의 초를 넣습니다.
+    fn start_detector(&mut self) -> Vec<EventUpdate> {
+        let total: usize = self.warmup.iter().map(|&(_, count)| count).sum();
+        let rate = total as f64 / self.warmup.len().max(1) as f64;
+        let config = self.settings.detection_config_for_rate(rate);
+        let mut detector = OnlineEventDetector::new(&config);
+
+        let first_sec = self.first_sec.unwrap_or_default();
+        let updates = self
+            .warmup
+            .drain(..)
+            .flat_map(|(sec, count)| detector.push(sec - first_sec, count))
+            .collect();
+        self.detector = Some(detector);
+        updates
+    }
+
+    fn notification(&self, update: EventUpdate, sec: i64) -> Option<LiveEventNotification> {
+        let format = |sec: i64| {
+            kst_from_millis(sec * 1000).map(|time| time.format("%Y-%m-%dT%H:%M:%S%z").to_string())
+        };
+        Some(LiveEventNotification {
+            channel_id: self.channel_id.clone(),
+            live_id: self.live_id,
+            phase: update.phase,
+            time: format(sec)?,
+            first_message_time: format(self.first_sec?)?,
+            event: update.event,
+        })
+    }
+}
+
+/// 전역 알림 설정을 바꾸고 알림 처리 작업을 시작합니다. `None`이면 이벤트를 탐지하지 않습니다.
+/// 이전 작업은 남은 알림을 처리한 뒤 끝납니다.
+pub fn configure(config: Option<LiveEventConfig>) {
+    *PUBLISHER.lock().unwrap() = config
+        .as_ref()
+        .map(|config| EventPublisher::start(config.output.clone(), config.webhook.clone()));
+    *LIVE_EVENT_CONFIG.lock().unwrap() = config;
+}
+
+/// 전역 설정으로 라이브의 이벤트 추적기를 만듭니다. 탐지를 끈 경우 None을 반환합니다.
+pub fn tracker(channel_id: &str, live_id: u64) -> Option<LiveEventTracker> {
+    let config = LIVE_EVENT_CONFIG.lock().unwrap();
+    let config = config.as_ref()?;
+    Some(LiveEventTracker::new(
+        channel_id,
+        live_id,
+        config.settings.clone(),
+        config.lateness_secs,
+    ))
+}
+
+/// 알림들을 JSONL 파일 뒤에 한 줄씩 추가합니다.
+pub fn append_notifications<P: AsRef<Path>>(
+    path: P,
+    notifications: &[LiveEventNotification],
+) -> Result<()> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create live event directory: {:?}", dir))?;
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open live event file: {:?}", path))?;
+    let mut writer = BufWriter::new(file);
+    for notification in notifications {
+        writeln!(writer, "{}", serde_json::to_string(notification)?)
+            .with_context(|| format!("Failed to write live event file: {:?}", path))?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// 알림을 보낸 순서대로 JSONL 파일에 기록하고 웹훅으로 보내는 작업
+/// 모든 세션의 알림이 채널 하나로 작업 하나에 전달되므로, 웹훅 요청이 서로 앞지르지 않습니다.
+/// 파일 쓰기는 비동기 런타임을 막지 않도록 `spawn_blocking`에서 실행합니다.
+/// 대기열은 `PUBLISH_QUEUE_CAPACITY`개 묶음까지만 쌓아 웹훅이 멈춰도 메모리가 계속 늘지 않습니다.
+pub struct EventPublisher {
+    sender: mpsc::Sender<Vec<LiveEventNotification>>,
+    task: JoinHandle<()>,
+}
+
+impl EventPublisher {
+    /// 알림 처리 작업을 시작합니다.
+    pub fn start(output: PathBuf, webhook: Option<String>) -> Self {
+        let (sender, mut receiver) =
+            mpsc::channel::<Vec<LiveEventNotification>>(PUBLISH_QUEUE_CAPACITY);
+        let task = tokio::spawn(async move {
+            while let Some(notifications) = receiver.recv().await {
+                let path = output.clone();
+                let written = tokio::task::spawn_blocking(move || {
+                    let result = append_notifications(&path, &notifications);
+                    (notifications, result)
+                })
+                .await;
+                let notifications = match written {
+                    Ok((notifications, result)) => {
+                        if let Err(e) = result {
+                            log(format!("Failed to write live events: {:?}", e));
+                        }
+                        notifications
+                    }
+                    Err(e) => {
+                        log(format!("Live event writer failed: {:?}", e));
+                        continue;
+                    }
+                };
+
+                let Some(url) = &webhook else {
+                    continue;
+                };
+                for notification in &notifications {
+                    let result = WEBHOOK_CLIENT
+                        .post(url)
+                        .json(notification)
+                        .send()
+                        .await
+                        .and_then(|resp| resp.error_for_status());
+                    if let Err(e) = result {
+                        log(format!("Live event webhook failed: {}", e));
+                    }
+                }
+            }
+        });
+        Self { sender, task }
+    }
+
+    /// 알림 묶음을 처리 대기열에 넣습니다. 대기열이 가득 차면 기다리지 않고 버립니다.
+    pub fn send(&self, notifications: Vec<LiveEventNotification>) {
+        match self.sender.try_send(notifications) {
+            Ok(()) => {}
+            Err(mpsc::error::TrySendError::Full(notifications)) => log(format!(
+                "Live event queue is full, dropping {} notifications",
+                notifications.len()
+            )),
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                log("Live event publisher stopped, dropping notifications")
+            }
+        }
+    }
+
+    /// 대기열에 남은 알림을 모두 처리할 때까지 기다립니다.
+    pub async fn close(self) {
+        drop(self.sender);
+        if let Err(e) = self.task.await {
+            log(format!("Live event publisher failed: {:?}", e));
+        }
+    }
+}
+
+/// 알림을 로그로 남기고 파일 기록과 웹훅 전송 작업에 넘깁니다. 채팅 수집을 막지 않도록 바로 반환합니다.
+pub fn publish(notifications: Vec<LiveEventNotification>) {
+    if notifications.is_empty() {
+        return;
+    }
+    let publisher = PUBLISHER.lock().unwrap();
+    let Some(publisher) = publisher.as_ref() else {
+        return;
+    };
+
+    for notification in &notifications {
+        log(format!(
+            "Event {:?} channel_id={} live_id={} peak={}s z={:.1} ({}s ~ {}s)",
+            notification.phase,
+            notification.channel_id,
+            notification.live_id,
+            notification.event.peak_sec,
+            notification.event.peak_z_score,
+            notification.event.start_sec,
+            notification.event.end_sec
+        ));
+    }
+    publisher.send(notifications);
+}
+
+/// 알림 처리 작업을 멈추고, 그 전에 보낸 알림을 모두 기록하고 보낼 때까지 기다립니다.
+pub async fn shutdown() {
+    let publisher = PUBLISHER.lock().unwrap().take();
+    if let Some(publisher) = publisher {
+        publisher.close().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::chat::event_config::EventDetectionOverrides;
+
+    #[test]
+    fn test_live_event_tracker_notifications() {
+        let settings = EventSettings {
+            preset: EventPreset::Large,
+            detection: EventDetectionOverrides {
+                baseline_window_sec: Some(120),
+                baseline_lag_sec: Some(10),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut tracker = LiveEventTracker::new("c1", 7, settings, 5);
+        let base = 1_735_700_400_000; // 2025-01-01T12:00:00+0900
+
+        // 평소 초당 1~2개, 200초부터 10초 동안 초당 40개
+        let mut notifications = Vec::new();
+        for sec in 0..300i64 {
+            let count = if (200..210).contains(&sec) {
+                40
+            } else {
+                1 + sec % 2
+            };
+            for i in 0..count {
+                tracker.record(base + sec * 1000 + i * 10);
+            }
+            notifications.extend(tracker.advance(base + sec * 1000 + 999));
+        }
+        // 이미 확정한 초의 늦은 채팅은 버림
+        tracker.record(base + 100_000);
+        assert_eq!(tracker.pending.keys().next(), Some(&(base / 1000 + 294)));
+
+        // 연결이 끊긴 동안은 건너뜀
+        notifications.extend(tracker.pause());
+        assert!(tracker.advance(base + 400_000).is_empty());
+        tracker.record(base + 500_000);
+        // 재연결 후 첫 채팅보다 이른 이전 채팅은 다음에 확정할 초로 셈
+        tracker.record(base + 450_000);
+        assert_eq!(
+            tracker.pending.iter().collect::<Vec<_>>(),
+            vec![(&(base / 1000 + 500), &2)]
+        );
+        notifications.extend(tracker.finish());
+        assert!(tracker.pending.is_empty());
+
+        let phases: Vec<EventPhase> = notifications.iter().map(|n| n.phase).collect();
+        assert_eq!(
+            phases,
+            vec![EventPhase::Started, EventPhase::Peaked, EventPhase::Ended]
+        );
+        let ended = &notifications[2];
+        assert!(ended.event.start_sec <= 200 && 200 <= ended.event.peak_sec);
+        assert!(ended.event.end_sec >= 209 && ended.event.end_sec < 300);
+        assert_eq!(ended.first_message_time, "2025-01-01T12:00:00+0900");
+        // 알림은 확정한 초에 나옴 (5초 지연)
+        assert!(notifications[0].time.as_str() >= "2025-01-01T12:03:20+0900");
+        assert_eq!(tracker.confirmed_until, Some(base / 1000 + 500));
+
+        let json = serde_json::to_string(ended).unwrap();
+        assert!(json.contains("\"channelId\":\"c1\"") && json.contains("\"phase\":\"ended\""));
+
+        // 처음 본 채팅보다 이른 채팅도 확정됨
+        let mut tracker = LiveEventTracker::new("c1", 7, EventSettings::default(), 0);
+        tracker.record(base + 10_000);
+        tracker.record(base + 5_000);
+        tracker.advance(base + 11_000);
+        assert!(tracker.pending.is_empty());
+        assert_eq!(tracker.confirmed_until, Some(base / 1000 + 10));
+    }
+
+    #[tokio::test]
+    async fn test_event_publisher_keeps_order() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // 받은 웹훅 본문을 순서대로 모으는 서버 (첫 요청은 늦게 응답)
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let received = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let server_received = received.clone();
+        let server = tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 1024];
+                let body = loop {
+                    let n = stream.read(&mut chunk).await.unwrap();
+                    if n == 0 {
+                        return;
+                    }
+                    buf.extend_from_slice(&chunk[..n]);
+                    let request = String::from_utf8_lossy(&buf).to_string();
+                    let Some((head, body)) = request.split_once("\r\n\r\n") else {
+                        continue;
+                    };
+                    let length: usize = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_ascii_lowercase()
+                                .strip_prefix("content-length: ")
+                                .map(str::to_string)
+                        })
+                        .and_then(|len| len.trim().parse().ok())
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break body.to_string();
+                    }
+                };
+                let body: LiveEventNotification = serde_json::from_str(&body).unwrap();
+                if body.event.peak_sec == 0 {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                }
+                server_received.lock().unwrap().push(body.event.peak_sec);
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .await
+                    .ok();
+            }
+        });
+
+        let output = std::env::temp_dir().join(format!(
+            "chzzk-chat-live-events-{}.jsonl",
+            std::process::id()
+        ));
+        let notification = |peak_sec: i64| LiveEventNotification {
+            channel_id: "c1".to_string(),
+            live_id: 7,
+            phase: EventPhase::Peaked,
+            time: "2025-01-01T12:00:00+0900".to_string(),
+            first_message_time: "2025-01-01T12:00:00+0900".to_string(),
+            event: EventInterval {
+                start_sec: peak_sec,
+                end_sec: peak_sec,
+                peak_sec,
+                peak_z_score: 10.0,
+                peak_count: 40,
+            },
+        };
+
+        let publisher = EventPublisher::start(output.clone(), Some(url));
+        publisher.send(vec![notification(0), notification(1)]);
+        publisher.send(vec![notification(2)]);
+        publisher.send(vec![notification(3)]);
+        publisher.close().await;
+        server.abort();
+
+        // 첫 요청이 늦어도 보낸 순서대로 도착
+        assert_eq!(*received.lock().unwrap(), vec![0, 1, 2, 3]);
+        let written: Vec<i64> = fs::read_to_string(&output)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<LiveEventNotification>(line)
+                    .unwrap()
+                    .event
+                    .peak_sec
+            })
+            .collect();
+        assert_eq!(written, vec![0, 1, 2, 3]);
+
+        fs::remove_file(&output).ok();
+    }
+
+    #[tokio::test]
+    async fn test_event_publisher_drops_when_queue_is_full() {
+        let output = std::env::temp_dir().join(format!(
+            "chzzk-chat-live-events-full-{}.jsonl",
+            std::process::id()
+        ));
+        let notification = LiveEventNotification {
+            channel_id: "c1".to_string(),
+            live_id: 7,
+            phase: EventPhase::Started,
+            time: "2025-01-01T12:00:00+0900".to_string(),
+            first_message_time: "2025-01-01T12:00:00+0900".to_string(),
+            event: EventInterval {
+                start_sec: 0,
+                end_sec: 0,
+                peak_sec: 0,
+                peak_z_score: 10.0,
+                peak_count: 40,
+            },
+        };
+
+        // 단일 스레드 런타임이라 기다리기 전까지 작업이 대기열을 비우지 않음
+        let publisher = EventPublisher::start(output.clone(), None);
+        for _ in 0..PUBLISH_QUEUE_CAPACITY + 10 {
+            publisher.send(vec![notification.clone()]);
+        }
+        publisher.close().await;
+
+        let written = fs::read_to_string(&output).unwrap().lines().count();
+        assert_eq!(written, PUBLISH_QUEUE_CAPACITY);
+
+        fs::remove_file(&output).ok();
+    }
+}
@@ -0,0 +1,370 @@
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{Context, Result};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+use crate::utils::{log, SCRAPING_CHANNELS};
+
+/// 채널별 초당 메시지 수를 계산하는 구간 길이
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+/// 요청 헤더 최대 크기 (이보다 크면 응답하지 않고 닫음)
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// 실시간 스크래퍼 전역 지표
+pub static METRICS: Lazy<Metrics> = Lazy::new(Metrics::new);
+
+/// 채널별 메시지 수와 최근 구간의 초당 메시지 수
+struct ChannelMessages {
+    total: u64,
+    window_start: Instant,
+    window_count: u64,
+    rate: f64,
+}
+
+impl ChannelMessages {
+    fn new(now: Instant) -> Self {
+        Self {
+            total: 0,
+            window_start: now,
+            window_count: 0,
+            rate: 0.0,
+        }
+    }
+
+    /// 구간이 끝났으면 초당 메시지 수를 갱신하고 새 구간을 시작합니다.
+    fn roll(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.window_start);
+        if elapsed >= RATE_WINDOW {
+            self.rate = self.window_count as f64 / elapsed.as_secs_f64();
+            self.window_start = now;
+            self.window_count = 0;
+        }
+    }
+}
+
+/// Prometheus 텍스트 형식으로 내보내는 게이지/카운터 모음
+pub struct Metrics {
+    /// channel_id -> 메시지 수
+    messages: DashMap<String, ChannelMessages>,
+    /// channel_id -> 웹소켓 재연결 횟수
+    reconnects: DashMap<String, u64>,
+    /// (endpoint, status) -> API 오류 횟수
+    api_errors: DashMap<(String, String), u64>,
+    /// 마지막 라이브 목록 스캔에 걸린 시간 (f64 비트)
+    last_scan_duration: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            messages: DashMap::new(),
+            reconnects: DashMap::new(),
+            api_errors: DashMap::new(),
+            last_scan_duration: AtomicU64::new(0f64.to_bits()),
+        }
+    }
+
+    /// 채널에서 받은 실시간 채팅 한 건을 기록합니다.
+    pub fn record_message(&self, channel_id: &str) {
+        let now = Instant::now();
+        let mut entry = self
+            .messages
+            .entry(channel_id.to_string())
+            .or_insert_with(|| ChannelMessages::new(now));
+        entry.roll(now);
+        entry.total += 1;
+        entry.window_count += 1;
+    }
+
+    /// 웹소켓 재연결 한 번을 기록합니다.
+    pub fn record_reconnect(&self, channel_id: &str) {
+        *self.reconnects.entry(channel_id.to_string()).or_insert(0) += 1;
+    }
+
+    /// API 요청 실패 한 번을 기록합니다. `status`는 HTTP 상태 코드 또는 오류 종류입니다.
+    pub fn record_api_error(&self, url: &str, status: &str) {
+        *self
+            .api_errors
+            .entry((endpoint_label(url), status.to_string()))
+            .or_insert(0) += 1;
+    }
+
+    /// 마지막 라이브 목록 스캔 시간을 기록합니다.
+    pub fn set_last_scan_duration(&self, duration: Duration) {
+        self.last_scan_duration
+            .store(duration.as_secs_f64().to_bits(), Ordering::Relaxed);
+    }
+
+    /// 세션이 끝난 채널의 채널별 지표를 지웁니다.
+    pub fn remove_channel(&self, channel_id: &str) {
+        self.messages.remove(channel_id);
+        self.reconnects.remove(channel_id);
+    }
+
+    /// Prometheus 텍스트 형식(0.0.4)으로 지표를 출력합니다.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        write_header(
+            &mut out,
+            "chzzk_active_sessions",
+            "gauge",
+            "Number of channels being scraped.",
+        );
+        writeln!(out, "chzzk_active_sessions {}", SCRAPING_CHANNELS.len()).ok();
+
+        let now = Instant::now();
+        let mut messages: Vec<(String, u64, f64)> = self
+            .messages
+            .iter_mut()
+            .map(|mut entry| {
+                entry.roll(now);
+                (entry.key().clone(), entry.total, entry.rate)
+            })
+            .collect();
+        messages.sort_by(|a, b| a.0.cmp(&b.0));
+
+        write_header(
+            &mut out,
+            "chzzk_chat_messages_total",
+            "counter",
+            "Live chat messages received per channel.",
+        );
+        for (channel_id, total, _) in &messages {
+            writeln!(
+                out,
+                "chzzk_chat_messages_total{{channel_id=\"{}\"}} {}",
+                escape(channel_id),
+                total
+            )
+            .ok();
+        }
+
+        write_header(
+            &mut out,
+            "chzzk_chat_messages_per_second",
+            "gauge",
+            "Live chat messages per second per channel.",
+        );
+        for (channel_id, _, rate) in &messages {
+            writeln!(
+                out,
+                "chzzk_chat_messages_per_second{{channel_id=\"{}\"}} {}",
+                escape(channel_id),
+                rate
+            )
+            .ok();
+        }
+
+        let mut reconnects: Vec<(String, u64)> = self
+            .reconnects
+            .iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect();
+        reconnects.sort();
+
+        write_header(
+            &mut out,
+            "chzzk_ws_reconnects_total",
+            "counter",
+            "Chat websocket reconnects per channel.",
+        );
+        for (channel_id, count) in &reconnects {
+            writeln!(
+                out,
+                "chzzk_ws_reconnects_total{{channel_id=\"{}\"}} {}",
+                escape(channel_id),
+                count
+            )
+            .ok();
+        }
+
+        let mut api_errors: Vec<((String, String), u64)> = self
+            .api_errors
+            .iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect();
+        api_errors.sort();
+
+        write_header(
+            &mut out,
+            "chzzk_api_errors_total",
+            "counter",
+            "Failed API requests by endpoint and status.",
+        );
+        for ((endpoint, status), count) in &api_errors {
+            writeln!(
+                out,
+                "chzzk_api_errors_total{{endpoint=\"{}\",status=\"{}\"}} {}",
+                escape(endpoint),
+                escape(status),
+                count
+            )
+            .ok();
+        }
+
+        write_header(
+            &mut out,
+            "chzzk_last_scan_duration_seconds",
+            "gauge",
+            "Duration of the last live list scan.",
+        );
+        writeln!(
+            out,
+            "chzzk_last_scan_duration_seconds {}",
+            f64::from_bits(self.last_scan_duration.load(Ordering::Relaxed))
+        )
+        .ok();
+
+        out
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 요청 URL에서 쿼리를 빼고, 채널 ID나 비디오 번호 같은 경로 조각을 `{id}`로 바꿔 endpoint 라벨을 만듭니다.
+pub fn endpoint_label(url: &str) -> String {
+    let path = url::Url::parse(url)
+        .map(|url| url.path().to_string())
+        .unwrap_or_else(|_| url.split('?').next().unwrap_or(url).to_string());
+
+    path.split('/')
+        .map(|segment| {
+            let is_number = !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit());
+            let is_hash = segment.len() >= 16 && segment.chars().all(|c| c.is_ascii_hexdigit());
+            if is_number || is_hash {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 지표의 `# HELP`, `# TYPE` 줄을 씁니다.
+fn write_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(out, "# HELP {} {}", name, help).ok();
+    writeln!(out, "# TYPE {} {}", name, kind).ok();
+}
+
+/// 라벨 값의 `\`, `"`, 줄바꿈을 이스케이프합니다.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// `/metrics` HTTP 엔드포인트를 열 주소에 바인딩합니다.
+pub async fn bind_metrics<A: ToSocketAddrs>(addr: A) -> Result<TcpListener> {
+    TcpListener::bind(addr)
+        .await
+        .context("Failed to bind metrics endpoint")
+}
+
+/// 연결을 받을 때마다 `/metrics` 요청에 전역 지표로 응답합니다.
+pub async fn serve_metrics(listener: TcpListener) {
+    while let Ok((stream, _)) = listener.accept().await {
+        tokio::spawn(async move {
+            if let Err(e) = respond(stream).await {
+                log(format!("Metrics request failed: {:?}", e));
+            }
+        });
+    }
+}
+
+async fn respond(mut stream: TcpStream) -> Result<()> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 || buf.len() + n > MAX_REQUEST_BYTES {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&buf);
+    let mut request_line = request.lines().next().unwrap_or("").split_whitespace();
+    let method = request_line.next().unwrap_or("");
+    let target = request_line.next().unwrap_or("/");
+    let path = target.split('?').next().unwrap_or(target);
+
+    let response = if method == "GET" && path == "/metrics" {
+        let body = METRICS.render();
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    } else {
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+    };
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_metrics() {
+        let metrics = Metrics::new();
+        metrics.record_message("ch1");
+        metrics.record_message("ch1");
+        metrics.record_reconnect("ch1");
+        metrics.record_api_error(
+            "https://api.chzzk.naver.com/service/v1/channels/0123456789abcdef0123456789abcdef?x=1",
+            "503",
+        );
+        metrics.set_last_scan_duration(Duration::from_millis(1500));
+
+        let text = metrics.render();
+        assert!(text.contains("chzzk_chat_messages_total{channel_id=\"ch1\"} 2"));
+        assert!(text.contains("chzzk_ws_reconnects_total{channel_id=\"ch1\"} 1"));
+        assert!(text.contains(
+            "chzzk_api_errors_total{endpoint=\"/service/v1/channels/{id}\",status=\"503\"} 1"
+        ));
+        assert!(text.contains("chzzk_last_scan_duration_seconds 1.5"));
+
+        metrics.remove_channel("ch1");
+        assert!(!metrics.render().contains("channel_id=\"ch1\""));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let listener = bind_metrics("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(serve_metrics(listener));
+
+        let resp = reqwest::get(format!("http://{}/metrics", addr))
+            .await
+            .unwrap();
+        assert!(resp.status().is_success());
+        assert!(resp
+            .text()
+            .await
+            .unwrap()
+            .contains("# TYPE chzzk_active_sessions gauge"));
+
+        let resp = reqwest::get(format!("http://{}/other", addr))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+
+        server.abort();
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use color_eyre::eyre::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::api::recorder::kst_from_millis;
+use crate::data::utils::parse_replay_time;
+
+/// A 채널의 마지막 채팅 후 B 채널 첫 채팅까지 이동으로 보는 최대 간격 (초)
+pub const DEFAULT_MIGRATION_WINDOW_SECS: i64 = 600;
+/// 이동 횟수를 묶는 시간 구간 길이 (초)
+pub const DEFAULT_MIGRATION_BUCKET_SECS: i64 = 300;
+/// A 채널 마지막 채팅 후 이 시간 동안 A 채널에서 다시 채팅하지 않아야 이동으로 확정 (초)
+pub const DEFAULT_MIGRATION_MIN_INACTIVE_SECS: i64 = 120;
+
+/// 실시간 채팅에서 집계하는 시청자 이동 추적기
+pub static MIGRATION_TRACKER: Lazy<Mutex<MigrationTracker>> = Lazy::new(|| {
+    Mutex::new(MigrationTracker::new(
+        DEFAULT_MIGRATION_WINDOW_SECS,
+        DEFAULT_MIGRATION_BUCKET_SECS,
+        DEFAULT_MIGRATION_MIN_INACTIVE_SECS,
+    ))
+});
+
+/// 시간 구간별 A→B 이동 횟수
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationFlow {
+    /// 구간 시작 시간 (ISO 8601 형식)
+    pub start: String,
+    pub from: String,
+    pub to: String,
+    pub count: u64,
+}
+
+/// 시청자 이동 JSON 데이터
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MigrationExport {
+    #[serde(rename = "windowSeconds")]
+    pub window_secs: i64,
+    #[serde(rename = "bucketSeconds")]
+    pub bucket_secs: i64,
+    #[serde(rename = "minInactiveSeconds", default)]
+    pub min_inactive_secs: i64,
+    pub flows: Vec<MigrationFlow>,
+}
+
+/// 확정을 기다리는 이동 후보
+struct PendingMove {
+    from: String,
+    to: String,
+    /// from 채널 마지막 채팅 시간(ms)
+    left_at: i64,
+    /// to 채널 첫 채팅이 속한 구간 시작 시간(ms)
+    bucket: i64,
+}
+
+/// 사용자가 A 채널에서 채팅을 멈추고 `window` 안에 B 채널에서 채팅을 시작하면
+/// A→B 이동 후보로 두고, A 채널 마지막 채팅 후 `min_inactive` 동안 A 채널에서 다시
+/// 채팅하지 않으면 이동으로 확정해 `bucket` 길이의 시간 구간별로 집계합니다.
+/// 두 채널에서 번갈아 채팅하는 사용자는 이동으로 세지 않습니다.
+pub struct MigrationTracker {
+    window_millis: i64,
+    bucket_millis: i64,
+    min_inactive_millis: i64,
+    /// user_id -> (마지막으로 채팅한 채널, 시간(ms))
+    last_seen: HashMap<String, (String, i64)>,
+    /// user_id -> 확정을 기다리는 이동 후보
+    pending: HashMap<String, Vec<PendingMove>>,
+    /// (구간 시작 시간(ms), from, to) -> 이동 횟수
+    flows: BTreeMap<(i64, String, String), u64>,
+}
+
+impl MigrationTracker {
+    pub fn new(window_secs: i64, bucket_secs: i64, min_inactive_secs: i64) -> Self {
+        Self {
+            window_millis: window_secs.max(1) * 1000,
+            bucket_millis: bucket_secs.max(1) * 1000,
+            min_inactive_millis: min_inactive_secs.max(0) * 1000,
+            last_seen: HashMap::new(),
+            pending: HashMap::new(),
+            flows: BTreeMap::new(),
+        }
+    }
+
+    /// 채팅 한 건을 기록합니다.
+    pub fn record(&mut self, channel_id: &str, user_id: &str, msg_time: i64) {
+        let prev = self.last_seen.get(user_id).cloned();
+
+        // 이미 기록한 시간보다 이전 채팅(재연결 등으로 늦게 받은 채팅)은 무시
+        if let Some((_, prev_time)) = &prev {
+            if msg_time < *prev_time {
+                return;
+            }
+        }
+
+        self.settle(user_id, msg_time);
+        // 비활성 시간이 지나기 전에 떠난 채널에서 다시 채팅하면 이동이 아님
+        if let Some(moves) = self.pending.get_mut(user_id) {
+            moves.retain(|m| m.from != channel_id);
+        }
+
+        if let Some((prev_channel, prev_time)) = prev {
+            if prev_channel != channel_id && msg_time - prev_time <= self.window_millis {
+                self.pending
+                    .entry(user_id.to_string())
+                    .or_default()
+                    .push(PendingMove {
+                        from: prev_channel,
+                        to: channel_id.to_string(),
+                        left_at: prev_time,
+                        bucket: msg_time - msg_time.rem_euclid(self.bucket_millis),
+                    });
+            }
+        }
+
+        self.last_seen
+            .insert(user_id.to_string(), (channel_id.to_string(), msg_time));
+    }
+
+    /// `now`(ms) 기준으로 비활성 시간이 지난 사용자의 이동 후보를 확정합니다.
+    fn settle(&mut self, user_id: &str, now: i64) {
+        let Some(moves) = self.pending.get_mut(user_id) else {
+            return;
+        };
+        let min_inactive = self.min_inactive_millis;
+        let flows = &mut self.flows;
+        moves.retain(|m| {
+            if now - m.left_at < min_inactive {
+                return true;
+            }
+            *flows
+                .entry((m.bucket, m.from.clone(), m.to.clone()))
+                .or_insert(0) += 1;
+            false
+        });
+        if moves.is_empty() {
+            self.pending.remove(user_id);
+        }
+    }
+
+    /// `now`(ms) 기준으로 비활성 시간이 지난 이동 후보를 확정하고,
+    /// `window`보다 오래된 사용자 기록을 지웁니다.
+    pub fn prune(&mut self, now: i64) {
+        let users: Vec<String> = self.pending.keys().cloned().collect();
+        for user_id in users {
+            self.settle(&user_id, now);
+        }
+
+        let window = self.window_millis;
+        self.last_seen.retain(|_, (_, time)| now - *time <= window);
+    }
+
+    /// 집계한 이동 횟수를 구간 시작 시간, from, to 순으로 정렬해 반환합니다.
+    pub fn export(&self) -> MigrationExport {
+        MigrationExport {
+            window_secs: self.window_millis / 1000,
+            bucket_secs: self.bucket_millis / 1000,
+            min_inactive_secs: self.min_inactive_millis / 1000,
+            flows: self
+                .flows
+                .iter()
+                .filter_map(|((bucket, from, to), count)| {
+                    Some(MigrationFlow {
+                        start: kst_from_millis(*bucket)?
+                            .format("%Y-%m-%dT%H:%M:%S%z")
+                            .to_string(),
+                        from: from.clone(),
+                        to: to.clone(),
+                        count: *count,
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// 이전에 내보낸 JSON의 이동 횟수를 이어서 집계합니다.
+    pub fn merge_export(&mut self, export: &MigrationExport) {
+        for flow in &export.flows {
+            let Ok(start) = parse_replay_time(&flow.start) else {
+                continue;
+            };
+            let start = start.timestamp_millis();
+            let bucket = start - start.rem_euclid(self.bucket_millis);
+            *self
+                .flows
+                .entry((bucket, flow.from.clone(), flow.to.clone()))
+                .or_insert(0) += flow.count;
+        }
+    }
+}
+
+/// 전역 추적기를 주어진 설정으로 초기화하고, `path`에 이전 결과가 있으면 이어서 집계합니다.
+pub fn configure<P: AsRef<Path>>(
+    window_secs: i64,
+    bucket_secs: i64,
+    min_inactive_secs: i64,
+    path: P,
+) -> Result<()> {
+    let mut tracker = MigrationTracker::new(window_secs, bucket_secs, min_inactive_secs);
+
+    let path = path.as_ref();
+    if path.exists() {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read migration file: {:?}", path))?;
+        let export: MigrationExport = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse migration file: {:?}", path))?;
+        tracker.merge_export(&export);
+    }
+
+    *MIGRATION_TRACKER.lock().unwrap() = tracker;
+    Ok(())
+}
+
+/// 실시간 채팅 한 건을 전역 추적기에 기록합니다.
+pub fn record_chat(channel_id: &str, user_id: &str, msg_time: i64) {
+    MIGRATION_TRACKER
+        .lock()
+        .unwrap()
+        .record(channel_id, user_id, msg_time);
+}
+
+/// 비활성 시간이 지난 이동 후보를 확정하고 전역 추적기의 집계 결과를 JSON 파일로 저장합니다.
+/// 오래된 사용자 기록은 정리합니다.
+pub fn save_migrations<P: AsRef<Path>>(path: P, now: i64) -> Result<usize> {
+    let export = {
+        let mut tracker = MIGRATION_TRACKER.lock().unwrap();
+        tracker.prune(now);
+        tracker.export()
+    };
+
+    let path = path.as_ref();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create migration directory: {:?}", dir))?;
+    }
+    let json_string =
+        serde_json::to_string(&export).context("Failed to serialize migrations to JSON")?;
+    fs::write(path, json_string)
+        .with_context(|| format!("Failed to write JSON file: {:?}", path))?;
+
+    Ok(export.flows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow_tuples(tracker: &MigrationTracker) -> Vec<(String, String, String, u64)> {
+        tracker
+            .export()
+            .flows
+            .into_iter()
+            .map(|f| (f.start, f.from, f.to, f.count))
+            .collect()
+    }
+
+    #[test]
+    fn test_migration_flows_per_bucket() {
+        let mut tracker = MigrationTracker::new(600, 300, 120);
+        let base = 1_735_700_400_000; // 2025-01-01T12:00:00+0900
+
+        tracker.record("A", "u1", base);
+        tracker.record("B", "u1", base + 60_000);
+        // 같은 채널 채팅은 이동이 아님
+        tracker.record("B", "u1", base + 90_000);
+        tracker.record("A", "u2", base + 10_000);
+        tracker.record("B", "u2", base + 120_000);
+        // 간격이 window보다 길면 이동이 아님
+        tracker.record("A", "u3", base);
+        tracker.record("B", "u3", base + 601_000);
+        // u1의 A→B는 A 채널 비활성 시간이 지나 확정되고, 다음 구간의 B→A 이동 후보가 생김
+        tracker.record("A", "u1", base + 310_000);
+        assert_eq!(
+            flow_tuples(&tracker),
+            vec![(
+                "2025-01-01T12:00:00+0900".to_string(),
+                "A".to_string(),
+                "B".to_string(),
+                1
+            )]
+        );
+
+        // 나머지 후보는 비활성 시간이 지난 뒤 정리할 때 확정
+        tracker.prune(base + 430_000);
+        assert_eq!(
+            flow_tuples(&tracker),
+            vec![
+                (
+                    "2025-01-01T12:00:00+0900".to_string(),
+                    "A".to_string(),
+                    "B".to_string(),
+                    2
+                ),
+                (
+                    "2025-01-01T12:05:00+0900".to_string(),
+                    "B".to_string(),
+                    "A".to_string(),
+                    1
+                ),
+            ]
+        );
+
+        // 저장했던 결과를 이어서 집계
+        let mut restored = MigrationTracker::new(600, 300, 120);
+        restored.merge_export(&tracker.export());
+        assert_eq!(restored.export().flows, tracker.export().flows);
+    }
+
+    #[test]
+    fn test_user_active_in_both_channels_is_not_migration() {
+        let mut tracker = MigrationTracker::new(600, 300, 120);
+        let base = 1_735_700_400_000; // 2025-01-01T12:00:00+0900
+
+        // 10분 동안 두 채널에서 30초마다 번갈아 채팅
+        for i in 0..20 {
+            let channel = if i % 2 == 0 { "A" } else { "B" };
+            tracker.record(channel, "u1", base + i * 30_000);
+        }
+        tracker.prune(base + 600_000);
+        assert!(tracker.export().flows.is_empty());
+
+        // A 채널을 떠나 B 채널에만 남으면 이동으로 확정
+        tracker.record("B", "u1", base + 630_000);
+        tracker.prune(base + 690_000);
+        assert_eq!(
+            flow_tuples(&tracker),
+            vec![(
+                "2025-01-01T12:05:00+0900".to_string(),
+                "A".to_string(),
+                "B".to_string(),
+                1
+            )]
+        );
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures::{SinkExt, StreamExt};
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::api::endpoints::{set_endpoints, Endpoints};
+
+/// 프로세스 전역 접속 주소와 `LIVE_CHAT_LOG_DIR`을 바꾸는 테스트를 하나씩 실행하기 위한 lock
+static GLOBAL_ENV: Lazy<AsyncMutex<()>> = Lazy::new(|| AsyncMutex::new(()));
+
+/// 정상 응답 전에 먼저 돌려줄 (HTTP 상태, Retry-After 초)
+type MockFailure = (u16, Option<u64>);
+
+/// mock 서버 공유 상태
+struct MockState {
+    /// 경로(`/service/...`, 쿼리 포함 가능)별 응답 JSON
+    routes: HashMap<String, Value>,
+    /// 채팅 웹소켓이 INIT 이후 보낼 메시지들
+    chat_script: Vec<Value>,
+    /// 최근 채팅 요청(5101)에 돌려줄 `messageList`
+    recent_chat: Vec<Value>,
+    /// 채널 상세 응답의 `openLive` 값 (채팅 스크립트를 다 보내면 false가 됨)
+    live_open: AtomicBool,
+    /// 웹소켓으로 받은 메시지들
+    received: Mutex<Vec<Value>>,
+    /// 경로별로 정상 응답 전에 먼저 돌려줄 (HTTP 상태, Retry-After) 목록
+    failures: Mutex<HashMap<String, VecDeque<MockFailure>>>,
+}
+
+/// 네트워크 없이 테스트하기 위해 로컬 포트에서 동작하는 치지직 mock 서버
+///
+/// HTTP는 경로별로 미리 정해둔 JSON을 돌려주고,
+/// 채팅 웹소켓은 INIT 메시지를 받은 뒤 정해둔 메시지들을 보내고,
+/// 클라이언트가 모두 처리하면 라이브 종료로 표시한 뒤 연결을 닫습니다.
+pub struct MockChzzk {
+    http_addr: SocketAddr,
+    ws_addr: SocketAddr,
+    state: Arc<MockState>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl MockChzzk {
+    /// HTTP와 웹소켓 mock 서버를 띄웁니다.
+    pub async fn start(
+        routes: Vec<(String, Value)>,
+        chat_script: Vec<Value>,
+        recent_chat: Vec<Value>,
+    ) -> Self {
+        let state = Arc::new(MockState {
+            routes: routes.into_iter().collect(),
+            chat_script,
+            recent_chat,
+            live_open: AtomicBool::new(true),
+            received: Mutex::new(Vec::new()),
+            failures: Mutex::new(HashMap::new()),
+        });
+
+        let http_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let ws_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let http_addr = http_listener.local_addr().unwrap();
+        let ws_addr = ws_listener.local_addr().unwrap();
+
+        let http_state = state.clone();
+        let http_task = tokio::spawn(async move {
+            while let Ok((stream, _)) = http_listener.accept().await {
+                tokio::spawn(serve_http(stream, http_state.clone()));
+            }
+        });
+
+        let ws_state = state.clone();
+        let ws_task = tokio::spawn(async move {
+            while let Ok((stream, _)) = ws_listener.accept().await {
+                tokio::spawn(serve_chat(stream, ws_state.clone()));
+            }
+        });
+
+        Self {
+            http_addr,
+            ws_addr,
+            state,
+            tasks: vec![http_task, ws_task],
+        }
+    }
+
+    /// 이 서버로 연결하는 접속 주소
+    pub fn endpoints(&self) -> Endpoints {
+        Endpoints {
+            api_base_url: format!("http://{}", self.http_addr),
+            chat_ws_url: format!("ws://{}/chat", self.ws_addr),
+        }
+    }
+
+    /// 전역 접속 주소를 이 서버로, `LIVE_CHAT_LOG_DIR`을 `log_dir`로 바꿉니다.
+    /// 다른 테스트와 겹치지 않도록 반환한 guard가 살아 있는 동안 lock을 잡고, drop 시 원래대로 되돌립니다.
+    pub async fn install_globals(&self, log_dir: &std::path::Path) -> GlobalEnvGuard {
+        let lock = GLOBAL_ENV.lock().await;
+        set_endpoints(self.endpoints());
+        std::env::set_var("LIVE_CHAT_LOG_DIR", log_dir);
+        GlobalEnvGuard { _lock: lock }
+    }
+
+    /// `path`에 대한 다음 요청들이 순서대로 주어진 (HTTP 상태, Retry-After 초)로 실패하게 합니다.
+    pub fn fail_next(&self, path: &str, failures: &[MockFailure]) {
+        self.state
+            .failures
+            .lock()
+            .unwrap()
+            .entry(path.to_string())
+            .or_default()
+            .extend(failures.iter().copied());
+    }
+
+    /// 웹소켓으로 받은 메시지들
+    pub fn received(&self) -> Vec<Value> {
+        self.state.received.lock().unwrap().clone()
+    }
+}
+
+/// `MockChzzk::install_globals`로 바꾼 전역 설정을 되돌리는 guard
+pub struct GlobalEnvGuard {
+    _lock: AsyncMutexGuard<'static, ()>,
+}
+
+impl Drop for GlobalEnvGuard {
+    fn drop(&mut self) {
+        std::env::remove_var("LIVE_CHAT_LOG_DIR");
+        set_endpoints(Endpoints::from_env());
+    }
+}
+
+impl Drop for MockChzzk {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+async fn serve_http(mut stream: TcpStream, state: Arc<MockState>) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+    }
+
+    let request = String::from_utf8_lossy(&buf);
+    let target = request
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .unwrap_or("/");
+    let path = target.split('?').next().unwrap_or(target);
+
+    let failure = {
+        let mut failures = state.failures.lock().unwrap();
+        [target, path]
+            .iter()
+            .find_map(|key| failures.get_mut(*key).and_then(VecDeque::pop_front))
+    };
+    if let Some((status, retry_after)) = failure {
+        let retry_after = retry_after
+            .map(|secs| format!("Retry-After: {}\r\n", secs))
+            .unwrap_or_default();
+        let response = format!(
+            "HTTP/1.1 {} Mock Failure\r\n{}Content-Length: 0\r\nConnection: close\r\n\r\n",
+            status, retry_after
+        );
+        stream.write_all(response.as_bytes()).await.ok();
+        stream.shutdown().await.ok();
+        return;
+    }
+
+    let body = state
+        .routes
+        .get(target)
+        .or_else(|| state.routes.get(path))
+        .cloned()
+        .map(|mut body| {
+            if let Some(open_live) = body.pointer_mut("/content/openLive") {
+                *open_live = json!(state.live_open.load(Ordering::SeqCst));
+            }
+            body
+        });
+
+    let response = match body {
+        Some(body) => {
+            let body = body.to_string();
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+        }
+        None => {
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+        }
+    };
+
+    stream.write_all(response.as_bytes()).await.ok();
+    stream.shutdown().await.ok();
+}
+
+async fn serve_chat(stream: TcpStream, state: Arc<MockState>) {
+    let Ok(mut ws_stream) = tokio_tungstenite::accept_async(stream).await else {
+        return;
+    };
+
+    // INIT 메시지를 받을 때까지 대기
+    while let Some(Ok(msg)) = ws_stream.next().await {
+        if let Message::Text(text) = msg {
+            let value: Value = serde_json::from_str(&text).unwrap_or(Value::Null);
+            let is_init = value["cmd"] == 100;
+            state.received.lock().unwrap().push(value);
+            if is_init {
+                break;
+            }
+        }
+    }
+
+    let connected = json!({ "ver": "3", "cmd": 10100, "bdy": { "sid": "mock-sid" } });
+    if ws_stream
+        .send(Message::Text(connected.to_string()))
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    for msg in &state.chat_script {
+        if ws_stream
+            .send(Message::Text(msg.to_string()))
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+
+    // 마지막으로 PING을 보내고, 보낸 PING 수만큼 PONG을 받으면
+    // 클라이언트가 앞의 메시지를 모두 처리한 것.
+    // 클라이언트의 첫 PING(연결 직후 라이브 상태 확인)도 받은 뒤에 종료해야
+    // 상태 확인이 스크립트 처리보다 늦게 실행되어 메시지를 놓치는 일이 없음
+    let ping = json!({ "ver": "3", "cmd": 0 }).to_string();
+    if ws_stream.send(Message::Text(ping)).await.is_err() {
+        return;
+    }
+    let mut pending_pongs = 1 + state
+        .chat_script
+        .iter()
+        .filter(|msg| msg["cmd"] == 0)
+        .count();
+    let mut client_pinged = false;
+    while let Some(Ok(msg)) = ws_stream.next().await {
+        if let Message::Text(text) = msg {
+            let value: Value = serde_json::from_str(&text).unwrap_or(Value::Null);
+            if value["cmd"] == 10000 {
+                pending_pongs -= 1;
+            }
+            if value["cmd"] == 0 {
+                client_pinged = true;
+            }
+            if value["cmd"] == 5101 {
+                let recent = json!({
+                    "ver": "3",
+                    "cmd": 15101,
+                    "bdy": { "messageList": state.recent_chat },
+                });
+                ws_stream.send(Message::Text(recent.to_string())).await.ok();
+            }
+            state.received.lock().unwrap().push(value);
+            if pending_pongs == 0 && client_pinged {
+                break;
+            }
+        }
+    }
+
+    // 스크립트를 다 처리했으면 라이브 종료로 표시하고 연결을 닫음
+    state.live_open.store(false, Ordering::SeqCst);
+    ws_stream.close(None).await.ok();
+    while let Some(Ok(_)) = ws_stream.next().await {}
+}
+
+/// `/service/v1/lives` 응답 (다음 페이지 없음)
+pub fn lives_response(lives: &[(&str, u64)]) -> Value {
+    let data: Vec<Value> = lives
+        .iter()
+        .map(|(channel_id, users)| {
+            json!({
+                "concurrentUserCount": users,
+                "adult": false,
+                "chatChannelId": null,
+                "channel": { "channelId": channel_id },
+            })
+        })
+        .collect();
+
+    json!({ "content": { "data": data, "page": { "next": null } } })
+}
+
+/// `/service/v1/channels/{channelId}` 응답
+pub fn channel_detail_response(channel_id: &str, follower_count: u64) -> Value {
+    json!({
+        "content": {
+            "channelId": channel_id,
+            "channelName": "mock",
+            "followerCount": follower_count,
+            "openLive": true,
+        }
+    })
+}
+
+/// `/service/v3/channels/{channelId}/live-detail` 응답
+pub fn live_detail_response(live_id: u64, chat_channel_id: &str) -> Value {
+    json!({ "content": { "liveId": live_id, "chatChannelId": chat_channel_id } })
+}
+
+/// 채팅 웹소켓 93101(채팅) 메시지
+pub fn chat_message(chats: &[(i64, &str, &str, &str)]) -> Value {
+    let bdy: Vec<Value> = chats
+        .iter()
+        .map(|(msg_time, nickname, msg, uid)| {
+            json!({
+                "uid": uid,
+                "msg": msg,
+                "msgTime": msg_time,
+                "profile": json!({ "nickname": nickname }).to_string(),
+            })
+        })
+        .collect();
+
+    json!({ "ver": "3", "cmd": 93101, "bdy": bdy })
+}
+
+/// 최근 채팅 응답(15101)의 `messageList` 항목
+pub fn recent_chat_entry(message_time: i64, nickname: &str, content: &str, user_id: &str) -> Value {
+    json!({
+        "userId": user_id,
+        "content": content,
+        "messageTime": message_time,
+        "messageTypeCode": 1,
+        "profile": json!({ "nickname": nickname }).to_string(),
+        "extras": null,
+    })
+}
+
+/// `/service/v1/videos/{videoNo}/chats` 응답 (`nextPlayerMessageTime`이 없으면 마지막 페이지)
+pub fn video_chats_response(chats: &[(i64, &str, &str)], next: Option<i64>) -> Value {
+    let video_chats: Vec<Value> = chats
+        .iter()
+        .map(|(message_time, user_id, content)| {
+            json!({
+                "messageTime": message_time,
+                "playerMessageTime": 0,
+                "userIdHash": user_id,
+                "content": content,
+                "profile": json!({ "nickname": user_id }).to_string(),
+            })
+        })
+        .collect();
+
+    json!({ "content": { "nextPlayerMessageTime": next, "videoChats": video_chats } })
+}
@@ -1,7 +1,16 @@
 pub mod client;
+pub mod endpoints;
+pub mod live_events;
+pub mod metrics;
+pub mod migration;
+#[cfg(test)]
+pub mod mock_server;
 pub mod models;
+pub mod protocol;
+pub mod recorder;
 pub mod scanner;
+pub mod viewer_sampler;
 pub mod websocket;
 
 // Re-export commonly used items
-pub use scanner::scan_channels;
+pub use scanner::{min_live_user_from_env, scan_channels};
@@ -11,6 +11,12 @@ pub struct ChannelInfo {
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Live {
+    #[serde(rename = "liveId")]
+    pub live_id: Option<u64>,
+    #[serde(rename = "liveTitle")]
+    pub live_title: Option<String>,
+    #[serde(rename = "liveCategoryValue")]
+    pub live_category_value: Option<String>,
     #[serde(rename = "concurrentUserCount")]
     pub concurrent_user_count: u64,
     pub adult: bool,
@@ -52,6 +58,10 @@ pub struct ChannelDetail {
     pub follower_count: Option<u64>,
     #[serde(rename = "openLive")]
     pub open_live: Option<bool>,
+    #[serde(rename = "channelName")]
+    pub channel_name: Option<String>,
+    #[serde(rename = "channelImageUrl")]
+    pub channel_image_url: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -61,6 +71,8 @@ pub struct ChannelDetailResponse {
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct LiveDetail {
+    #[serde(rename = "liveId")]
+    pub live_id: Option<u64>,
     #[serde(rename = "chatChannelId")]
     pub chat_channel_id: Option<String>,
 }
@@ -70,10 +82,74 @@ pub struct LiveDetailResponse {
     pub content: Option<LiveDetail>,
 }
 
+/// 다시보기 채팅 한 건
+#[derive(Debug, Deserialize, Clone)]
+pub struct VideoChat {
+    /// 메시지 작성 시간 (밀리초 유닉스 타임스탬프)
+    #[serde(rename = "messageTime")]
+    pub message_time: i64,
+    /// 다시보기 재생 기준 메시지 시간 (밀리초)
+    #[serde(rename = "playerMessageTime")]
+    #[allow(dead_code)]
+    pub player_message_time: i64,
+    #[serde(rename = "userIdHash")]
+    pub user_id_hash: String,
+    pub content: String,
+    /// 닉네임 등이 담긴 JSON 문자열
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VideoChatsContent {
+    #[serde(rename = "nextPlayerMessageTime")]
+    pub next_player_message_time: Option<i64>,
+    #[serde(rename = "videoChats", default)]
+    pub video_chats: Vec<VideoChat>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VideoChatsResponse {
+    pub content: Option<VideoChatsContent>,
+}
+
+/// 채널 다시보기 목록의 비디오 한 건
+#[derive(Debug, Deserialize, Clone)]
+pub struct ChannelVideo {
+    #[serde(rename = "videoNo")]
+    pub video_no: u64,
+    #[serde(rename = "videoTitle")]
+    pub video_title: String,
+    /// 공개 시간 (KST, `%Y-%m-%d %H:%M:%S`), 다시보기는 방송 종료 시간
+    #[serde(rename = "publishDate")]
+    pub publish_date: String,
+    /// 영상 길이 (초)
+    pub duration: Option<i64>,
+    #[serde(rename = "thumbnailImageUrl")]
+    pub thumbnail_image_url: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(rename = "videoCategoryValue")]
+    pub video_category_value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChannelVideosContent {
+    #[serde(rename = "totalPages", default)]
+    pub total_pages: u32,
+    #[serde(default)]
+    pub data: Vec<ChannelVideo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChannelVideosResponse {
+    pub content: Option<ChannelVideosContent>,
+}
+
 /// 스크래핑에 필요한 최소 정보만 모아놓은 구조체
 #[derive(Debug, Clone)]
 pub struct LiveReady {
     pub channel_id: String,
+    pub live_id: u64,
     pub chat_channel_id: String,
     #[allow(dead_code)]
     pub follower_count: u64,
@@ -108,3 +184,21 @@ pub struct InitMessage {
     pub tid: i32,
     pub bdy: InitBody,
 }
+
+#[derive(Debug, Serialize)]
+pub struct RecentChatBody {
+    #[serde(rename = "recentMessageCount")]
+    pub recent_message_count: u32,
+}
+
+/// 최근 채팅 요청 (cmd 5101)
+#[derive(Debug, Serialize)]
+pub struct RecentChatRequest {
+    pub ver: String,
+    pub cmd: i32,
+    pub svcid: String,
+    pub cid: String,
+    pub sid: String,
+    pub tid: i32,
+    pub bdy: RecentChatBody,
+}
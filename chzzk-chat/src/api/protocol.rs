@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// ====== 채팅 웹소켓 명령 코드 ======
+
+pub const CMD_PING: i64 = 0;
+pub const CMD_PONG: i64 = 10000;
+pub const CMD_CONNECT: i64 = 100;
+pub const CMD_CONNECTED: i64 = 10100;
+pub const CMD_REQUEST_RECENT_CHAT: i64 = 5101;
+pub const CMD_RECENT_CHAT: i64 = 15101;
+pub const CMD_EVENT: i64 = 93006;
+pub const CMD_CHAT: i64 = 93101;
+pub const CMD_DONATION: i64 = 93102;
+pub const CMD_KICK: i64 = 94005;
+pub const CMD_BLOCK: i64 = 94006;
+pub const CMD_BLIND: i64 = 94008;
+pub const CMD_NOTICE: i64 = 94010;
+pub const CMD_PENALTY: i64 = 94015;
+
+/// 서버에서 받은 채팅 웹소켓 메시지
+#[derive(Debug, Clone)]
+pub enum ChatCommand {
+    /// 서버 ping (PONG으로 응답해야 함)
+    Ping,
+    /// ping에 대한 응답
+    Pong,
+    /// INIT(cmd 100)에 대한 응답 (최근 채팅 요청에 필요한 세션 ID 포함)
+    Connected { sid: Option<String> },
+    /// 일반 채팅 (93101)
+    Chat(Vec<ChatEntry>),
+    /// 후원/구독 (93102)
+    Donation(Vec<ChatEntry>),
+    /// 최근 채팅 요청(5101)에 대한 응답 (15101)
+    RecentChat(RecentChat),
+    /// 고정 공지 (94010), `None`이면 공지 해제
+    Notice(Option<Box<ChatEntry>>),
+    /// 메시지 가림/삭제 (94008)
+    Blind(BlindMessage),
+    /// 이벤트, 강퇴, 차단, 제재 등 시스템 메시지
+    System { cmd: i64, bdy: Value },
+    /// 알 수 없는 명령
+    Unknown { cmd: i64, bdy: Value },
+}
+
+/// 채팅 메시지 종류 (`msgTypeCode`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatType {
+    Text,
+    Image,
+    Sticker,
+    Video,
+    Rich,
+    Donation,
+    Subscription,
+    System,
+    Open,
+    Other(i64),
+}
+
+impl From<i64> for ChatType {
+    fn from(code: i64) -> Self {
+        match code {
+            1 => ChatType::Text,
+            2 => ChatType::Image,
+            3 => ChatType::Sticker,
+            4 => ChatType::Video,
+            5 => ChatType::Rich,
+            10 => ChatType::Donation,
+            11 => ChatType::Subscription,
+            30 => ChatType::System,
+            121 => ChatType::Open,
+            other => ChatType::Other(other),
+        }
+    }
+}
+
+/// 채팅/후원 메시지 한 건
+///
+/// 실시간 메시지(93101/93102)와 최근 채팅(15101)은 필드 이름이 달라서 alias로 둘 다 받습니다.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatEntry {
+    #[serde(rename = "uid", alias = "userId", default)]
+    pub user_id: String,
+    #[serde(rename = "msg", alias = "content", default)]
+    pub message: String,
+    /// 메시지 작성 시간 (밀리초 유닉스 타임스탬프)
+    #[serde(rename = "msgTime", alias = "messageTime", default)]
+    pub msg_time: i64,
+    #[serde(
+        rename = "msgTypeCode",
+        alias = "messageTypeCode",
+        default,
+        deserialize_with = "deserialize_chat_type"
+    )]
+    pub msg_type: Option<ChatType>,
+    /// `NORMAL`, `HIDDEN`, `BLIND` 등
+    #[allow(dead_code)]
+    #[serde(rename = "msgStatusType", alias = "messageStatusType", default)]
+    pub status: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_json_string")]
+    pub profile: Option<ChatProfile>,
+    #[serde(default, deserialize_with = "deserialize_json_string")]
+    pub extras: Option<ChatExtras>,
+}
+
+impl ChatEntry {
+    /// 프로필의 닉네임 (없으면 "Unknown")
+    pub fn nickname(&self) -> &str {
+        self.profile
+            .as_ref()
+            .and_then(|profile| profile.nickname.as_deref())
+            .unwrap_or("Unknown")
+    }
+
+    /// 후원 또는 구독 메시지인지 확인합니다.
+    pub fn is_donation(&self) -> bool {
+        matches!(
+            self.msg_type,
+            Some(ChatType::Donation) | Some(ChatType::Subscription)
+        )
+    }
+}
+
+/// 채팅 작성자 프로필 (`profile` JSON 문자열)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[allow(dead_code)]
+pub struct ChatProfile {
+    #[serde(rename = "userIdHash")]
+    pub user_id_hash: Option<String>,
+    pub nickname: Option<String>,
+    #[serde(rename = "profileImageUrl")]
+    pub profile_image_url: Option<String>,
+    /// `common_user`, `streamer`, `streaming_chat_manager` 등
+    #[serde(rename = "userRoleCode")]
+    pub user_role_code: Option<String>,
+    #[serde(rename = "verifiedMark", default)]
+    pub verified_mark: bool,
+}
+
+/// 메시지 부가 정보 (`extras` JSON 문자열)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[allow(dead_code)]
+pub struct ChatExtras {
+    /// 후원 금액 (치즈)
+    #[serde(rename = "payAmount")]
+    pub pay_amount: Option<u64>,
+    /// `CHAT`, `VIDEO`, `MISSION` 등
+    #[serde(rename = "donationType")]
+    pub donation_type: Option<String>,
+    #[serde(rename = "isAnonymous")]
+    pub is_anonymous: Option<bool>,
+    /// 구독 개월 수
+    pub month: Option<u32>,
+    #[serde(rename = "tierName")]
+    pub tier_name: Option<String>,
+    #[serde(rename = "tierNo")]
+    pub tier_no: Option<u32>,
+    /// 이모티콘 이름 → 이미지 URL
+    #[serde(default)]
+    pub emojis: HashMap<String, String>,
+    #[serde(rename = "osType")]
+    pub os_type: Option<String>,
+}
+
+/// 최근 채팅 응답 본문
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RecentChat {
+    #[serde(rename = "messageList", default)]
+    pub messages: Vec<ChatEntry>,
+    #[serde(rename = "userCount")]
+    #[allow(dead_code)]
+    pub user_count: Option<Value>,
+}
+
+/// 가려진 메시지 정보
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BlindMessage {
+    #[serde(rename = "messageTime", default)]
+    pub message_time: i64,
+    #[serde(rename = "userId")]
+    pub user_id: Option<String>,
+    #[serde(rename = "blindType")]
+    pub blind_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawFrame {
+    #[serde(default)]
+    cmd: i64,
+    #[serde(default)]
+    bdy: Value,
+}
+
+impl ChatCommand {
+    /// 웹소켓 텍스트 프레임을 해석합니다.
+    pub fn decode(text: &str) -> serde_json::Result<Self> {
+        let RawFrame { cmd, bdy } = serde_json::from_str(text)?;
+
+        let command = match cmd {
+            CMD_PING => ChatCommand::Ping,
+            CMD_PONG => ChatCommand::Pong,
+            CMD_CONNECTED => ChatCommand::Connected {
+                sid: bdy["sid"].as_str().map(str::to_string),
+            },
+            CMD_CHAT => ChatCommand::Chat(decode_entries(bdy)?),
+            CMD_DONATION => ChatCommand::Donation(decode_entries(bdy)?),
+            CMD_RECENT_CHAT => ChatCommand::RecentChat(serde_json::from_value(bdy)?),
+            CMD_NOTICE if bdy.is_null() => ChatCommand::Notice(None),
+            CMD_NOTICE => ChatCommand::Notice(Some(serde_json::from_value(bdy)?)),
+            CMD_BLIND => ChatCommand::Blind(serde_json::from_value(bdy)?),
+            CMD_EVENT | CMD_KICK | CMD_BLOCK | CMD_PENALTY => ChatCommand::System { cmd, bdy },
+            _ => ChatCommand::Unknown { cmd, bdy },
+        };
+
+        Ok(command)
+    }
+}
+
+/// 93101/93102 본문은 배열이지만 단일 객체로 오는 경우도 받습니다.
+fn decode_entries(bdy: Value) -> serde_json::Result<Vec<ChatEntry>> {
+    match bdy {
+        Value::Array(_) => serde_json::from_value(bdy),
+        Value::Null => Ok(Vec::new()),
+        other => Ok(vec![serde_json::from_value(other)?]),
+    }
+}
+
+fn deserialize_chat_type<'de, D>(deserializer: D) -> Result<Option<ChatType>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<i64>::deserialize(deserializer)?.map(ChatType::from))
+}
+
+/// `profile`/`extras`는 JSON 문자열로 내려오므로 한 번 더 파싱합니다.
+/// 객체로 오는 경우도 받고, 파싱에 실패하면 `None`으로 둡니다.
+fn deserialize_json_string<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value = Value::deserialize(deserializer)?;
+    let parsed = match value {
+        Value::String(s) => serde_json::from_str(&s).ok(),
+        Value::Null => None,
+        other => serde_json::from_value(other).ok(),
+    };
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_chat_and_donation() {
+        let chat = r#"{"ver":"3","cmd":93101,"bdy":[{"uid":"u1","msg":"안녕","msgTypeCode":1,"msgTime":1761296595000,"msgStatusType":"NORMAL","profile":"{\"nickname\":\"시청자\",\"userRoleCode\":\"common_user\"}","extras":"{\"emojis\":{},\"osType\":\"PC\"}"}]}"#;
+        let ChatCommand::Chat(entries) = ChatCommand::decode(chat).unwrap() else {
+            panic!("expected chat");
+        };
+        assert_eq!(entries[0].user_id, "u1");
+        assert_eq!(entries[0].nickname(), "시청자");
+        assert_eq!(entries[0].msg_type, Some(ChatType::Text));
+        assert!(!entries[0].is_donation());
+
+        let donation = r#"{"cmd":93102,"bdy":[{"uid":"u2","msg":"후원합니다","msgTypeCode":10,"msgTime":1761296596000,"profile":null,"extras":"{\"payAmount\":1000,\"donationType\":\"CHAT\",\"isAnonymous\":true}"}]}"#;
+        let ChatCommand::Donation(entries) = ChatCommand::decode(donation).unwrap() else {
+            panic!("expected donation");
+        };
+        let extras = entries[0].extras.as_ref().unwrap();
+        assert_eq!(extras.pay_amount, Some(1000));
+        assert_eq!(extras.donation_type.as_deref(), Some("CHAT"));
+        assert_eq!(entries[0].nickname(), "Unknown");
+        assert!(entries[0].is_donation());
+    }
+
+    #[test]
+    fn test_decode_recent_chat_and_blind() {
+        let recent = r#"{"cmd":15101,"bdy":{"messageList":[{"userId":"u1","content":"이전 채팅","messageTime":1761296590000,"messageTypeCode":1,"profile":"{\"nickname\":\"a\"}","extras":null}],"userCount":{"normal":3}}}"#;
+        let ChatCommand::RecentChat(recent) = ChatCommand::decode(recent).unwrap() else {
+            panic!("expected recent chat");
+        };
+        assert_eq!(recent.messages.len(), 1);
+        assert_eq!(recent.messages[0].message, "이전 채팅");
+        assert_eq!(recent.messages[0].msg_time, 1761296590000);
+
+        let blind = r#"{"cmd":94008,"bdy":{"messageTime":1761296590000,"userId":"u1","blindType":"CBOTBLIND"}}"#;
+        let ChatCommand::Blind(blind) = ChatCommand::decode(blind).unwrap() else {
+            panic!("expected blind");
+        };
+        assert_eq!(blind.user_id.as_deref(), Some("u1"));
+
+        assert!(matches!(
+            ChatCommand::decode(r#"{"cmd":0}"#).unwrap(),
+            ChatCommand::Ping
+        ));
+        assert!(matches!(
+            ChatCommand::decode(r#"{"cmd":94010,"bdy":null}"#).unwrap(),
+            ChatCommand::Notice(None)
+        ));
+    }
+}
@@ -0,0 +1,546 @@
+use std::collections::{HashSet, VecDeque};
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, FixedOffset, TimeZone};
+use color_eyre::eyre::{Context, Result};
+use serde::Serialize;
+
+use crate::api::protocol::{ChatEntry, ChatType};
+use crate::data::chat::loader::{format_chat_line, parse_chat_line};
+
+/// 중복 확인을 위해 기억하는 최근 채팅 수
+const RECENT_CHAT_KEYS: usize = 10_000;
+/// 기존 로그 파일에서 최근 채팅을 읽어올 때 읽는 최대 크기 (bytes)
+const RECENT_CHAT_TAIL_BYTES: u64 = 1024 * 1024;
+
+/// 실시간 채팅 로그 기본 저장 디렉토리
+const DEFAULT_LIVE_CHAT_LOG_DIR: &str = "../chat_logs_live";
+
+/// 실시간 채팅 로그 저장 디렉토리를 반환합니다.
+/// `LIVE_CHAT_LOG_DIR` 환경 변수로 변경할 수 있습니다.
+pub fn live_chat_log_dir() -> PathBuf {
+    env::var("LIVE_CHAT_LOG_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_LIVE_CHAT_LOG_DIR))
+}
+
+/// 밀리초 단위 유닉스 타임스탬프를 KST 시간으로 변환합니다.
+pub fn kst_from_millis(millis: i64) -> Option<DateTime<FixedOffset>> {
+    let kst_offset = FixedOffset::east_opt(9 * 3600)?;
+    kst_offset.timestamp_millis_opt(millis).single()
+}
+
+/// 채팅 `profile` JSON 문자열에서 닉네임을 꺼냅니다.
+pub fn nickname_from_profile(profile: Option<&str>) -> String {
+    profile
+        .and_then(|profile| serde_json::from_str::<serde_json::Value>(profile).ok())
+        .and_then(|profile| profile["nickname"].as_str().map(str::to_string))
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// 연결이 끊겨 채팅을 수집하지 못한 구간
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatGap {
+    /// 연결이 끊긴 시간 (KST)
+    pub start: String,
+    /// 다시 연결된 시간 (KST)
+    pub end: String,
+    /// 누락 구간 길이 (초)
+    pub seconds: i64,
+    /// 연결이 끊긴 사유
+    pub reason: String,
+}
+
+/// 후원/구독 기록
+#[derive(Debug, Clone, Serialize)]
+pub struct DonationRecord {
+    /// 메시지 시간 (KST)
+    pub time: String,
+    pub user_id: String,
+    pub nickname: String,
+    /// `donation` 또는 `subscription`
+    pub kind: String,
+    pub message: String,
+    /// 후원 금액 (치즈)
+    pub pay_amount: Option<u64>,
+    /// `CHAT`, `VIDEO`, `MISSION` 등
+    pub donation_type: Option<String>,
+    pub is_anonymous: Option<bool>,
+    /// 구독 개월 수
+    pub month: Option<u32>,
+    pub tier_name: Option<String>,
+}
+
+impl DonationRecord {
+    /// 후원/구독 메시지에서 기록을 만듭니다. 시간 정보가 없으면 `None`을 반환합니다.
+    pub fn from_entry(entry: &ChatEntry) -> Option<Self> {
+        let time = kst_from_millis(entry.msg_time)?;
+        let extras = entry.extras.clone().unwrap_or_default();
+        let kind = match entry.msg_type {
+            Some(ChatType::Subscription) => "subscription",
+            _ => "donation",
+        };
+
+        Some(Self {
+            time: time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            user_id: entry.user_id.clone(),
+            nickname: entry.nickname().to_string(),
+            kind: kind.to_string(),
+            message: entry.message.clone(),
+            pay_amount: extras.pay_amount,
+            donation_type: extras.donation_type,
+            is_anonymous: extras.is_anonymous,
+            month: extras.month,
+            tier_name: extras.tier_name,
+        })
+    }
+}
+
+/// 채팅 중복 확인 키: (user_id, 시간, 메시지)
+/// 실시간/최근 채팅은 밀리초 시간을 쓰고, 로그 파일에서 읽은 채팅은 파일에 초 단위까지만 있으므로 초 단위 시간을 씁니다.
+type ChatKey = (String, i64, String);
+
+fn chat_key(time: i64, message: &str, user_id: &str) -> ChatKey {
+    (
+        user_id.to_string(),
+        time,
+        message.replace(['\r', '\n'], " ").trim().to_string(),
+    )
+}
+
+/// 최근에 기록한 채팅 키 (최대 `RECENT_CHAT_KEYS`개)
+#[derive(Default)]
+struct RecentChats {
+    /// 이번 실행에서 기록한 채팅 (밀리초 시간)
+    keys: HashSet<ChatKey>,
+    order: VecDeque<ChatKey>,
+    /// 기존 로그 파일 끝부분의 채팅 (초 단위 시간)
+    loaded: HashSet<ChatKey>,
+}
+
+impl RecentChats {
+    /// 키를 추가합니다. 이미 있으면 false를 반환합니다.
+    /// 같은 초에 같은 사용자가 같은 메시지를 보낸 서로 다른 채팅은 밀리초 시간으로 구분하고,
+    /// 파일에서 읽은 채팅과는 초 단위로만 비교합니다.
+    fn insert(&mut self, timestamp: &DateTime<FixedOffset>, message: &str, user_id: &str) -> bool {
+        if self
+            .loaded
+            .contains(&chat_key(timestamp.timestamp(), message, user_id))
+        {
+            return false;
+        }
+
+        let key = chat_key(timestamp.timestamp_millis(), message, user_id);
+        if !self.keys.insert(key.clone()) {
+            return false;
+        }
+        self.order.push_back(key);
+        if self.order.len() > RECENT_CHAT_KEYS {
+            if let Some(old) = self.order.pop_front() {
+                self.keys.remove(&old);
+            }
+        }
+        true
+    }
+
+    /// 기존 로그 파일 끝부분의 채팅을 읽어 옵니다. (프로세스 재시작 후 이어서 기록할 때)
+    fn load_tail(path: &Path) -> Self {
+        let mut recent = Self::default();
+        let Ok(mut file) = File::open(path) else {
+            return recent;
+        };
+
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let start = len.saturating_sub(RECENT_CHAT_TAIL_BYTES);
+        if file.seek(SeekFrom::Start(start)).is_err() {
+            return recent;
+        }
+
+        // 중간부터 읽으면 첫 줄은 잘려 있으므로 버림
+        let lines = BufReader::new(file).lines().skip(usize::from(start > 0));
+        for line in lines.map_while(|line| line.ok()) {
+            if let Some(msg) = parse_chat_line(&line) {
+                recent.loaded.insert(chat_key(
+                    msg.timestamp.timestamp(),
+                    &msg.message,
+                    &msg.user_id,
+                ));
+            }
+        }
+        recent
+    }
+}
+
+/// 라이브 세션별 채팅 로그 파일 writer
+///
+/// `chatLog-{live_id}.log` 파일에 `data::chat::loader`가 읽는 형식으로 기록하므로
+/// 저장 디렉토리를 그대로 `analysis-chat`의 입력으로 사용할 수 있습니다.
+/// 같은 라이브에 다시 연결하면 기존 파일 뒤에 이어서 기록합니다.
+/// 다시보기 채팅 다운로드에서는 `live_id` 자리에 video 번호를 사용합니다.
+pub struct ChatLogWriter {
+    dir: PathBuf,
+    live_id: u64,
+    writer: BufWriter<File>,
+    written: usize,
+    recent: RecentChats,
+    /// 최근 채팅을 기다리는 동안 모아 둔 채팅 (`None`이면 바로 기록)
+    pending: Option<Vec<PendingChat>>,
+}
+
+/// 최근 채팅과 시간순으로 합치기 위해 기록을 미룬 채팅
+struct PendingChat {
+    timestamp: DateTime<FixedOffset>,
+    nickname: String,
+    message: String,
+    user_id: String,
+}
+
+impl ChatLogWriter {
+    /// `dir` 아래에 라이브 세션의 채팅 로그 파일을 엽니다.
+    pub fn open<P: AsRef<Path>>(dir: P, live_id: u64) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create live chat log directory: {:?}", dir))?;
+
+        let writer = Self::open_file(&dir, live_id)?;
+        let recent = RecentChats::load_tail(&Self::file_path(&dir, live_id));
+
+        Ok(Self {
+            dir,
+            live_id,
+            writer,
+            written: 0,
+            recent,
+            pending: None,
+        })
+    }
+
+    fn file_path(dir: &Path, live_id: u64) -> PathBuf {
+        dir.join(format!("chatLog-{}.log", live_id))
+    }
+
+    fn open_file(dir: &Path, live_id: u64) -> Result<BufWriter<File>> {
+        let path = Self::file_path(dir, live_id);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open live chat log file: {:?}", path))?;
+        Ok(BufWriter::new(file))
+    }
+
+    /// 현재 기록 중인 파일 경로
+    pub fn path(&self) -> PathBuf {
+        Self::file_path(&self.dir, self.live_id)
+    }
+
+    /// 현재 기록 중인 라이브 ID
+    pub fn live_id(&self) -> u64 {
+        self.live_id
+    }
+
+    /// 라이브 세션이 바뀌면 새 라이브 ID의 파일로 교체합니다.
+    pub fn rotate(&mut self, live_id: u64) -> Result<()> {
+        if live_id == self.live_id {
+            return Ok(());
+        }
+
+        self.flush()?;
+        self.writer = Self::open_file(&self.dir, live_id)?;
+        self.recent = RecentChats::load_tail(&Self::file_path(&self.dir, live_id));
+        self.live_id = live_id;
+        Ok(())
+    }
+
+    /// 이 writer로 기록한 메시지 수
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// 최근 채팅(backlog)을 요청하기 전에 호출합니다.
+    /// `finish_backlog`(또는 `flush`)까지 기록을 미뤘다가 최근 채팅과 함께 시간순으로 기록합니다.
+    pub fn begin_backlog(&mut self) {
+        self.pending.get_or_insert_with(Vec::new);
+    }
+
+    /// 미뤄 둔 채팅을 시간순으로 정렬해 기록합니다.
+    pub fn finish_backlog(&mut self) -> Result<()> {
+        let Some(mut pending) = self.pending.take() else {
+            return Ok(());
+        };
+        pending.sort_by_key(|chat| chat.timestamp);
+        for chat in &pending {
+            self.write_line(
+                &chat.timestamp,
+                &chat.nickname,
+                &chat.message,
+                &chat.user_id,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// 채팅 메시지 한 건을 기록합니다.
+    pub fn write_chat(
+        &mut self,
+        timestamp: &DateTime<FixedOffset>,
+        nickname: &str,
+        message: &str,
+        user_id: &str,
+    ) -> Result<()> {
+        self.recent.insert(timestamp, message, user_id);
+        self.write_or_defer(timestamp, nickname, message, user_id)
+    }
+
+    /// 최근 채팅(backlog) 메시지를 기록합니다.
+    /// (user_id, 시간, 메시지)가 같은 채팅을 이미 기록했으면 건너뛰고 false를 반환합니다.
+    pub fn write_backlog_chat(
+        &mut self,
+        timestamp: &DateTime<FixedOffset>,
+        nickname: &str,
+        message: &str,
+        user_id: &str,
+    ) -> Result<bool> {
+        if !self.recent.insert(timestamp, message, user_id) {
+            return Ok(false);
+        }
+        self.write_or_defer(timestamp, nickname, message, user_id)?;
+        Ok(true)
+    }
+
+    fn write_or_defer(
+        &mut self,
+        timestamp: &DateTime<FixedOffset>,
+        nickname: &str,
+        message: &str,
+        user_id: &str,
+    ) -> Result<()> {
+        match self.pending.as_mut() {
+            Some(pending) => {
+                pending.push(PendingChat {
+                    timestamp: *timestamp,
+                    nickname: nickname.to_string(),
+                    message: message.to_string(),
+                    user_id: user_id.to_string(),
+                });
+                Ok(())
+            }
+            None => self.write_line(timestamp, nickname, message, user_id),
+        }
+    }
+
+    fn write_line(
+        &mut self,
+        timestamp: &DateTime<FixedOffset>,
+        nickname: &str,
+        message: &str,
+        user_id: &str,
+    ) -> Result<()> {
+        let line = format_chat_line(timestamp, nickname, message, user_id);
+        writeln!(self.writer, "{}", line)
+            .with_context(|| format!("Failed to write live chat log: {:?}", self.path()))?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// 수집하지 못한 구간을 `chatLog-{live_id}.gaps.jsonl` 파일에 한 줄씩 기록합니다.
+    /// 로더는 `.log`로 끝나는 파일만 읽으므로 분석 입력에는 섞이지 않습니다.
+    pub fn record_gap(
+        &self,
+        start: &DateTime<FixedOffset>,
+        end: &DateTime<FixedOffset>,
+        reason: &str,
+    ) -> Result<()> {
+        let gap = ChatGap {
+            start: start.format("%Y-%m-%d %H:%M:%S").to_string(),
+            end: end.format("%Y-%m-%d %H:%M:%S").to_string(),
+            seconds: (*end - *start).num_seconds(),
+            reason: reason.to_string(),
+        };
+
+        self.append_jsonl("gaps", &gap)
+    }
+
+    /// 후원/구독 기록을 `chatLog-{live_id}.donations.jsonl` 파일에 한 줄씩 기록합니다.
+    pub fn record_donation(&self, record: &DonationRecord) -> Result<()> {
+        self.append_jsonl("donations", record)
+    }
+
+    /// `chatLog-{live_id}.{kind}.jsonl` 파일 뒤에 JSON 한 줄을 추가합니다.
+    fn append_jsonl<T: Serialize>(&self, kind: &str, value: &T) -> Result<()> {
+        let path = self
+            .dir
+            .join(format!("chatLog-{}.{}.jsonl", self.live_id, kind));
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {} file: {:?}", kind, path))?;
+        writeln!(file, "{}", serde_json::to_string(value)?)
+            .with_context(|| format!("Failed to write {} file: {:?}", kind, path))?;
+        Ok(())
+    }
+
+    /// 버퍼에 남은 내용을 파일에 씁니다. 최근 채팅을 기다리며 미뤄 둔 채팅도 기록합니다.
+    pub fn flush(&mut self) -> Result<()> {
+        self.finish_backlog()?;
+        self.writer
+            .flush()
+            .with_context(|| format!("Failed to flush live chat log: {:?}", self.path()))
+    }
+}
+
+impl Drop for ChatLogWriter {
+    fn drop(&mut self) {
+        self.finish_backlog().ok();
+        self.writer.flush().ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::chat::loader::load_chat_log;
+
+    #[test]
+    fn test_chat_log_writer_output_is_loadable() {
+        let dir = env::temp_dir().join(format!("chzzk-chat-recorder-{}", std::process::id()));
+        let timestamp = kst_from_millis(1_761_296_595_000).unwrap();
+
+        {
+            let mut writer = ChatLogWriter::open(&dir, 42).unwrap();
+            writer
+                .write_chat(
+                    &timestamp,
+                    "1연지",
+                    "머타타",
+                    "f2959e925442442d133ed215d603786d",
+                )
+                .unwrap();
+            writer
+                .write_chat(&timestamp, "닉네임", "여러\n줄", "abc")
+                .unwrap();
+            assert_eq!(writer.written(), 2);
+        }
+
+        let chat_log = load_chat_log(dir.join("chatLog-42.log")).unwrap().0;
+        assert_eq!(chat_log.video_id, 42);
+        assert_eq!(chat_log.messages.len(), 2);
+        assert_eq!(chat_log.messages[0].timestamp, timestamp);
+        assert_eq!(chat_log.messages[0].nickname, "1연지");
+        assert_eq!(chat_log.messages[1].message, "여러 줄");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_chat_log_writer_rotate_and_gap() {
+        let dir = env::temp_dir().join(format!("chzzk-chat-rotate-{}", std::process::id()));
+        let start = kst_from_millis(1_761_296_595_000).unwrap();
+        let end = kst_from_millis(1_761_296_625_000).unwrap();
+
+        let mut writer = ChatLogWriter::open(&dir, 1).unwrap();
+        writer.write_chat(&start, "a", "첫 방송", "u1").unwrap();
+        writer.rotate(2).unwrap();
+        writer.write_chat(&end, "a", "새 방송", "u1").unwrap();
+        writer.record_gap(&start, &end, "test").unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(writer.live_id(), 2);
+        assert_eq!(
+            load_chat_log(dir.join("chatLog-1.log"))
+                .unwrap()
+                .0
+                .messages
+                .len(),
+            1
+        );
+        assert_eq!(
+            load_chat_log(dir.join("chatLog-2.log"))
+                .unwrap()
+                .0
+                .messages
+                .len(),
+            1
+        );
+
+        let gaps = fs::read_to_string(dir.join("chatLog-2.gaps.jsonl")).unwrap();
+        assert!(gaps.contains("\"seconds\":30"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_backlog_chat_dedup_after_reopen() {
+        let dir = env::temp_dir().join(format!("chzzk-chat-backlog-{}", std::process::id()));
+        let live = kst_from_millis(1_761_296_595_120).unwrap();
+        let backlog = kst_from_millis(1_761_296_595_870).unwrap();
+
+        {
+            let mut writer = ChatLogWriter::open(&dir, 3).unwrap();
+            writer.write_chat(&live, "a", "안녕", "u1").unwrap();
+        }
+
+        // 다시 열어도 기존 파일 끝부분의 채팅과 중복을 확인함
+        let mut writer = ChatLogWriter::open(&dir, 3).unwrap();
+        assert!(!writer
+            .write_backlog_chat(&backlog, "a", "안녕", "u1")
+            .unwrap());
+        assert!(writer
+            .write_backlog_chat(&backlog, "b", "안녕", "u2")
+            .unwrap());
+        writer.flush().unwrap();
+
+        let chat_log = load_chat_log(dir.join("chatLog-3.log")).unwrap().0;
+        assert_eq!(chat_log.messages.len(), 2);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_backlog_merged_in_time_order() {
+        let dir = env::temp_dir().join(format!("chzzk-chat-merge-{}", std::process::id()));
+        let at = |millis: i64| kst_from_millis(1_761_296_590_000 + millis).unwrap();
+
+        let mut writer = ChatLogWriter::open(&dir, 4).unwrap();
+        writer.begin_backlog();
+        // 최근 채팅 응답보다 실시간 채팅이 먼저 도착
+        writer.write_chat(&at(5_120), "a", "ㅋㅋ", "u1").unwrap();
+        writer
+            .write_chat(&at(6_000), "b", "늦은 채팅", "u2")
+            .unwrap();
+        assert!(writer
+            .write_backlog_chat(&at(1_000), "c", "먼저 온 채팅", "u3")
+            .unwrap());
+        // 이미 받은 채팅은 건너뛰고, 같은 초에 보낸 다른 채팅은 남김
+        assert!(!writer
+            .write_backlog_chat(&at(5_120), "a", "ㅋㅋ", "u1")
+            .unwrap());
+        assert!(writer
+            .write_backlog_chat(&at(5_870), "a", "ㅋㅋ", "u1")
+            .unwrap());
+        writer.finish_backlog().unwrap();
+        writer.flush().unwrap();
+
+        let chat_log = load_chat_log(dir.join("chatLog-4.log")).unwrap().0;
+        let messages: Vec<(&str, &str)> = chat_log
+            .messages
+            .iter()
+            .map(|msg| (msg.user_id.as_str(), msg.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                ("u3", "먼저 온 채팅"),
+                ("u1", "ㅋㅋ"),
+                ("u1", "ㅋㅋ"),
+                ("u2", "늦은 채팅"),
+            ]
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,28 +1,66 @@
 use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use color_eyre::eyre::Result;
 use futures::future::join_all;
+use once_cell::sync::Lazy;
+use tokio::task::JoinHandle;
 
-use crate::api::client::{fetch_channel, fetch_live_detail, fetch_lives_pages};
-use crate::api::models::LiveReady;
+use crate::api::client::CHZZK_CLIENT;
+use crate::api::models::{Live, LiveReady};
 use crate::api::websocket::spawn_scrape_chats;
 use crate::utils::{log, SCRAPING_CHANNELS};
 
-/// Node의 scanChannels와 대응
-pub async fn scan_channels() -> Result<()> {
-    let min_live_user: u64 = env::var("MIN_LIVE_USER")
+/// `MIN_LIVE_USER` 환경 변수에서 최소 동시 시청자 수를 읽습니다.
+pub fn min_live_user_from_env() -> u64 {
+    env::var("MIN_LIVE_USER")
         .ok()
         .and_then(|s| s.parse().ok())
-        .unwrap_or(100); // 기본값
+        .unwrap_or(100) // 기본값
+}
+
+/// 마지막으로 가져온 수집 대상 라이브 목록
+struct LivesSnapshot {
+    fetched_at: Instant,
+    min_live_user: u64,
+    lives: Vec<Live>,
+}
 
+static LATEST_LIVES: Lazy<Mutex<Option<LivesSnapshot>>> = Lazy::new(|| Mutex::new(None));
+
+/// 수집 대상 라이브(최소 시청자 수 이상, 성인 방송 제외) 목록을 가져옵니다.
+/// `max_age` 안에 같은 최소 시청자 수로 가져온 목록이 있으면 API를 다시 부르지 않고 그 목록을 씁니다.
+/// 스캐너와 시청자 수 샘플러가 같은 목록을 공유해 라이브 목록 요청을 한 번만 보내도록 합니다.
+pub async fn fetch_target_lives(min_live_user: u64, max_age: Duration) -> Result<Vec<Live>> {
+    if let Some(snapshot) = LATEST_LIVES.lock().unwrap().as_ref() {
+        if snapshot.min_live_user == min_live_user && snapshot.fetched_at.elapsed() < max_age {
+            return Ok(snapshot.lives.clone());
+        }
+    }
+
+    let mut lives = CHZZK_CLIENT.fetch_lives_pages(min_live_user).await?;
+    // adult == false만
+    lives.retain(|l| !l.adult);
+
+    *LATEST_LIVES.lock().unwrap() = Some(LivesSnapshot {
+        fetched_at: Instant::now(),
+        min_live_user,
+        lives: lives.clone(),
+    });
+    Ok(lives)
+}
+
+/// Node의 scanChannels와 대응
+/// 새로 시작한 스크래핑 세션들의 JoinHandle을 반환합니다.
+pub async fn scan_channels(min_live_user: u64) -> Result<Vec<JoinHandle<()>>> {
     log(format!(
         "Starting scan with MIN_LIVE_USER = {}",
         min_live_user
     ));
 
-    let mut lives = fetch_lives_pages(min_live_user).await?;
-    // adult == false만
-    lives.retain(|l| !l.adult);
+    // 새로 시작한 라이브를 놓치지 않도록 항상 새로 가져옴
+    let lives = fetch_target_lives(min_live_user, Duration::ZERO).await?;
 
     // 병렬로 채널 상세 데이터 fetch
     let futures: Vec<_> = lives
@@ -30,8 +68,10 @@ pub async fn scan_channels() -> Result<()> {
         .map(|live| {
             let channel_id = live.channel.channel_id.clone();
             async move {
-                let (detail_result, live_detail_result) =
-                    tokio::join!(fetch_channel(&channel_id), fetch_live_detail(&channel_id));
+                let (detail_result, live_detail_result) = tokio::join!(
+                    CHZZK_CLIENT.fetch_channel(&channel_id),
+                    CHZZK_CLIENT.fetch_live_detail(&channel_id)
+                );
 
                 let detail_opt = detail_result.ok().flatten();
                 let live_detail_opt = live_detail_result.ok().flatten();
@@ -39,10 +79,18 @@ pub async fn scan_channels() -> Result<()> {
                 let detail = detail_opt?;
                 let follower_count = detail.follower_count?;
                 let live_detail = live_detail_opt?;
+                let Some(live_id) = live_detail.live_id else {
+                    log(format!(
+                        "Skipping live without liveId (channel {})",
+                        channel_id
+                    ));
+                    return None;
+                };
                 let chat_channel_id = live_detail.chat_channel_id.clone()?;
 
                 let ready_live = LiveReady {
                     channel_id,
+                    live_id,
                     chat_channel_id,
                     follower_count,
                 };
@@ -60,9 +108,118 @@ pub async fn scan_channels() -> Result<()> {
     ready_lives.retain(|l| !SCRAPING_CHANNELS.contains(&l.channel_id));
 
     // 각 live마다 WebSocket 스크래핑 시작
-    for live in ready_lives {
-        spawn_scrape_chats(live);
+    let handles = ready_lives.into_iter().map(spawn_scrape_chats).collect();
+
+    Ok(handles)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::api::mock_server::{
+        channel_detail_response, chat_message, live_detail_response, lives_response,
+        recent_chat_entry, MockChzzk,
+    };
+    use crate::data::chat::loader::load_chat_log;
+
+    #[tokio::test]
+    async fn test_scan_connect_record_with_mock_server() {
+        let channel_id = "0123456789abcdef0123456789abcdef";
+        let live_id = 777;
+
+        let server = MockChzzk::start(
+            vec![
+                (
+                    "/service/v1/lives".to_string(),
+                    lives_response(&[(channel_id, 500)]),
+                ),
+                (
+                    format!("/service/v1/channels/{}", channel_id),
+                    channel_detail_response(channel_id, 1000),
+                ),
+                (
+                    format!("/service/v3/channels/{}/live-detail", channel_id),
+                    live_detail_response(live_id, "chat-channel"),
+                ),
+            ],
+            vec![
+                json!({ "ver": "3", "cmd": 0 }),
+                chat_message(&[
+                    (1_761_296_595_000, "시청자1", "안녕하세요", "user-1"),
+                    (1_761_296_596_000, "시청자2", "ㅋㅋㅋ", "user-2"),
+                ]),
+            ],
+            // 연결 전 채팅 1건 + 실시간으로 이미 받은 채팅 1건 (중복)
+            vec![
+                recent_chat_entry(1_761_296_590_000, "시청자0", "먼저 온 채팅", "user-0"),
+                recent_chat_entry(1_761_296_595_000, "시청자1", "안녕하세요", "user-1"),
+            ],
+        )
+        .await;
+        let dir = std::env::temp_dir().join(format!("chzzk-chat-mock-{}", std::process::id()));
+        let _globals = server.install_globals(&dir).await;
+
+        let handles = scan_channels(100).await.unwrap();
+        assert_eq!(handles.len(), 1);
+        for handle in handles {
+            tokio::time::timeout(Duration::from_secs(10), handle)
+                .await
+                .expect("scrape session did not finish")
+                .unwrap();
+        }
+
+        let received = server.received();
+        assert_eq!(received[0]["cmd"], 100);
+        assert_eq!(received[0]["cid"], "chat-channel");
+        assert!(received
+            .iter()
+            .any(|msg| msg["cmd"] == 5101 && msg["sid"] == "mock-sid"));
+        assert!(!SCRAPING_CHANNELS.contains(channel_id));
+
+        let chat_log = load_chat_log(dir.join(format!("chatLog-{}.log", live_id)))
+            .unwrap()
+            .0;
+        let mut messages: Vec<&str> = chat_log
+            .messages
+            .iter()
+            .map(|msg| msg.message.as_str())
+            .collect();
+        messages.sort();
+        assert_eq!(messages, vec!["ㅋㅋㅋ", "먼저 온 채팅", "안녕하세요"]);
+
+        std::fs::remove_dir_all(&dir).ok();
     }
 
-    Ok(())
+    #[tokio::test]
+    async fn test_fetch_target_lives_reuses_recent_list() {
+        let lives_path = "/service/v1/lives";
+        let server = MockChzzk::start(
+            vec![(
+                lives_path.to_string(),
+                lives_response(&[("channel-a", 500), ("channel-b", 50)]),
+            )],
+            Vec::new(),
+            Vec::new(),
+        )
+        .await;
+        let dir = std::env::temp_dir().join(format!("chzzk-chat-lives-{}", std::process::id()));
+        let _globals = server.install_globals(&dir).await;
+
+        let lives = fetch_target_lives(100, Duration::ZERO).await.unwrap();
+        assert_eq!(lives.len(), 1);
+
+        // 최근 목록이 있으면 요청하지 않음 (요청했다면 404로 실패)
+        server.fail_next(lives_path, &[(404, None)]);
+        let cached = fetch_target_lives(100, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(cached[0].channel.channel_id, "channel-a");
+
+        // 최소 시청자 수가 다르거나 목록이 오래되었으면 새로 요청
+        assert!(fetch_target_lives(10, Duration::from_secs(60))
+            .await
+            .is_err());
+    }
 }
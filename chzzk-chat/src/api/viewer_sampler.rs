@@ -0,0 +1,73 @@
+use std::fs::{self, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, Utc};
+use color_eyre::eyre::{Context, Result};
+
+use crate::api::models::Live;
+use crate::api::recorder::live_chat_log_dir;
+use crate::api::scanner::fetch_target_lives;
+use crate::data::models::ViewerSample;
+
+/// 동시 시청자 수 샘플 파일 경로 (`LIVE_CHAT_LOG_DIR/viewerCounts.jsonl`)
+/// 로더는 `chatLog-*.log` 파일만 읽으므로 채팅 로그와 같은 디렉토리에 둡니다.
+pub fn viewer_samples_path() -> PathBuf {
+    live_chat_log_dir().join("viewerCounts.jsonl")
+}
+
+/// 라이브 목록을 시청자 수 샘플로 변환합니다. `liveId`가 없는 라이브는 건너뜁니다.
+pub fn samples_from_lives(lives: &[Live], timestamp: &DateTime<FixedOffset>) -> Vec<ViewerSample> {
+    let timestamp = timestamp.format("%Y-%m-%dT%H:%M:%S%z").to_string();
+
+    lives
+        .iter()
+        .filter_map(|live| {
+            Some(ViewerSample {
+                channel_id: live.channel.channel_id.clone(),
+                live_id: live.live_id?,
+                timestamp: timestamp.clone(),
+                concurrent_user_count: live.concurrent_user_count,
+                category: live.live_category_value.clone(),
+                title: live.live_title.clone(),
+            })
+        })
+        .collect()
+}
+
+/// 샘플들을 JSONL 파일 뒤에 한 줄씩 추가합니다.
+pub fn append_viewer_samples<P: AsRef<Path>>(path: P, samples: &[ViewerSample]) -> Result<()> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create viewer sample directory: {:?}", dir))?;
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open viewer sample file: {:?}", path))?;
+    let mut writer = BufWriter::new(file);
+    for sample in samples {
+        writeln!(writer, "{}", serde_json::to_string(sample)?)
+            .with_context(|| format!("Failed to write viewer sample file: {:?}", path))?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// 수집 대상 라이브(최소 시청자 수 이상, 성인 방송 제외)의 시청자 수를 기록합니다.
+/// 스캐너가 `max_age` 안에 가져온 라이브 목록이 있으면 다시 요청하지 않고 그 목록을 씁니다.
+/// 기록한 샘플 수를 반환합니다.
+pub async fn sample_viewers(min_live_user: u64, max_age: Duration) -> Result<usize> {
+    let lives = fetch_target_lives(min_live_user, max_age).await?;
+
+    let now = Utc::now().with_timezone(&FixedOffset::east_opt(9 * 3600).unwrap());
+    let samples = samples_from_lives(&lives, &now);
+    append_viewer_samples(viewer_samples_path(), &samples)?;
+
+    Ok(samples.len())
+}
@@ -1,42 +1,248 @@
 use std::time::Duration;
 
+use chrono::{DateTime, FixedOffset, Utc};
 use color_eyre::eyre::Result;
 use futures::{SinkExt, StreamExt};
-use serde_json::Value;
+use rand::Rng;
+use tokio::task::JoinHandle;
 use tokio::time;
 use tokio_tungstenite::{
     connect_async,
     tungstenite::{client::IntoClientRequest, Message},
 };
 
-use crate::api::models::{InitBody, InitMessage, LiveReady};
-use crate::utils::{log, SCRAPING_CHANNELS};
+use crate::api::client::CHZZK_CLIENT;
+use crate::api::endpoints::chat_ws_url;
+use crate::api::live_events::{self, LiveEventTracker};
+use crate::api::metrics::METRICS;
+use crate::api::migration;
+use crate::api::models::{InitBody, InitMessage, LiveReady, RecentChatBody, RecentChatRequest};
+use crate::api::protocol::{
+    ChatCommand, ChatEntry, CMD_CONNECT, CMD_PING, CMD_PONG, CMD_REQUEST_RECENT_CHAT,
+};
+use crate::api::recorder::{kst_from_millis, live_chat_log_dir, ChatLogWriter, DonationRecord};
+use crate::utils::{log, wait_for_shutdown, SCRAPING_CHANNELS, SHUTDOWN};
+
+/// 재연결 대기 시간의 최소값
+const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(1);
+/// 재연결 대기 시간의 최대값
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(60);
+/// 이 시간 이상 연결이 유지되었으면 backoff를 초기화합니다.
+const STABLE_SESSION: Duration = Duration::from_secs(60);
+/// 연결 직후 요청하는 최근 채팅 수
+const RECENT_CHAT_COUNT: u32 = 50;
+
+/// 웹소켓 세션 종료 사유
+enum SessionEnd {
+    /// 라이브가 종료되어 더 이상 재연결하지 않음
+    LiveClosed,
+    /// 연결이 끊어져 재연결이 필요함
+    Disconnected(String),
+    /// 프로세스 종료 요청
+    Shutdown,
+}
+
+/// 지수 backoff + jitter 계산기
+struct Backoff {
+    attempt: u32,
+}
+
+impl Backoff {
+    fn new() -> Self {
+        Self { attempt: 0 }
+    }
+
+    /// 다음 재연결까지 대기할 시간을 반환합니다.
+    /// `base * 2^attempt`(최대 `RECONNECT_MAX_DELAY`)의 절반에 나머지 절반 범위의 jitter를 더합니다.
+    fn next_delay(&mut self) -> Duration {
+        let exp = RECONNECT_BASE_DELAY.saturating_mul(1 << self.attempt.min(16));
+        let capped = exp.min(RECONNECT_MAX_DELAY);
+        self.attempt = self.attempt.saturating_add(1);
+
+        let half = capped / 2;
+        let jitter = rand::thread_rng().gen_range(0..=half.as_millis() as u64);
+        half + Duration::from_millis(jitter)
+    }
+
+    fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+/// 현재 시간 (KST)
+fn now_kst() -> DateTime<FixedOffset> {
+    Utc::now().with_timezone(&FixedOffset::east_opt(9 * 3600).unwrap())
+}
+
+/// `fetch_channel`이 라이브 종료(`open_live == false`)를 보고했는지 확인합니다.
+/// API 오류 등으로 확인할 수 없으면 라이브가 계속 중인 것으로 간주합니다.
+async fn is_live_closed(channel_id: &str) -> bool {
+    match CHZZK_CLIENT.fetch_channel(channel_id).await {
+        Ok(Some(detail)) => detail.open_live == Some(false),
+        Ok(None) => false,
+        Err(e) => {
+            log(format!(
+                "fetch_channel error for channel {}: {:?}",
+                channel_id, e
+            ));
+            false
+        }
+    }
+}
 
 /// Node의 scrapeChats(live)와 대응 (백그라운드 태스크로 실행)
-pub fn spawn_scrape_chats(live: LiveReady) {
+pub fn spawn_scrape_chats(live: LiveReady) -> JoinHandle<()> {
     tokio::spawn(async move {
-        if let Err(e) = scrape_chats(live.clone()).await {
+        let channel_id = live.channel_id.clone();
+        if let Err(e) = scrape_chats(live).await {
             log(format!(
                 "scrape_chats error for channel {}: {:?}",
-                live.channel_id, e
+                channel_id, e
+            ));
+            SCRAPING_CHANNELS.remove(&channel_id);
+            METRICS.remove_channel(&channel_id);
+        }
+    })
+}
+
+/// 라이브가 끝날 때까지 웹소켓 세션을 유지합니다.
+/// 연결이 끊기면 backoff 후 재연결하고, `fetch_channel`이 라이브 종료를 보고할 때만 포기합니다.
+async fn scrape_chats(mut live: LiveReady) -> Result<()> {
+    SCRAPING_CHANNELS.insert(live.channel_id.clone());
+
+    let mut chat_writer = ChatLogWriter::open(live_chat_log_dir(), live.live_id)?;
+    let mut event_tracker = live_events::tracker(&live.channel_id, live.live_id);
+    let mut backoff = Backoff::new();
+
+    loop {
+        let connected_at = time::Instant::now();
+        let reason = match run_session(&live, &mut chat_writer, &mut event_tracker).await {
+            Ok(SessionEnd::LiveClosed) | Ok(SessionEnd::Shutdown) => break,
+            Ok(SessionEnd::Disconnected(reason)) => reason,
+            Err(e) => format!("{:?}", e),
+        };
+        chat_writer.flush()?;
+        if let Some(tracker) = event_tracker.as_mut() {
+            live_events::publish(tracker.pause());
+        }
+
+        if connected_at.elapsed() >= STABLE_SESSION {
+            backoff.reset();
+        }
+
+        let gap_start = now_kst();
+        log(format!(
+            "Disconnected! channel_id={} live_id={} reason={}",
+            live.channel_id, live.live_id, reason
+        ));
+
+        if is_live_closed(&live.channel_id).await {
+            log(format!(
+                "Channel {} closed live, stop reconnecting.",
+                live.channel_id
             ));
-            SCRAPING_CHANNELS.remove(&live.channel_id);
+            break;
+        }
+
+        let delay = backoff.next_delay();
+        METRICS.record_reconnect(&live.channel_id);
+        log(format!(
+            "Reconnecting channel_id={} in {:.1}s (attempt {})",
+            live.channel_id,
+            delay.as_secs_f64(),
+            backoff.attempt
+        ));
+        tokio::select! {
+            _ = time::sleep(delay) => {}
+            _ = wait_for_shutdown() => break,
         }
-    });
+
+        // 재연결 전에 채팅 채널과 라이브 ID를 갱신 (방송이 새로 시작되었을 수 있음)
+        let detail = CHZZK_CLIENT
+            .fetch_live_detail(&live.channel_id)
+            .await
+            .ok()
+            .flatten();
+        if let Some(chat_channel_id) = detail.as_ref().and_then(|d| d.chat_channel_id.clone()) {
+            live.chat_channel_id = chat_channel_id;
+        }
+
+        // 공백은 끊긴 라이브의 파일에 기록한 뒤 새 라이브로 교체
+        record_gap(&live, &chat_writer, &gap_start, &reason);
+
+        let new_live_id = detail
+            .and_then(|d| d.live_id)
+            .filter(|&live_id| live_id != live.live_id);
+        if let Some(live_id) = new_live_id {
+            log(format!(
+                "Live changed! channel_id={} live_id={} -> {}",
+                live.channel_id, live.live_id, live_id
+            ));
+            live.live_id = live_id;
+            chat_writer.rotate(live_id)?;
+            if let Some(tracker) = event_tracker.as_mut() {
+                live_events::publish(tracker.finish());
+            }
+            event_tracker = live_events::tracker(&live.channel_id, live_id);
+        }
+    }
+
+    chat_writer.flush()?;
+    if let Some(tracker) = event_tracker.as_mut() {
+        live_events::publish(tracker.finish());
+    }
+    SCRAPING_CHANNELS.remove(&live.channel_id);
+    METRICS.remove_channel(&live.channel_id);
+    log(format!(
+        "Closed! channel_id={} live_id={} chats={} scrapingChannels={}",
+        live.channel_id,
+        chat_writer.live_id(),
+        chat_writer.written(),
+        SCRAPING_CHANNELS.len()
+    ));
+
+    Ok(())
 }
 
-async fn scrape_chats(live: LiveReady) -> Result<()> {
-    let request = "wss://kr-ss1.chat.naver.com/chat"
-        .into_client_request()
-        .unwrap();
+/// 연결이 끊겼다가 다시 연결하기까지의 공백을 현재 라이브의 gaps 파일에 기록합니다.
+fn record_gap(
+    live: &LiveReady,
+    chat_writer: &ChatLogWriter,
+    gap_start: &DateTime<FixedOffset>,
+    reason: &str,
+) {
+    let gap_end = now_kst();
+    log(format!(
+        "Gap channel_id={} live_id={} {} ~ {} ({}s)",
+        live.channel_id,
+        chat_writer.live_id(),
+        gap_start.format("%Y-%m-%d %H:%M:%S"),
+        gap_end.format("%Y-%m-%d %H:%M:%S"),
+        (gap_end - *gap_start).num_seconds()
+    ));
+    if let Err(e) = chat_writer.record_gap(gap_start, &gap_end, reason) {
+        log(format!("Failed to record gap: {:?}", e));
+    }
+}
+
+/// 웹소켓에 한 번 연결해서 끊어질 때까지 채팅을 수집합니다.
+async fn run_session(
+    live: &LiveReady,
+    chat_writer: &mut ChatLogWriter,
+    event_tracker: &mut Option<LiveEventTracker>,
+) -> Result<SessionEnd> {
+    let request = chat_ws_url().into_client_request()?;
+
+    if *SHUTDOWN.borrow() {
+        return Ok(SessionEnd::Shutdown);
+    }
 
     let (mut ws_stream, _) = connect_async(request).await?;
-    SCRAPING_CHANNELS.insert(live.channel_id.clone());
 
     // INIT 메시지 전송
     let init_msg = InitMessage {
         ver: "3".to_string(),
-        cmd: 100,
+        cmd: CMD_CONNECT as i32,
         svcid: "game".to_string(),
         cid: live.chat_channel_id.clone(),
         tid: 1,
@@ -58,30 +264,32 @@ async fn scrape_chats(live: LiveReady) -> Result<()> {
         .await?;
 
     log(format!(
-        "Opened! channel_id={} scrapingChannels={}",
+        "Opened! channel_id={} live_id={} file={:?} scrapingChannels={}",
         live.channel_id,
+        live.live_id,
+        chat_writer.path(),
         SCRAPING_CHANNELS.len()
     ));
 
     let mut ping_interval = time::interval(Duration::from_secs(20));
+    // 이벤트 탐지용 초 단위 확정 주기
+    let mut event_interval = time::interval(Duration::from_secs(1));
 
     loop {
         tokio::select! {
             _ = ping_interval.tick() => {
                 // Node 코드처럼 주기적으로 채널 상태 확인 및 PING
-                if let Some(detail) = crate::api::client::fetch_channel(&live.channel_id).await? {
-                    if let Some(open_live) = detail.open_live {
-                        if !open_live {
-                            log(format!("Channel {} closed live, closing websocket.", live.channel_id));
-                            ws_stream.close(None).await.ok();
-                            break;
-                        }
-                    }
+                if is_live_closed(&live.channel_id).await {
+                    log(format!("Channel {} closed live, closing websocket.", live.channel_id));
+                    ws_stream.close(None).await.ok();
+                    return Ok(SessionEnd::LiveClosed);
                 }
 
+                chat_writer.flush()?;
+
                 let ping_msg = serde_json::json!({
                     "ver": 3,
-                    "cmd": 0,
+                    "cmd": CMD_PING,
                 });
 
                 ws_stream
@@ -90,76 +298,241 @@ async fn scrape_chats(live: LiveReady) -> Result<()> {
                     .ok();
             }
 
+            _ = event_interval.tick(), if event_tracker.is_some() => {
+                if let Some(tracker) = event_tracker.as_mut() {
+                    live_events::publish(tracker.advance(Utc::now().timestamp_millis()));
+                }
+            }
+
+            _ = wait_for_shutdown() => {
+                ws_stream.close(None).await.ok();
+                return Ok(SessionEnd::Shutdown);
+            }
+
             msg = ws_stream.next() => {
                 match msg {
                     Some(Ok(Message::Text(text))) => {
-                        handle_ws_message(&live, &mut ws_stream, &text).await?;
+                        handle_ws_message(&mut ws_stream, live, chat_writer, event_tracker, &text).await?;
                     }
                     Some(Ok(Message::Ping(_))) => {
                         // 서버에서 온 ping에 자동 응답은 tungstenite가 처리하지만,
                         // 필요하면 여기서 수동으로 처리 가능.
                     }
-                    Some(Ok(Message::Close(_))) => {
-                        break;
+                    Some(Ok(Message::Close(frame))) => {
+                        return Ok(SessionEnd::Disconnected(format!("close frame: {:?}", frame)));
                     }
                     Some(Err(e)) => {
                         log(format!("WebSocket error for channel {}: {:?}", live.channel_id, e));
-                        break;
+                        return Ok(SessionEnd::Disconnected(format!("websocket error: {}", e)));
                     }
                     None => {
                         // 스트림 종료
-                        break;
+                        return Ok(SessionEnd::Disconnected("stream ended".to_string()));
                     }
                     _ => {}
                 }
             }
         }
     }
-
-    SCRAPING_CHANNELS.remove(&live.channel_id);
-    log(format!(
-        "Closed! channel_id={} scrapingChannels={}",
-        live.channel_id,
-        SCRAPING_CHANNELS.len()
-    ));
-
-    Ok(())
 }
 
 async fn handle_ws_message(
-    live: &LiveReady,
     ws_stream: &mut (impl futures::Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin),
+    live: &LiveReady,
+    chat_writer: &mut ChatLogWriter,
+    event_tracker: &mut Option<LiveEventTracker>,
     text: &str,
 ) -> Result<()> {
-    let v: Value = serde_json::from_str(text)?;
-
-    let cmd = v["cmd"].as_i64().unwrap_or_default();
-
-    if cmd == 0 {
-        // 서버 ping -> PONG 응답
-        let pong_msg = serde_json::json!({
-            "ver": 3,
-            "cmd": 10000,
-        });
-        ws_stream
-            .send(Message::Text(pong_msg.to_string()))
-            .await
-            .ok();
-    } else if cmd == 93101 {
-        if let Some(bdy) = v["bdy"].as_array() {
-            for chat in bdy {
-                if let Some(uid) = chat["uid"].as_str() {
-                    // db.insertChat({ channelId, userId }) 대신 println!
-                    println!(
-                        "CHAT channelId={} userId={}, msg={}",
-                        live.channel_id,
-                        uid,
-                        chat["msg"].as_str().unwrap_or("")
-                    );
+    // 스키마와 맞지 않는 프레임 하나로 세션을 끊지 않도록 기록만 하고 건너뜀
+    let command = match ChatCommand::decode(text) {
+        Ok(command) => command,
+        Err(e) => {
+            log(format!(
+                "Skipping undecodable frame live_id={} error={} frame={}",
+                chat_writer.live_id(),
+                e,
+                text
+            ));
+            return Ok(());
+        }
+    };
+
+    match command {
+        ChatCommand::Ping => {
+            // 서버 ping -> PONG 응답
+            let pong_msg = serde_json::json!({
+                "ver": 3,
+                "cmd": CMD_PONG,
+            });
+            ws_stream
+                .send(Message::Text(pong_msg.to_string()))
+                .await
+                .ok();
+        }
+        ChatCommand::Chat(entries) | ChatCommand::Donation(entries) => {
+            for entry in &entries {
+                if write_chat_entry(chat_writer, entry, false)? {
+                    METRICS.record_message(&live.channel_id);
+                    migration::record_chat(&live.channel_id, &entry.user_id, entry.msg_time);
+                    if let Some(tracker) = event_tracker.as_mut() {
+                        tracker.record(entry.msg_time);
+                    }
+                }
+            }
+        }
+        ChatCommand::Blind(blind) => {
+            log(format!(
+                "Blind message live_id={} user_id={:?} time={} type={:?}",
+                chat_writer.live_id(),
+                blind.user_id,
+                blind.message_time,
+                blind.blind_type
+            ));
+        }
+        ChatCommand::Notice(notice) => {
+            log(format!(
+                "Notice live_id={} {:?}",
+                chat_writer.live_id(),
+                notice.map(|notice| notice.message)
+            ));
+        }
+        ChatCommand::System { cmd, bdy } | ChatCommand::Unknown { cmd, bdy } => {
+            log(format!(
+                "System message live_id={} cmd={} bdy={}",
+                chat_writer.live_id(),
+                cmd,
+                bdy
+            ));
+        }
+        ChatCommand::Connected { sid: Some(sid) } => {
+            // 연결 전 채팅을 받기 위해 최근 채팅 요청
+            // 응답이 올 때까지 실시간 채팅 기록을 미뤘다가 시간순으로 합쳐서 기록
+            chat_writer.begin_backlog();
+            let request = RecentChatRequest {
+                ver: "3".to_string(),
+                cmd: CMD_REQUEST_RECENT_CHAT as i32,
+                svcid: "game".to_string(),
+                cid: live.chat_channel_id.clone(),
+                sid,
+                tid: 2,
+                bdy: RecentChatBody {
+                    recent_message_count: RECENT_CHAT_COUNT,
+                },
+            };
+            ws_stream
+                .send(Message::Text(serde_json::to_string(&request)?))
+                .await
+                .ok();
+        }
+        ChatCommand::RecentChat(mut recent) => {
+            recent.messages.sort_by_key(|entry| entry.msg_time);
+
+            let mut merged = 0;
+            for entry in &recent.messages {
+                if write_chat_entry(chat_writer, entry, true)? {
+                    merged += 1;
                 }
             }
+            chat_writer.finish_backlog()?;
+            log(format!(
+                "Recent chat live_id={} received={} merged={}",
+                chat_writer.live_id(),
+                recent.messages.len(),
+                merged
+            ));
         }
+        ChatCommand::Pong | ChatCommand::Connected { sid: None } => {}
     }
 
     Ok(())
 }
+
+/// 채팅 한 건을 로그에 기록하고, 후원/구독이면 후원 기록도 남깁니다.
+/// `backlog`이면 이미 기록한 채팅은 건너뜁니다. 새로 기록했으면 true를 반환합니다.
+fn write_chat_entry(
+    chat_writer: &mut ChatLogWriter,
+    entry: &ChatEntry,
+    backlog: bool,
+) -> Result<bool> {
+    if entry.user_id.is_empty() || entry.message.trim().is_empty() {
+        if entry.is_donation() && !backlog {
+            record_donation(chat_writer, entry)?;
+        }
+        return Ok(false);
+    }
+    let Some(timestamp) = kst_from_millis(entry.msg_time) else {
+        return Ok(false);
+    };
+
+    let written = if backlog {
+        chat_writer.write_backlog_chat(
+            &timestamp,
+            entry.nickname(),
+            &entry.message,
+            &entry.user_id,
+        )?
+    } else {
+        chat_writer.write_chat(&timestamp, entry.nickname(), &entry.message, &entry.user_id)?;
+        true
+    };
+
+    if written && entry.is_donation() {
+        record_donation(chat_writer, entry)?;
+    }
+    Ok(written)
+}
+
+fn record_donation(chat_writer: &ChatLogWriter, entry: &ChatEntry) -> Result<()> {
+    match DonationRecord::from_entry(entry) {
+        Some(record) => chat_writer.record_donation(&record),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_live() -> LiveReady {
+        LiveReady {
+            channel_id: "channel".to_string(),
+            live_id: 1,
+            chat_channel_id: "chat-channel".to_string(),
+            follower_count: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_undecodable_frame_keeps_session() {
+        let dir = std::env::temp_dir().join(format!("chzzk-chat-ws-{}", std::process::id()));
+        let mut chat_writer = ChatLogWriter::open(&dir, 1).unwrap();
+        let mut sink = futures::sink::drain().sink_map_err(|never| match never {});
+        let live = test_live();
+
+        // 본문 형식이 맞지 않는 채팅, JSON이 아닌 프레임
+        for text in [r#"{"cmd": 93101, "bdy": "not a chat"}"#, "not json"] {
+            handle_ws_message(&mut sink, &live, &mut chat_writer, &mut None, text)
+                .await
+                .unwrap();
+        }
+        assert_eq!(chat_writer.written(), 0);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        let mut backoff = Backoff::new();
+        for attempt in 0..20u32 {
+            let delay = backoff.next_delay();
+            let exp = RECONNECT_BASE_DELAY
+                .saturating_mul(1 << attempt.min(16))
+                .min(RECONNECT_MAX_DELAY);
+            assert!(delay >= exp / 2 - Duration::from_millis(1));
+            assert!(delay <= exp);
+        }
+
+        backoff.reset();
+        assert!(backoff.next_delay() <= RECONNECT_BASE_DELAY);
+    }
+}
@@ -0,0 +1,282 @@
+use std::fs;
+use std::path::PathBuf;
+
+use color_eyre::eyre::{eyre, Context, Result};
+use rayon::prelude::*;
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::data::chat::event::{detect_event_intervals, EventDetectionResult};
+use crate::data::chat::event_align::{
+    find_shared_moments, joint_alignment, pairwise_offsets, stream_count_histogram, AlignVod,
+    SharedMoment,
+};
+use crate::data::chat::event_config::EventConfigOpt;
+use crate::data::chat::store::kst_datetime;
+use crate::data::models::{ChannelWithReplays, Replay};
+use crate::data::utils::parse_replay_time;
+use crate::data::video_analyzer::find_overlapping_replays;
+use crate::utils;
+use crate::{load_channels_and_chat_logs, AnalysisChatOpt};
+
+/// 여러 VOD 이벤트 정렬 옵션
+#[derive(StructOpt, Debug)]
+pub struct AlignEventsOpt {
+    /// 채널 및 리플레이 데이터 파일 경로 (여러 개 지정 가능)
+    #[structopt(long)]
+    pub files: Vec<String>,
+
+    /// 기준 Video ID (이 다시보기와 시간이 겹치는 VOD를 모두 정렬)
+    #[structopt(long)]
+    pub video_id: Option<u64>,
+
+    /// 시간 범위 시작 (`--video-id` 대신 사용, 예: "2024-11-10 10:00:00")
+    #[structopt(long)]
+    pub start: Option<String>,
+
+    /// 시간 범위 끝
+    #[structopt(long)]
+    pub end: Option<String>,
+
+    /// 같은 순간으로 묶을 피크 시간 차이 (초)
+    #[structopt(long, default_value = "30")]
+    pub window_sec: i64,
+
+    /// 공통 순간에 필요한 최소 VOD 수
+    #[structopt(long, default_value = "2")]
+    pub min_streams: usize,
+
+    /// 결과 JSON 파일 경로
+    #[structopt(long)]
+    pub output: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    pub config: EventConfigOpt,
+}
+
+/// 정렬 결과 JSON
+#[derive(Debug, Serialize)]
+struct AlignmentOutput {
+    start: String,
+    end: String,
+    reference_video_id: Option<u64>,
+    vods: Vec<VodOutput>,
+    pairs: Vec<PairOutput>,
+    moments: Vec<SharedMoment>,
+}
+
+/// 정렬된 VOD 하나
+#[derive(Debug, Serialize)]
+struct VodOutput {
+    video_id: u64,
+    channel_name: String,
+    title: String,
+    /// 기준 VOD 시계에 맞추려면 절대 시간에 더할 초 (연결되지 않으면 None)
+    correction_sec: Option<f64>,
+    event_count: usize,
+}
+
+/// 두 VOD 사이의 offset 관측
+#[derive(Debug, Serialize)]
+struct PairOutput {
+    a_video_id: u64,
+    b_video_id: u64,
+    offset_sec: f64,
+    matches: usize,
+    residual_sec: Option<f64>,
+}
+
+/// 여러 VOD 이벤트 정렬 모드 실행
+/// 시간 범위와 겹치는 모든 VOD의 이벤트를 탐지하고, VOD별 offset을 함께 추정해
+/// 여러 방송에서 같이 터진 순간을 각 VOD의 다시보기 기준 시간과 함께 출력합니다.
+pub fn run_align_events(opts: &AlignEventsOpt) -> Result<()> {
+    let settings = opts.config.resolve()?;
+
+    let (channels, chat_logs) = load_channels_and_chat_logs(&AnalysisChatOpt {
+        files: opts.files.clone(),
+        ..Default::default()
+    })?;
+
+    let (start, end) = time_range(opts, &channels)?;
+    let replays = find_overlapping_replays(&channels, start, end);
+    utils::log(format!(
+        "{} ~ {} 범위와 겹치는 다시보기 {}개",
+        start.format("%Y-%m-%d %H:%M:%S"),
+        end.format("%Y-%m-%d %H:%M:%S"),
+        replays.len()
+    ));
+
+    // 채팅 로그가 있는 VOD의 이벤트 탐지
+    let detected: Vec<(&ChannelWithReplays, &Replay, i64, EventDetectionResult)> = replays
+        .into_par_iter()
+        .filter_map(|(channel, replay)| {
+            let chat_log = chat_logs.get(replay.video_no)?;
+            let vod_start = parse_replay_time(&replay.start).ok()?.timestamp();
+            let result = detect_event_intervals(chat_log, &settings.detection_config(chat_log))?;
+            Some((channel, replay, vod_start, result))
+        })
+        .collect();
+    if detected.len() < 2 {
+        return Err(eyre!(
+            "Need at least 2 VODs with chat logs to align, found {}",
+            detected.len()
+        ));
+    }
+
+    let vods: Vec<AlignVod> = detected
+        .iter()
+        .map(|(_, replay, vod_start, result)| AlignVod {
+            video_id: replay.video_no,
+            vod_start: *vod_start,
+            result,
+        })
+        .collect();
+
+    utils::log(format!("VOD {}개 쌍별 offset 추정 중...", vods.len()));
+    let pairs = pairwise_offsets(&vods, &settings.matching);
+    let alignment = joint_alignment(vods.len(), pairs);
+    let moments = find_shared_moments(
+        &vods,
+        &alignment.corrections,
+        &settings.matching,
+        opts.window_sec,
+        opts.min_streams,
+    );
+
+    let output = AlignmentOutput {
+        start: start.to_rfc3339(),
+        end: end.to_rfc3339(),
+        reference_video_id: (!alignment.pairs.is_empty())
+            .then(|| vods[alignment.reference].video_id),
+        vods: detected
+            .iter()
+            .zip(&alignment.corrections)
+            .map(|((channel, replay, _, result), correction)| VodOutput {
+                video_id: replay.video_no,
+                channel_name: channel.name.clone(),
+                title: replay.title.clone(),
+                correction_sec: *correction,
+                event_count: result.events.len(),
+            })
+            .collect(),
+        pairs: alignment
+            .pairs
+            .iter()
+            .map(|pair| PairOutput {
+                a_video_id: vods[pair.a].video_id,
+                b_video_id: vods[pair.b].video_id,
+                offset_sec: pair.offset_sec,
+                matches: pair.matches,
+                residual_sec: pair.residual_sec,
+            })
+            .collect(),
+        moments,
+    };
+
+    print_alignment(&output);
+
+    if let Some(path) = &opts.output {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+        }
+        let json = serde_json::to_string_pretty(&output)?;
+        fs::write(path, json).with_context(|| format!("Failed to write {:?}", path))?;
+        utils::log(format!("정렬 결과 저장: {:?}", path));
+    }
+
+    Ok(())
+}
+
+/// `--video-id`의 다시보기 시간 또는 `--start`/`--end`로 시간 범위를 정합니다.
+fn time_range(
+    opts: &AlignEventsOpt,
+    channels: &[ChannelWithReplays],
+) -> Result<(
+    chrono::DateTime<chrono::FixedOffset>,
+    chrono::DateTime<chrono::FixedOffset>,
+)> {
+    match (opts.video_id, &opts.start, &opts.end) {
+        (Some(video_id), None, None) => {
+            let replay = channels
+                .iter()
+                .flat_map(|channel| &channel.replays)
+                .find(|replay| replay.video_no == video_id)
+                .ok_or_else(|| eyre!("Video ID {} not found in channel data", video_id))?;
+            Ok((
+                parse_replay_time(&replay.start)?,
+                parse_replay_time(&replay.end)?,
+            ))
+        }
+        (None, Some(start), Some(end)) => Ok((parse_replay_time(start)?, parse_replay_time(end)?)),
+        _ => Err(eyre!("Specify either --video-id or both --start and --end")),
+    }
+}
+
+/// 정렬 결과를 출력합니다.
+fn print_alignment(output: &AlignmentOutput) {
+    println!("\n=== VOD 정렬 결과 ({}개) ===", output.vods.len());
+    for vod in &output.vods {
+        let reference = if output.reference_video_id == Some(vod.video_id) {
+            " (기준)"
+        } else {
+            ""
+        };
+        match vod.correction_sec {
+            Some(correction) => println!(
+                "[{}] {} (Video ID: {}){}: 보정 {:+.1}초, 이벤트 {}개",
+                vod.channel_name, vod.title, vod.video_id, reference, correction, vod.event_count
+            ),
+            None => println!(
+                "[{}] {} (Video ID: {}): 매칭되는 VOD 없음, 이벤트 {}개",
+                vod.channel_name, vod.title, vod.video_id, vod.event_count
+            ),
+        }
+    }
+
+    println!("\n=== 쌍별 offset ({}개) ===", output.pairs.len());
+    for pair in &output.pairs {
+        println!(
+            "{} → {}: {:+.1}초 (매칭 {}개, 잔차 {:+.1}초)",
+            pair.a_video_id,
+            pair.b_video_id,
+            pair.offset_sec,
+            pair.matches,
+            pair.residual_sec.unwrap_or(0.0)
+        );
+    }
+
+    println!("\n=== 공통 순간 ({}개) ===", output.moments.len());
+    for (streams, count) in stream_count_histogram(&output.moments) {
+        println!("VOD {}개: {}개", streams, count);
+    }
+    for moment in &output.moments {
+        println!(
+            "\n[{}] VOD {}개",
+            kst_datetime(moment.aligned_time).format("%Y-%m-%d %H:%M:%S"),
+            moment.streams.len()
+        );
+        for stream in &moment.streams {
+            println!(
+                "  Video ID {}: {} (z={:.2}, {}개/초)",
+                stream.video_id,
+                format_local_time(stream.local_sec),
+                stream.event.peak_z_score,
+                stream.event.peak_count
+            );
+        }
+    }
+}
+
+/// 다시보기 기준 초를 `HH:MM:SS`로 표시합니다.
+fn format_local_time(sec: i64) -> String {
+    let sign = if sec < 0 { "-" } else { "" };
+    let sec = sec.abs();
+    format!(
+        "{}{:02}:{:02}:{:02}",
+        sign,
+        sec / 3600,
+        sec % 3600 / 60,
+        sec % 60
+    )
+}
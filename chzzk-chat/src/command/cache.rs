@@ -0,0 +1,152 @@
+use std::fs;
+
+use color_eyre::eyre::{Context, Result};
+use structopt::StructOpt;
+
+use crate::data::chat::cache::{self, CacheEntry, CacheHeader, CacheStatus};
+use crate::data::chat::loader::load_chat_log;
+use crate::utils;
+
+/// 채팅 로그 캐시 관리 옵션
+#[derive(StructOpt, Debug)]
+pub struct CacheOpt {
+    /// 원본 채팅 로그 디렉토리
+    #[structopt(long, default_value = "../chat_logs")]
+    pub chat_logs_dir: String,
+
+    /// 캐시 디렉토리
+    #[structopt(long, default_value = "../chat_logs_cache")]
+    pub cache_dir: String,
+
+    #[structopt(subcommand)]
+    pub action: CacheAction,
+}
+
+#[derive(StructOpt, Debug)]
+pub enum CacheAction {
+    /// 캐시 파일 목록과 상태 출력 (원본 해시는 수정 시간이 바뀐 경우에만 비교)
+    #[structopt(name = "list")]
+    List,
+
+    /// 모든 캐시의 원본 해시와 본문을 검사
+    #[structopt(name = "verify")]
+    Verify {
+        /// 오래되었거나 손상된 캐시를 다시 만듦
+        #[structopt(long)]
+        rebuild: bool,
+    },
+
+    /// 원본과 맞지 않는 캐시(오래됨, 형식 버전 다름, 손상, 원본 없음) 삭제
+    #[structopt(name = "prune")]
+    Prune {
+        /// 삭제하지 않고 대상만 출력
+        #[structopt(long)]
+        dry_run: bool,
+    },
+}
+
+/// 캐시 관리 모드 실행
+pub fn run_cache(opts: &CacheOpt) -> Result<()> {
+    match &opts.action {
+        CacheAction::List => {
+            let entries = cache::scan_cache_dir(&opts.cache_dir, &opts.chat_logs_dir, false)?;
+            for entry in &entries {
+                println!(
+                    "{:>12} {:>9} {:>12} {:?}",
+                    entry.video_id,
+                    entry.status.as_str(),
+                    entry.cache_size,
+                    entry.cache_path
+                );
+            }
+            print_summary(&entries);
+        }
+        CacheAction::Verify { rebuild } => {
+            let entries = cache::scan_cache_dir(&opts.cache_dir, &opts.chat_logs_dir, true)?;
+            for entry in entries.iter().filter(|e| e.status != CacheStatus::Fresh) {
+                println!(
+                    "{:>12} {:>9} {:?}",
+                    entry.video_id,
+                    entry.status.as_str(),
+                    entry.cache_path
+                );
+            }
+            print_summary(&entries);
+
+            if *rebuild {
+                let mut rebuilt = 0;
+                // 원본이 없는 캐시는 다시 만들 수 없으므로 prune 대상
+                let targets = entries.iter().filter(|e| {
+                    matches!(
+                        e.status,
+                        CacheStatus::Stale | CacheStatus::Outdated | CacheStatus::Corrupt
+                    ) && e.source_path.exists()
+                });
+                for entry in targets {
+                    rebuild_cache(&opts.cache_dir, entry)?;
+                    rebuilt += 1;
+                }
+                utils::log(format!("캐시 {}개를 다시 만들었습니다", rebuilt));
+            }
+        }
+        CacheAction::Prune { dry_run } => {
+            let entries = cache::scan_cache_dir(&opts.cache_dir, &opts.chat_logs_dir, false)?;
+            let mut pruned = 0;
+            let mut freed = 0;
+            for entry in entries.iter().filter(|e| e.status != CacheStatus::Fresh) {
+                println!(
+                    "{} {:?} ({})",
+                    if *dry_run { "would remove" } else { "remove" },
+                    entry.cache_path,
+                    entry.status.as_str()
+                );
+                if !dry_run {
+                    fs::remove_file(&entry.cache_path).with_context(|| {
+                        format!("Failed to remove cache file: {:?}", entry.cache_path)
+                    })?;
+                }
+                pruned += 1;
+                freed += entry.cache_size;
+            }
+            utils::log(format!(
+                "캐시 정리{}: {}개, {:.1} MiB",
+                if *dry_run { " (dry run)" } else { "" },
+                pruned,
+                freed as f64 / (1024.0 * 1024.0)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// 원본 로그를 다시 파싱해서 캐시를 새로 만듭니다.
+/// 쓰다 남은 임시 파일이었으면 새 캐시를 만든 뒤 지웁니다.
+fn rebuild_cache(cache_dir: &str, entry: &CacheEntry) -> Result<()> {
+    let header = CacheHeader::from_source(&entry.source_path)?;
+    let (chat_log, _) = load_chat_log(&entry.source_path)?;
+    let cache_path = cache::cache_file_path(cache_dir, entry.video_id);
+    cache::save_cached_chat_log(&chat_log, &header, &cache_path)?;
+
+    if entry.cache_path != cache_path {
+        fs::remove_file(&entry.cache_path).ok();
+    }
+    Ok(())
+}
+
+/// 상태별 캐시 수를 출력합니다.
+fn print_summary(entries: &[CacheEntry]) {
+    let count = |status: CacheStatus| entries.iter().filter(|e| e.status == status).count();
+    let total_size: u64 = entries.iter().map(|e| e.cache_size).sum();
+
+    utils::log(format!(
+        "캐시 {}개 ({:.1} MiB): fresh={} stale={} outdated={} corrupt={} orphan={}",
+        entries.len(),
+        total_size as f64 / (1024.0 * 1024.0),
+        count(CacheStatus::Fresh),
+        count(CacheStatus::Stale),
+        count(CacheStatus::Outdated),
+        count(CacheStatus::Corrupt),
+        count(CacheStatus::Orphan)
+    ));
+}
@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Context, Result};
+use structopt::StructOpt;
+
+use crate::data::chat::format::{
+    read_chat_logs, write_text_chat_log, ChatLogFormat, ChatRecordWriter,
+};
+use crate::data::chat::loader::{
+    extract_video_id_from_filename, is_chat_log_filename, open_chat_log, ParseStats,
+};
+use crate::data::models::ChatLog;
+use crate::utils;
+
+/// 채팅 로그 형식 변환 옵션
+#[derive(StructOpt, Debug)]
+pub struct ConvertOpt {
+    /// 입력 채팅 로그 파일 또는 디렉토리 (텍스트, JSONL, CSV 및 .gz/.zst, 여러 개 지정 가능)
+    #[structopt(required = true)]
+    pub inputs: Vec<PathBuf>,
+
+    /// 출력 디렉토리 (비디오별 `chatLog-{id}.{log,jsonl,csv}`로 저장)
+    #[structopt(long, short)]
+    pub output: PathBuf,
+
+    /// 출력 형식 (text, jsonl, csv)
+    #[structopt(long)]
+    pub to: ChatLogFormat,
+}
+
+/// 형식 변환 모드 실행
+/// 로더가 다시 읽을 수 있도록 비디오마다 `chatLog-{id}.{ext}` 파일 하나로 씁니다.
+pub fn run_convert(opts: &ConvertOpt) -> Result<()> {
+    let inputs = collect_inputs(&opts.inputs)?;
+    utils::log(format!(
+        "채팅 로그 {}개 파일을 {} 형식으로 변환합니다",
+        inputs.len(),
+        opts.to.extension()
+    ));
+
+    fs::create_dir_all(&opts.output)
+        .with_context(|| format!("Failed to create output directory: {:?}", opts.output))?;
+
+    let pb = utils::create_progress_bar(inputs.len() as u64, "Converting chat logs...");
+    let mut stats = ParseStats::default();
+    // 이미 쓴 비디오 (여러 입력에 나뉜 같은 비디오는 이어서 씀)
+    let mut written: HashSet<u64> = HashSet::new();
+    for input in &inputs {
+        let (chat_logs, file_stats) = read_input(input)?;
+        stats.add(file_stats);
+
+        for chat_log in &chat_logs {
+            let append = !written.insert(chat_log.video_id);
+            write_video(&opts.output, opts.to, chat_log, append)?;
+        }
+        pb.inc(1);
+    }
+    pb.finish_with_message("Chat logs converted!");
+
+    utils::log(format!(
+        "변환 완료: 비디오 {}개, 메시지 {}개, 형식 오류 {}줄 -> {:?}",
+        written.len(),
+        stats.parsed,
+        stats.malformed,
+        opts.output
+    ));
+    Ok(())
+}
+
+/// 비디오 하나의 채팅을 `output_dir/chatLog-{id}.{ext}`에 씁니다. `append`면 기존 파일 뒤에 이어 씁니다.
+fn write_video(
+    output_dir: &Path,
+    to: ChatLogFormat,
+    chat_log: &ChatLog,
+    append: bool,
+) -> Result<()> {
+    let path = output_dir.join(format!("chatLog-{}.{}", chat_log.video_id, to.extension()));
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(&path)
+        .with_context(|| format!("Failed to create chat log: {:?}", path))?;
+    let file = BufWriter::new(file);
+
+    match to {
+        ChatLogFormat::Text => write_text_chat_log(chat_log, file),
+        _ => {
+            let mut writer = if append {
+                ChatRecordWriter::appending(file, to)?
+            } else {
+                ChatRecordWriter::new(file, to)?
+            };
+            writer.write_chat_log(chat_log)?;
+            writer.flush()
+        }
+    }
+}
+
+/// 입력 경로 목록을 파일 목록으로 펼칩니다. 디렉토리는 안의 채팅 로그 파일을 이름 순으로 사용합니다.
+fn collect_inputs(inputs: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for input in inputs {
+        if !input.is_dir() {
+            files.push(input.clone());
+            continue;
+        }
+
+        let mut dir_files: Vec<PathBuf> = fs::read_dir(input)
+            .with_context(|| format!("Failed to read directory: {:?}", input))?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(is_chat_log_filename)
+            })
+            .collect();
+        dir_files.sort();
+        files.append(&mut dir_files);
+    }
+    Ok(files)
+}
+
+/// 입력 파일 하나를 확장자에 맞는 형식으로 읽습니다.
+/// 텍스트 파일은 파일 이름(`chatLog-{id}.log`)에서 video_id를 가져옵니다.
+fn read_input(path: &Path) -> Result<(Vec<ChatLog>, ParseStats)> {
+    let format = ChatLogFormat::from_path(path).unwrap_or(ChatLogFormat::Text);
+    let video_id = match format {
+        ChatLogFormat::Text => {
+            let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            Some(
+                extract_video_id_from_filename(filename)
+                    .ok_or_else(|| eyre!("Failed to extract video_id from filename: {:?}", path))?,
+            )
+        }
+        _ => None,
+    };
+
+    read_chat_logs(open_chat_log(path)?, format, video_id)
+        .with_context(|| format!("Failed to read chat log file: {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::chat::loader::{load_all_chat_logs, load_chat_log};
+
+    #[test]
+    fn test_convert_output_loads_back() {
+        let dir = std::env::temp_dir().join(format!("chzzk-chat-convert-{}", std::process::id()));
+        let input = dir.join("input");
+        fs::create_dir_all(&input).unwrap();
+
+        // 비디오 두 개가 섞인 JSONL과, 같은 비디오 1의 텍스트 로그
+        let combined = input.join("combined.jsonl");
+        fs::write(
+            &combined,
+            concat!(
+                r#"{"video_id":1,"timestamp":"2025-10-24T18:03:15+09:00","nickname":"a","message":"하나","user_id":"u1"}"#,
+                "\n",
+                r#"{"video_id":2,"timestamp":"2025-10-24T18:03:16+09:00","nickname":"b","message":"둘","user_id":"u2"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        fs::write(
+            input.join("chatLog-1.log"),
+            "[2025-10-24 18:03:17] c: 셋 (u3)\n",
+        )
+        .unwrap();
+
+        for to in [
+            ChatLogFormat::Jsonl,
+            ChatLogFormat::Csv,
+            ChatLogFormat::Text,
+        ] {
+            let output = dir.join(to.extension());
+            run_convert(&ConvertOpt {
+                inputs: vec![combined.clone(), input.join("chatLog-1.log")],
+                output: output.clone(),
+                to,
+            })
+            .unwrap();
+
+            let store = load_all_chat_logs(&output, output.join("cache").to_str()).unwrap();
+            assert_eq!(store.len(), 2);
+            assert_eq!(store.get(1).unwrap().len(), 2);
+            assert_eq!(store.get(2).unwrap().message(0), "둘");
+        }
+
+        // 파일 이름과 다른 video_id의 줄은 건너뛴 줄로 셈
+        let mismatched = input.join("chatLog-1.jsonl");
+        fs::copy(&combined, &mismatched).unwrap();
+        let (chat_log, stats) = load_chat_log(&mismatched).unwrap();
+        assert_eq!(chat_log.messages.len(), 1);
+        assert_eq!((stats.parsed, stats.skipped), (1, 1));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
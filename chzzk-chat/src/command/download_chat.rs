@@ -0,0 +1,509 @@
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{Duration as ChronoDuration, FixedOffset, Utc};
+use color_eyre::eyre::{Context, Result};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use structopt::StructOpt;
+use tokio::time;
+
+use crate::api::client::{ChzzkClient, CHZZK_CLIENT};
+use crate::api::recorder::{kst_from_millis, nickname_from_profile, ChatLogWriter};
+use crate::data::chat::loader::chat_log_source_path;
+use crate::data::utils::parse_replay_time;
+use crate::utils;
+
+/// 페이지 요청 사이 대기 시간
+const PAGE_DELAY: Duration = Duration::from_millis(200);
+
+/// 다시보기 채팅 다운로드 옵션
+#[derive(StructOpt, Debug)]
+pub struct DownloadChatOpt {
+    /// 다운로드할 다시보기 Video ID (여러 개 지정 가능)
+    #[structopt(long)]
+    pub video_id: Vec<u64>,
+
+    /// 채널 ID (채널 및 리플레이 데이터에 있는 다시보기를 모두 다운로드, 여러 개 지정 가능)
+    #[structopt(long)]
+    pub channel: Vec<String>,
+
+    /// --channel 사용 시 최근 N일 다시보기만 다운로드
+    #[structopt(long)]
+    pub recent_days: Option<u64>,
+
+    /// 채널 및 리플레이 데이터 파일 경로 (여러 개 지정 가능)
+    #[structopt(long)]
+    pub files: Vec<String>,
+
+    /// 채팅 로그 저장 디렉토리
+    #[structopt(long, default_value = "../chat_logs")]
+    pub out_dir: String,
+
+    /// 동시에 다운로드할 다시보기 수
+    #[structopt(long, default_value = "4")]
+    pub concurrency: usize,
+
+    /// 이미 다운로드가 끝난 다시보기도 다시 다운로드
+    #[structopt(long)]
+    pub force: bool,
+}
+
+/// 다운로드 진행 상태 (재시작 시 이어받기용)
+#[derive(Debug, Serialize, Deserialize)]
+struct DownloadProgress {
+    /// 다음에 요청할 playerMessageTime
+    next_player_message_time: i64,
+    /// 이 시점까지 기록된 로그 파일 크기 (bytes)
+    log_len: u64,
+}
+
+/// 다시보기 하나의 다운로드 결과
+#[derive(Debug)]
+pub enum DownloadStatus {
+    /// 다운로드 완료 (이번 실행에서 기록한 메시지 수)
+    Completed(usize),
+    /// 이미 다운로드가 끝나 있어 건너뜀
+    Skipped,
+}
+
+fn log_path(out_dir: &Path, video_no: u64) -> PathBuf {
+    out_dir.join(format!("chatLog-{}.log", video_no))
+}
+
+fn progress_path(out_dir: &Path, video_no: u64) -> PathBuf {
+    out_dir.join(format!("chatLog-{}.log.progress", video_no))
+}
+
+fn load_progress(path: &Path) -> Result<Option<DownloadProgress>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read progress file: {:?}", path))?;
+    let progress = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse progress file: {:?}", path))?;
+    Ok(Some(progress))
+}
+
+fn save_progress(path: &Path, progress: &DownloadProgress) -> Result<()> {
+    fs::write(path, serde_json::to_string(progress)?)
+        .with_context(|| format!("Failed to write progress file: {:?}", path))
+}
+
+/// 다시보기 채팅을 `chatLog-{video_no}.log` 파일로 다운로드합니다.
+///
+/// 페이지마다 진행 상태를 `chatLog-{video_no}.log.progress` 파일에 저장하므로
+/// 중간에 중단되어도 다음 실행에서 마지막 `playerMessageTime`부터 이어받습니다.
+/// 진행 상태 파일 없이 로그 파일만 있으면 다운로드가 끝난 것으로 간주합니다.
+pub async fn download_video_chat(
+    client: &ChzzkClient,
+    video_no: u64,
+    out_dir: &Path,
+    force: bool,
+) -> Result<DownloadStatus> {
+    fs::create_dir_all(out_dir)
+        .with_context(|| format!("Failed to create output directory: {:?}", out_dir))?;
+
+    let log_path = log_path(out_dir, video_no);
+    let progress_path = progress_path(out_dir, video_no);
+
+    if force {
+        fs::remove_file(&log_path).ok();
+        fs::remove_file(&progress_path).ok();
+    }
+
+    // 압축해서 보관 중인 로그(`.log.gz`, `.log.zst`)도 다운로드가 끝난 것으로 간주
+    let progress = load_progress(&progress_path)?;
+    if !force && progress.is_none() && chat_log_source_path(out_dir, video_no).exists() {
+        return Ok(DownloadStatus::Skipped);
+    }
+
+    // 마지막으로 저장된 지점 이후에 기록된 내용은 버리고 이어받기
+    let mut next_player_message_time = 0;
+    if let Some(progress) = progress.filter(|_| log_path.exists()) {
+        OpenOptions::new()
+            .write(true)
+            .open(&log_path)
+            .and_then(|file| file.set_len(progress.log_len))
+            .with_context(|| format!("Failed to truncate chat log: {:?}", log_path))?;
+        next_player_message_time = progress.next_player_message_time;
+    } else {
+        fs::remove_file(&log_path).ok();
+    }
+
+    save_progress(
+        &progress_path,
+        &DownloadProgress {
+            next_player_message_time,
+            log_len: fs::metadata(&log_path).map(|m| m.len()).unwrap_or(0),
+        },
+    )?;
+
+    let mut writer = ChatLogWriter::open(out_dir, video_no)?;
+
+    loop {
+        let (chats, next_page) = client
+            .fetch_video_chats(video_no, next_player_message_time)
+            .await?;
+
+        // 다음 페이지 시간이 앞으로 가지 않으면 같은 페이지를 계속 받게 되므로 기록하지 않고 멈춤
+        // 진행 상태 파일은 남겨 두어 다음 실행에서 이어받음
+        if let Some(next) = next_page.filter(|_| !chats.is_empty()) {
+            if next <= next_player_message_time {
+                return Err(color_eyre::eyre::eyre!(
+                    "Video {} chat paging did not advance: next playerMessageTime {} <= {}",
+                    video_no,
+                    next,
+                    next_player_message_time
+                ));
+            }
+        }
+
+        for chat in &chats {
+            let Some(timestamp) = kst_from_millis(chat.message_time) else {
+                continue;
+            };
+            if chat.content.trim().is_empty() {
+                continue;
+            }
+            let nickname = nickname_from_profile(chat.profile.as_deref());
+            writer.write_chat(&timestamp, &nickname, &chat.content, &chat.user_id_hash)?;
+        }
+        writer.flush()?;
+
+        match next_page {
+            Some(next) if !chats.is_empty() => {
+                next_player_message_time = next;
+                save_progress(
+                    &progress_path,
+                    &DownloadProgress {
+                        next_player_message_time,
+                        log_len: fs::metadata(&log_path)?.len(),
+                    },
+                )?;
+            }
+            _ => break,
+        }
+
+        time::sleep(PAGE_DELAY).await;
+    }
+
+    fs::remove_file(&progress_path)
+        .with_context(|| format!("Failed to remove progress file: {:?}", progress_path))?;
+
+    Ok(DownloadStatus::Completed(writer.written()))
+}
+
+/// 채널 및 리플레이 데이터에서 채널들의 다시보기 번호를 모읍니다.
+fn collect_channel_video_nos(opts: &DownloadChatOpt) -> Result<Vec<u64>> {
+    let channels = crate::load_channels(&opts.files)?;
+
+    let cutoff_date = opts.recent_days.map(|days| {
+        Utc::now().with_timezone(&FixedOffset::east_opt(9 * 3600).unwrap())
+            - ChronoDuration::days(days as i64)
+    });
+
+    let mut video_nos = Vec::new();
+    for channel_id in &opts.channel {
+        let channel = channels
+            .iter()
+            .find(|ch| ch.channel_id == *channel_id)
+            .ok_or_else(|| color_eyre::eyre::eyre!("Channel ID {} not found", channel_id))?;
+
+        video_nos.extend(
+            channel
+                .replays
+                .iter()
+                .filter(|replay| match cutoff_date {
+                    Some(cutoff) => parse_replay_time(&replay.start)
+                        .map(|start| start >= cutoff)
+                        .unwrap_or(false),
+                    None => true,
+                })
+                .map(|replay| replay.video_no),
+        );
+    }
+
+    Ok(video_nos)
+}
+
+/// 다시보기 채팅 다운로드 모드 실행
+pub async fn run_download_chat(opts: &DownloadChatOpt) -> Result<()> {
+    let mut video_nos = opts.video_id.clone();
+    if !opts.channel.is_empty() {
+        video_nos.extend(collect_channel_video_nos(opts)?);
+    }
+
+    let mut seen = HashSet::new();
+    video_nos.retain(|video_no| seen.insert(*video_no));
+
+    if video_nos.is_empty() {
+        return Err(color_eyre::eyre::eyre!(
+            "다운로드할 다시보기가 없습니다. --video-id 또는 --channel을 지정해주세요."
+        ));
+    }
+
+    let out_dir = PathBuf::from(&opts.out_dir);
+    utils::log(format!(
+        "다시보기 채팅 다운로드 시작: {}개 비디오, 저장 위치: {:?}",
+        video_nos.len(),
+        out_dir
+    ));
+
+    let pb = utils::create_progress_bar(video_nos.len() as u64, "Downloading chat logs...");
+
+    let results: Vec<(u64, Result<DownloadStatus>)> = stream::iter(video_nos)
+        .map(|video_no| {
+            let out_dir = &out_dir;
+            async move {
+                (
+                    video_no,
+                    download_video_chat(&CHZZK_CLIENT, video_no, out_dir, opts.force).await,
+                )
+            }
+        })
+        .buffer_unordered(opts.concurrency.max(1))
+        .inspect(|_| pb.inc(1))
+        .collect()
+        .await;
+
+    pb.finish_with_message("Chat logs downloaded!");
+
+    let mut completed = 0;
+    let mut skipped = 0;
+    let mut failed = 0;
+    for (video_no, result) in results {
+        match result {
+            Ok(DownloadStatus::Completed(count)) => {
+                completed += 1;
+                utils::log(format!("Video {}: {}개 메시지 저장", video_no, count));
+            }
+            Ok(DownloadStatus::Skipped) => skipped += 1,
+            Err(e) => {
+                failed += 1;
+                utils::log(format!("Video {} 다운로드 실패: {:?}", video_no, e));
+            }
+        }
+    }
+
+    utils::log(format!(
+        "다운로드 완료: 완료 {}개, 건너뜀 {}개, 실패 {}개",
+        completed, skipped, failed
+    ));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::ClientConfig;
+    use crate::api::mock_server::{video_chats_response, MockChzzk};
+    use crate::data::chat::loader::load_chat_log;
+
+    const VIDEO_NO: u64 = 42;
+
+    fn chats_path(player_message_time: i64) -> String {
+        format!(
+            "/service/v1/videos/{}/chats?playerMessageTime={}",
+            VIDEO_NO, player_message_time
+        )
+    }
+
+    /// 0 → 1000 → 2000 순서로 이어지는 세 페이지
+    async fn paged_server() -> MockChzzk {
+        MockChzzk::start(
+            vec![
+                (
+                    chats_path(0),
+                    video_chats_response(
+                        &[
+                            (1_761_296_590_000, "user-1", "첫 페이지"),
+                            (1_761_296_591_000, "user-2", "ㅋㅋㅋ"),
+                        ],
+                        Some(1000),
+                    ),
+                ),
+                (
+                    chats_path(1000),
+                    video_chats_response(
+                        &[(1_761_296_592_000, "user-1", "둘째 페이지")],
+                        Some(2000),
+                    ),
+                ),
+                (
+                    chats_path(2000),
+                    video_chats_response(&[(1_761_296_593_000, "user-3", "마지막")], None),
+                ),
+            ],
+            Vec::new(),
+            Vec::new(),
+        )
+        .await
+    }
+
+    fn test_client(server: &MockChzzk) -> ChzzkClient {
+        ChzzkClient::new(ClientConfig {
+            api_base_url: Some(server.endpoints().api_base_url),
+            max_retries: 0,
+            ..ClientConfig::default()
+        })
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "chzzk-chat-download-{}-{}",
+            name,
+            std::process::id()
+        ));
+        fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    fn messages(out_dir: &Path) -> Vec<String> {
+        load_chat_log(log_path(out_dir, VIDEO_NO))
+            .unwrap()
+            .0
+            .messages
+            .into_iter()
+            .map(|msg| msg.message)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_download_follows_pages() {
+        let server = paged_server().await;
+        let dir = test_dir("pages");
+
+        let status = download_video_chat(&test_client(&server), VIDEO_NO, &dir, false)
+            .await
+            .unwrap();
+
+        assert!(matches!(status, DownloadStatus::Completed(4)));
+        assert_eq!(
+            messages(&dir),
+            vec!["첫 페이지", "ㅋㅋㅋ", "둘째 페이지", "마지막"]
+        );
+        assert!(!progress_path(&dir, VIDEO_NO).exists());
+
+        // 진행 상태 파일이 없으면 다운로드가 끝난 것으로 간주
+        let status = download_video_chat(&test_client(&server), VIDEO_NO, &dir, false)
+            .await
+            .unwrap();
+        assert!(matches!(status, DownloadStatus::Skipped));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_download_resumes() {
+        let server = paged_server().await;
+        let dir = test_dir("resume");
+        server.fail_next(&chats_path(1000), &[(500, None)]);
+
+        assert!(
+            download_video_chat(&test_client(&server), VIDEO_NO, &dir, false)
+                .await
+                .is_err()
+        );
+        let progress = load_progress(&progress_path(&dir, VIDEO_NO))
+            .unwrap()
+            .unwrap();
+        assert_eq!(progress.next_player_message_time, 1000);
+        assert_eq!(messages(&dir), vec!["첫 페이지", "ㅋㅋㅋ"]);
+
+        let status = download_video_chat(&test_client(&server), VIDEO_NO, &dir, false)
+            .await
+            .unwrap();
+
+        // 이어받은 두 페이지만 새로 기록
+        assert!(matches!(status, DownloadStatus::Completed(2)));
+        assert_eq!(
+            messages(&dir),
+            vec!["첫 페이지", "ㅋㅋㅋ", "둘째 페이지", "마지막"]
+        );
+        assert!(!progress_path(&dir, VIDEO_NO).exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_download_stops_when_paging_does_not_advance() {
+        // 1000 페이지가 다시 1000을 다음 페이지로 돌려줌
+        let server = MockChzzk::start(
+            vec![
+                (
+                    chats_path(0),
+                    video_chats_response(&[(1_761_296_590_000, "user-1", "첫 페이지")], Some(1000)),
+                ),
+                (
+                    chats_path(1000),
+                    video_chats_response(
+                        &[(1_761_296_592_000, "user-1", "같은 페이지")],
+                        Some(1000),
+                    ),
+                ),
+            ],
+            Vec::new(),
+            Vec::new(),
+        )
+        .await;
+        let dir = test_dir("stuck");
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            download_video_chat(&test_client(&server), VIDEO_NO, &dir, false),
+        )
+        .await
+        .expect("download did not stop");
+        assert!(result.is_err());
+
+        // 앞으로 가지 않은 페이지는 기록하지 않고, 이어받을 수 있도록 진행 상태를 남김
+        assert_eq!(messages(&dir), vec!["첫 페이지"]);
+        let progress = load_progress(&progress_path(&dir, VIDEO_NO))
+            .unwrap()
+            .unwrap();
+        assert_eq!(progress.next_player_message_time, 1000);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_resume_truncates_partial_last_line() {
+        let server = paged_server().await;
+        let dir = test_dir("truncate");
+        server.fail_next(&chats_path(1000), &[(500, None)]);
+        assert!(
+            download_video_chat(&test_client(&server), VIDEO_NO, &dir, false)
+                .await
+                .is_err()
+        );
+
+        // 진행 상태 저장 이후 쓰다 만 줄
+        let log = log_path(&dir, VIDEO_NO);
+        let saved_len = fs::metadata(&log).unwrap().len();
+        {
+            use std::io::Write;
+            let mut file = OpenOptions::new().append(true).open(&log).unwrap();
+            write!(file, "[2025-10-24 18:03:13] 끊긴").unwrap();
+        }
+        assert!(fs::metadata(&log).unwrap().len() > saved_len);
+
+        download_video_chat(&test_client(&server), VIDEO_NO, &dir, false)
+            .await
+            .unwrap();
+
+        let content = fs::read_to_string(&log).unwrap();
+        assert!(!content.contains("끊긴"));
+        assert!(content.ends_with('\n'));
+        assert_eq!(
+            messages(&dir),
+            vec!["첫 페이지", "ㅋㅋㅋ", "둘째 페이지", "마지막"]
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+use color_eyre::eyre::Result;
+use structopt::StructOpt;
+
+use crate::data::parquet_export::export_chat_parquet;
+use crate::utils;
+use crate::{load_channels_and_chat_logs, AnalysisChatOpt};
+
+/// Parquet 내보내기 옵션
+#[derive(StructOpt, Debug)]
+pub struct ExportParquetOpt {
+    /// 채널 및 리플레이 데이터 파일 경로 (여러 개 지정 가능)
+    #[structopt(long)]
+    pub files: Vec<String>,
+
+    /// 출력 디렉토리 (`channel_id=.../date=.../part-{video_id}.parquet`)
+    #[structopt(long, default_value = "../parquet/chats")]
+    pub output: PathBuf,
+}
+
+/// Parquet 내보내기 모드 실행
+pub fn run_export_parquet(opts: &ExportParquetOpt) -> Result<()> {
+    let (channels, chat_logs) = load_channels_and_chat_logs(&AnalysisChatOpt {
+        files: opts.files.clone(),
+        ..Default::default()
+    })?;
+
+    let summary = export_chat_parquet(&chat_logs, &channels, &opts.output)?;
+    utils::log(format!(
+        "Parquet 내보내기 완료: 비디오 {}개, 파일 {}개, 메시지 {}개 -> {:?}",
+        summary.videos, summary.files, summary.rows, opts.output
+    ));
+
+    Ok(())
+}
@@ -4,8 +4,11 @@ use std::fs;
 use std::path::Path;
 
 use crate::data::chat;
+use crate::data::chat::event_config::EventConfigOpt;
+use crate::data::chat::event_summary::{EventSummarizer, EventSummary};
+use crate::data::chat::loader::load_donation_times;
+use crate::data::chat::store::ChatColumns;
 use crate::data::chat::EventInterval;
-use crate::data::models::ChatLog;
 use crate::data::utils;
 use crate::utils as crate_utils;
 use chrono::{Duration as ChronoDuration, FixedOffset, Utc};
@@ -22,6 +25,8 @@ pub enum ExtractEventOpt {
         /// Video ID
         #[structopt(long)]
         video_id: u64,
+        #[structopt(flatten)]
+        config: EventConfigOpt,
     },
     /// Channel의 최근 N일 이벤트 추출
     #[structopt(name = "channel")]
@@ -32,6 +37,8 @@ pub enum ExtractEventOpt {
         /// 최근 N일
         #[structopt(long)]
         recent_days: u64,
+        #[structopt(flatten)]
+        config: EventConfigOpt,
     },
     /// 구독자 수 상위 N명 채널의 최근 M일 이벤트 추출
     #[structopt(name = "top")]
@@ -45,6 +52,8 @@ pub enum ExtractEventOpt {
         /// 각 채널당 최근 비디오 최대 개수 (기본값: 100)
         #[structopt(long, default_value = "100")]
         max_videos_per_channel: u64,
+        #[structopt(flatten)]
+        config: EventConfigOpt,
     },
 }
 
@@ -54,22 +63,29 @@ pub fn run_extract_event(opts: &ExtractEventOpt) -> Result<()> {
     use crate::AnalysisChatOpt;
 
     match opts {
-        ExtractEventOpt::Video { video_id } => {
+        ExtractEventOpt::Video { video_id, config } => {
+            let settings = config.resolve()?;
+
             // 채널과 채팅 로그 로드
             let (_, chat_logs) = load_channels_and_chat_logs(&AnalysisChatOpt::default())?;
 
             // 특정 video_id 찾기
-            let chat_log = chat_logs
-                .iter()
-                .find(|log| log.video_id == *video_id)
-                .ok_or_else(|| {
-                    color_eyre::eyre::eyre!("Video ID {} not found in chat logs", video_id)
-                })?;
+            let chat_log = chat_logs.get(*video_id).ok_or_else(|| {
+                color_eyre::eyre::eyre!("Video ID {} not found in chat logs", video_id)
+            })?;
 
             // 이벤트 탐지
-            let event_result = chat::detect_event_intervals(chat_log).ok_or_else(|| {
-                color_eyre::eyre::eyre!("Failed to detect events for video {}", video_id)
-            })?;
+            let detection_config = settings.detection_config(chat_log);
+            println!(
+                "Event detection config ({:?} preset, {:.2} messages/sec): {:?}",
+                settings.preset,
+                chat::event_config::chat_rate(chat_log),
+                detection_config
+            );
+            let event_result = chat::detect_event_intervals(chat_log, &detection_config)
+                .ok_or_else(|| {
+                    color_eyre::eyre::eyre!("Failed to detect events for video {}", video_id)
+                })?;
 
             // 이벤트 출력
             chat::print_event_intervals(&event_result);
@@ -80,7 +96,10 @@ pub fn run_extract_event(opts: &ExtractEventOpt) -> Result<()> {
         ExtractEventOpt::Channel {
             channel: channel_id,
             recent_days,
+            config,
         } => {
+            let settings = config.resolve()?;
+
             // 채널과 채팅 로그 로드
             let (channels, chat_logs) = load_channels_and_chat_logs(&AnalysisChatOpt::default())?;
 
@@ -120,6 +139,7 @@ pub fn run_extract_event(opts: &ExtractEventOpt) -> Result<()> {
 
             // 해당 비디오들의 채팅 로그 찾기 및 처리
             let matching_chat_logs: Vec<_> = chat_logs
+                .logs
                 .iter()
                 .filter(|log| recent_video_ids.contains(&log.video_id))
                 .collect();
@@ -139,7 +159,10 @@ pub fn run_extract_event(opts: &ExtractEventOpt) -> Result<()> {
                 println!("\n=== Processing Video ID: {} ===", chat_log.video_id);
 
                 // 이벤트 탐지
-                if let Some(event_result) = chat::detect_event_intervals(chat_log) {
+                let detection_config = settings.detection_config(chat_log);
+                if let Some(event_result) =
+                    chat::detect_event_intervals(chat_log, &detection_config)
+                {
                     // 이벤트 출력
                     chat::print_event_intervals(&event_result);
 
@@ -157,7 +180,10 @@ pub fn run_extract_event(opts: &ExtractEventOpt) -> Result<()> {
             max_followers,
             recent_days,
             max_videos_per_channel,
+            config,
         } => {
+            let settings = config.resolve()?;
+
             // 채널과 채팅 로그 로드
             let (channels, chat_logs) = load_channels_and_chat_logs(&AnalysisChatOpt::default())?;
 
@@ -246,8 +272,9 @@ pub fn run_extract_event(opts: &ExtractEventOpt) -> Result<()> {
             );
 
             // ====== 두 번째 단계: 다시보기들 출력하기 (병렬화) ======
-            // video_id -> ChatLog 매핑 생성 (빠른 조회를 위해)
-            let video_to_chat_log: std::collections::HashMap<u64, &ChatLog> = chat_logs
+            // video_id -> 채팅 로그 매핑 생성 (빠른 조회를 위해)
+            let video_to_chat_log: std::collections::HashMap<u64, &ChatColumns> = chat_logs
+                .logs
                 .iter()
                 .filter(|log| recent_video_ids_set.contains(&log.video_id))
                 .map(|log| (log.video_id, log))
@@ -274,7 +301,9 @@ pub fn run_extract_event(opts: &ExtractEventOpt) -> Result<()> {
                     let chat_log = video_to_chat_log.get(video_id).unwrap();
 
                     // 이벤트 탐지
-                    let result = if let Some(event_result) = chat::detect_event_intervals(chat_log)
+                    let detection_config = settings.detection_config(chat_log);
+                    let result = if let Some(event_result) =
+                        chat::detect_event_intervals(chat_log, &detection_config)
                     {
                         // JSON 파일로 저장
                         match save_event_chats_to_json(chat_log, &event_result) {
@@ -310,10 +339,16 @@ pub fn run_extract_event(opts: &ExtractEventOpt) -> Result<()> {
     Ok(())
 }
 
+/// 이벤트마다 저장할 라벨 수
+const EVENT_LABEL_COUNT: usize = 5;
+
 /// JSON 출력용 이벤트 데이터 구조체
 #[derive(Debug, Serialize, Deserialize)]
 struct EventChatJson {
     event: EventInterval,
+    /// 평소 대비 많이 나온 라벨과 대표 반응
+    #[serde(flatten)]
+    summary: EventSummary,
     messages: Vec<String>,
 }
 
@@ -325,9 +360,9 @@ struct EventChatsJson {
     events: Vec<EventChatJson>,
 }
 
-/// ChatLog와 EventDetectionResult를 받아서 하나의 JSON 파일로 저장합니다.
+/// 채팅 로그와 EventDetectionResult를 받아서 하나의 JSON 파일로 저장합니다.
 fn save_event_chats_to_json(
-    chat_log: &ChatLog,
+    chat_log: &ChatColumns,
     event_result: &chat::EventDetectionResult,
 ) -> Result<()> {
     // JSON 파일명 생성
@@ -346,9 +381,12 @@ fn save_event_chats_to_json(
     let mut _skipped_count = 0;
 
     let mut events_json = Vec::new();
+    // 후원 반응은 채팅 로그 옆의 후원/구독 기록으로 판별
+    let donation_times = load_donation_times("../chat_logs", chat_log.video_id)?;
+    let summarizer = EventSummarizer::new(chat_log, donation_times);
 
     // 각 이벤트마다 처리
-    for (_event_idx, event) in event_result.events.iter().enumerate() {
+    for event in event_result.events.iter() {
         // 이벤트 구간의 절대 시간 계산
         let start_abs = event_result.first_message_time.timestamp() + event.start_sec;
         let end_abs = event_result.first_message_time.timestamp() + event.end_sec;
@@ -358,8 +396,7 @@ fn save_event_chats_to_json(
         if seen_intervals.contains(&interval_key) {
             // ProgressBar를 사용하는 경우 출력을 줄이기 위해 주석 처리
             // println!(
-            //     "Skipped event #{} (duplicate interval: {} ~ {})",
-            //     event_idx + 1,
+            //     "Skipped event (duplicate interval: {} ~ {})",
             //     event.start_sec,
             //     event.end_sec
             // );
@@ -368,27 +405,22 @@ fn save_event_chats_to_json(
         }
         seen_intervals.insert(interval_key);
 
-        // 해당 구간의 메시지 필터링 및 추출
-        let mut message_items: Vec<_> = chat_log
-            .messages
-            .iter()
-            .filter(|msg| {
-                let msg_timestamp = msg.timestamp.timestamp();
-                msg_timestamp >= start_abs && msg_timestamp <= end_abs
-            })
-            .collect();
-
-        // 시간 순으로 정렬
-        message_items.sort_by_key(|msg| msg.timestamp);
-
-        // 메시지 문자열만 추출
-        let messages: Vec<String> = message_items
+        // 해당 구간의 메시지를 시간 순으로 추출
+        let indices = chat_log.indices_between(start_abs, end_abs);
+        let messages: Vec<String> = indices
             .iter()
-            .map(|msg| msg.message.clone())
+            .map(|&i| chat_log.message(i).to_string())
             .collect();
 
         events_json.push(EventChatJson {
             event: event.clone(),
+            summary: summarizer.summarize(
+                chat_log,
+                &indices,
+                start_abs,
+                end_abs,
+                EVENT_LABEL_COUNT,
+            ),
             messages,
         });
 
@@ -2,9 +2,10 @@ use chrono::Utc;
 use color_eyre::eyre::Result;
 use std::io::{self, Write};
 
-use crate::api::client;
+use crate::api::client::CHZZK_CLIENT;
 use crate::api::models::ChannelDetail;
-use crate::data::models::{ChannelWithReplays, ChatLog, Replay};
+use crate::data::chat::store::ChatStore;
+use crate::data::models::{ChannelWithReplays, Replay};
 use crate::data::utils as data_utils;
 use crate::utils;
 
@@ -175,21 +176,15 @@ async fn search_channel_by_name(query: &str) -> Result<Option<(ChannelDetail, St
     // 채널 ID 형식인지 확인 (32자 hex)
     if query.len() == 32 && query.chars().all(|c| c.is_ascii_hexdigit()) {
         // 이미 채널 ID인 경우
-        if let Some(channel) = client::fetch_channel(query).await? {
+        if let Some(channel) = CHZZK_CLIENT.fetch_channel(query).await? {
             // 채널 ID만으로는 이름을 알 수 없으므로, 검색 API로 이름 가져오기
-            let url = "https://api.chzzk.naver.com/service/v1/search/channels";
+            let url = CHZZK_CLIENT.api_url("/service/v1/search/channels");
             let params = [
                 ("keyword", query),
                 ("size", "1"),
                 ("withFirstChannelContent", "false"),
             ];
-            let client = reqwest::Client::new();
-            let resp = client
-                .get(url)
-                .query(&params)
-                .header("User-Agent", "Mozilla")
-                .send()
-                .await?;
+            let resp = CHZZK_CLIENT.get(&url, &params).await?;
 
             let channel_name = if resp.status().is_success() {
                 let json: serde_json::Value = resp.json().await?;
@@ -213,20 +208,14 @@ async fn search_channel_by_name(query: &str) -> Result<Option<(ChannelDetail, St
     }
 
     // 검색 API 호출
-    let url = "https://api.chzzk.naver.com/service/v1/search/channels";
+    let url = CHZZK_CLIENT.api_url("/service/v1/search/channels");
     let params = [
         ("keyword", query),
         ("size", "20"),
         ("withFirstChannelContent", "false"),
     ];
 
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(url)
-        .query(&params)
-        .header("User-Agent", "Mozilla")
-        .send()
-        .await?;
+    let resp = CHZZK_CLIENT.get(&url, &params).await?;
 
     if !resp.status().is_success() {
         return Err(color_eyre::eyre::eyre!("검색 API 오류: {}", resp.status()));
@@ -274,7 +263,7 @@ async fn search_channel_by_name(query: &str) -> Result<Option<(ChannelDetail, St
         .to_string();
 
     // 채널 상세 정보 가져오기
-    if let Some(channel) = client::fetch_channel(&channel_id).await? {
+    if let Some(channel) = CHZZK_CLIENT.fetch_channel(&channel_id).await? {
         Ok(Some((channel, channel_name)))
     } else {
         Ok(None)
@@ -325,13 +314,12 @@ fn list_recent_videos<'a>(
 }
 
 /// 특정 비디오의 채팅 개수 조회
-fn get_chat_count(chat_logs: &[ChatLog], video_no: u64) -> Result<Option<usize>> {
-    let chat_log = chat_logs.iter().find(|log| log.video_id == video_no);
-    Ok(chat_log.map(|log| log.messages.len()))
+fn get_chat_count(chat_logs: &ChatStore, video_no: u64) -> Result<Option<usize>> {
+    Ok(chat_logs.get(video_no).map(|log| log.len()))
 }
 
 /// 모든 데이터 로드
-fn load_all_data() -> Result<(Vec<ChannelWithReplays>, Vec<ChatLog>)> {
+fn load_all_data() -> Result<(Vec<ChannelWithReplays>, ChatStore)> {
     use crate::AnalysisChatOpt;
 
     let opts = AnalysisChatOpt::default();
@@ -0,0 +1,221 @@
+use std::path::Path;
+
+use chrono::Duration as ChronoDuration;
+use color_eyre::eyre::Result;
+use futures::stream::{self, StreamExt};
+use structopt::StructOpt;
+
+use crate::api::client::CHZZK_CLIENT;
+use crate::api::models::ChannelVideo;
+use crate::data::loader::{load_channel_with_replays, merge_replays, save_channel_with_replays};
+use crate::data::models::{ChannelWithReplays, Replay};
+use crate::data::utils::parse_replay_time;
+use crate::utils;
+
+/// 채널 다시보기 목록 수집 옵션
+#[derive(StructOpt, Debug)]
+pub struct ListReplaysOpt {
+    /// 다시보기 목록을 가져올 채널 ID (여러 개 지정 가능)
+    #[structopt(long)]
+    pub channel: Vec<String>,
+
+    /// 채널 및 리플레이 데이터에 있는 모든 채널의 다시보기 목록을 갱신
+    #[structopt(long)]
+    pub all: bool,
+
+    /// 채널 및 리플레이 데이터 파일 경로 (여러 개 지정 가능, 새 채널은 마지막 파일에 추가)
+    #[structopt(long)]
+    pub files: Vec<String>,
+
+    /// 채널당 가져올 최대 페이지 수 (지정하지 않으면 전체)
+    #[structopt(long)]
+    pub max_pages: Option<u32>,
+
+    /// 페이지당 비디오 수
+    #[structopt(long, default_value = "30")]
+    pub page_size: u32,
+
+    /// 동시에 조회할 채널 수
+    #[structopt(long, default_value = "4")]
+    pub concurrency: usize,
+}
+
+/// API 비디오 정보를 `Replay`로 변환합니다.
+/// `publishDate`를 방송 종료 시간으로 보고, 영상 길이만큼 뺀 시간을 시작 시간으로 사용합니다.
+pub fn replay_from_video(video: &ChannelVideo) -> Replay {
+    let start = match (parse_replay_time(&video.publish_date), video.duration) {
+        (Ok(end), Some(duration)) => (end - ChronoDuration::seconds(duration))
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string(),
+        _ => "시작 시간 정보 없음".to_string(),
+    };
+
+    Replay {
+        title: video.video_title.clone(),
+        start,
+        end: video.publish_date.clone(),
+        video_no: video.video_no,
+        thumbnail: video.thumbnail_image_url.clone(),
+        tags: video.tags.clone().unwrap_or_default(),
+        category_ko: video.video_category_value.clone(),
+    }
+}
+
+/// 채널 다시보기 목록 수집 모드 실행
+pub async fn run_list_replays(opts: &ListReplaysOpt) -> Result<()> {
+    let file_paths = crate::channel_files(&opts.files);
+
+    // 파일별로 나눠서 로드 (병합 후 원래 파일에 다시 저장)
+    let mut files: Vec<Vec<ChannelWithReplays>> = Vec::new();
+    for file_path in &file_paths {
+        if Path::new(file_path).exists() {
+            files.push(load_channel_with_replays(file_path)?);
+        } else {
+            utils::log(format!("파일이 없어 새로 생성합니다: {}", file_path));
+            files.push(Vec::new());
+        }
+    }
+
+    let mut channel_ids = opts.channel.clone();
+    if opts.all {
+        channel_ids.extend(
+            files
+                .iter()
+                .flatten()
+                .map(|channel| channel.channel_id.clone()),
+        );
+    }
+    let mut seen = std::collections::HashSet::new();
+    channel_ids.retain(|channel_id| seen.insert(channel_id.clone()));
+
+    if channel_ids.is_empty() {
+        return Err(color_eyre::eyre::eyre!(
+            "다시보기 목록을 가져올 채널이 없습니다. --channel 또는 --all을 지정해주세요."
+        ));
+    }
+
+    utils::log(format!(
+        "다시보기 목록 수집 시작: {}개 채널",
+        channel_ids.len()
+    ));
+
+    let pb = utils::create_progress_bar(channel_ids.len() as u64, "Listing replays...");
+
+    let results: Vec<(String, Result<Vec<ChannelVideo>>)> = stream::iter(channel_ids)
+        .map(|channel_id| async move {
+            let videos = CHZZK_CLIENT
+                .fetch_channel_videos_pages(&channel_id, opts.page_size, opts.max_pages)
+                .await;
+            (channel_id, videos)
+        })
+        .buffer_unordered(opts.concurrency.max(1))
+        .inspect(|_| pb.inc(1))
+        .collect()
+        .await;
+
+    pb.finish_with_message("Replays listed!");
+
+    let mut changed = vec![false; files.len()];
+    let mut total_added = 0;
+    for (channel_id, videos) in results {
+        let videos = match videos {
+            Ok(videos) => videos,
+            Err(e) => {
+                utils::log(format!(
+                    "채널 {} 다시보기 목록 조회 실패: {:?}",
+                    channel_id, e
+                ));
+                continue;
+            }
+        };
+        let replays: Vec<Replay> = videos.iter().map(replay_from_video).collect();
+
+        let position = files.iter().enumerate().find_map(|(file_index, channels)| {
+            channels
+                .iter()
+                .position(|channel| channel.channel_id == channel_id)
+                .map(|channel_index| (file_index, channel_index))
+        });
+
+        let (file_index, added) = match position {
+            Some((file_index, channel_index)) => {
+                let channel = &mut files[file_index][channel_index];
+                (file_index, merge_replays(&mut channel.replays, replays))
+            }
+            None => {
+                let detail = match CHZZK_CLIENT.fetch_channel(&channel_id).await {
+                    Ok(Some(detail)) => detail,
+                    Ok(None) => {
+                        utils::log(format!("채널 {} 정보를 가져오지 못했습니다", channel_id));
+                        continue;
+                    }
+                    Err(e) => {
+                        utils::log(format!("채널 {} 정보 조회 실패: {:?}", channel_id, e));
+                        continue;
+                    }
+                };
+
+                let mut channel = ChannelWithReplays {
+                    name: detail.channel_name.unwrap_or_default(),
+                    follower: detail.follower_count.unwrap_or(0),
+                    channel_id: channel_id.clone(),
+                    image: detail.channel_image_url,
+                    replays: Vec::new(),
+                };
+                let added = merge_replays(&mut channel.replays, replays);
+
+                let file_index = files.len() - 1;
+                files[file_index].push(channel);
+                (file_index, added)
+            }
+        };
+
+        changed[file_index] = true;
+        total_added += added;
+        utils::log(format!(
+            "채널 {}: 다시보기 {}개 조회, {}개 추가",
+            channel_id,
+            videos.len(),
+            added
+        ));
+    }
+
+    for ((file_path, channels), changed) in file_paths.iter().zip(&files).zip(changed) {
+        if changed {
+            save_channel_with_replays(file_path, channels)?;
+            utils::log(format!("저장 완료: {}", file_path));
+        }
+    }
+
+    utils::log(format!("다시보기 목록 수집 완료: {}개 추가", total_added));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_from_video() {
+        let video: ChannelVideo = serde_json::from_str(
+            r#"{
+                "videoNo": 123,
+                "videoTitle": "테스트 방송",
+                "publishDate": "2025-01-01 12:00:00",
+                "duration": 3600,
+                "thumbnailImageUrl": null,
+                "tags": null,
+                "videoCategoryValue": "talk"
+            }"#,
+        )
+        .unwrap();
+
+        let replay = replay_from_video(&video);
+        assert_eq!(replay.video_no, 123);
+        assert_eq!(replay.start, "2025-01-01 11:00:00");
+        assert_eq!(replay.end, "2025-01-01 12:00:00");
+        assert!(replay.tags.is_empty());
+        assert_eq!(replay.category_ko.as_deref(), Some("talk"));
+    }
+}
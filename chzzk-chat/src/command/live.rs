@@ -0,0 +1,252 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::Utc;
+use color_eyre::eyre::Result;
+use structopt::StructOpt;
+use tokio::task::JoinHandle;
+use tokio::time::{self, MissedTickBehavior};
+
+use crate::api;
+use crate::api::live_events::LiveEventConfig;
+use crate::data::chat::event_config::EventConfigOpt;
+use crate::utils::{self, SCRAPING_CHANNELS};
+
+/// 종료 요청 후 세션들이 정리되기를 기다리는 최대 시간
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 실시간 채팅 수집 데몬 옵션
+#[derive(StructOpt, Debug)]
+pub struct LiveOpt {
+    /// 라이브 목록 재스캔 주기 (초)
+    #[structopt(long, default_value = "60")]
+    pub scan_interval: u64,
+
+    /// 최소 동시 시청자 수 (지정하지 않으면 MIN_LIVE_USER 환경 변수 또는 100)
+    #[structopt(long)]
+    pub min_live_user: Option<u64>,
+
+    /// 라이브별 동시 시청자 수 기록 주기 (초, 0이면 기록하지 않음)
+    #[structopt(long, default_value = "60")]
+    pub sample_interval: u64,
+
+    /// A 채널 마지막 채팅 후 B 채널 첫 채팅까지 시청자 이동으로 볼 최대 간격 (초)
+    #[structopt(long, default_value = "600")]
+    pub migration_window: i64,
+
+    /// 시청자 이동 횟수를 묶는 시간 구간 길이 (초)
+    #[structopt(long, default_value = "300")]
+    pub migration_bucket: i64,
+
+    /// A 채널 마지막 채팅 후 A 채널에서 다시 채팅하지 않아야 이동으로 확정하는 최소 시간 (초)
+    #[structopt(long, default_value = "120")]
+    pub migration_min_inactive: i64,
+
+    /// 시청자 이동 집계 결과 JSON 파일 경로 (스캔할 때마다 갱신)
+    #[structopt(long, default_value = "../web/public/viewer_migrations.json")]
+    pub migration_output: String,
+
+    /// Prometheus 형식 `/metrics` 엔드포인트 주소
+    #[structopt(long, default_value = "127.0.0.1:9898")]
+    pub metrics_addr: SocketAddr,
+
+    /// 실시간 이벤트(채팅 급증) 탐지를 끔
+    #[structopt(long)]
+    pub no_live_events: bool,
+
+    /// 이벤트 알림 JSONL 파일 경로 (지정하지 않으면 LIVE_CHAT_LOG_DIR/liveEvents.jsonl)
+    #[structopt(long)]
+    pub event_output: Option<PathBuf>,
+
+    /// 이벤트 알림을 JSON으로 POST할 웹훅 URL
+    #[structopt(long)]
+    pub event_webhook: Option<String>,
+
+    /// 늦게 도착하는 채팅을 기다렸다가 초 단위로 확정하는 시간 (초)
+    #[structopt(long, default_value = "5")]
+    pub event_lateness: i64,
+
+    // 이벤트 탐지 설정 (설정 파일, preset, 파라미터 플래그)
+    #[structopt(flatten)]
+    pub event_config: EventConfigOpt,
+}
+
+/// SIGINT(Ctrl+C) 또는 SIGTERM을 기다립니다.
+async fn wait_for_shutdown_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut sigterm = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            _ = sigterm.recv() => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
+
+    Ok(())
+}
+
+/// 주기적으로 라이브 목록을 스캔하면서 채팅을 계속 수집합니다.
+pub async fn run_live(opts: &LiveOpt) -> Result<()> {
+    let min_live_user = opts
+        .min_live_user
+        .unwrap_or_else(api::min_live_user_from_env);
+
+    utils::log(format!(
+        "실시간 채팅 수집 데몬 시작: scan_interval={}s, sample_interval={}s, min_live_user={}",
+        opts.scan_interval, opts.sample_interval, min_live_user
+    ));
+
+    api::migration::configure(
+        opts.migration_window,
+        opts.migration_bucket,
+        opts.migration_min_inactive,
+        &opts.migration_output,
+    )?;
+
+    api::live_events::configure(if opts.no_live_events {
+        None
+    } else {
+        let config = LiveEventConfig {
+            output: opts
+                .event_output
+                .clone()
+                .unwrap_or_else(api::live_events::live_events_path),
+            webhook: opts.event_webhook.clone(),
+            settings: opts.event_config.resolve()?,
+            lateness_secs: opts.event_lateness,
+        };
+        utils::log(format!(
+            "실시간 이벤트 탐지: preset={:?} output={:?} webhook={}",
+            config.settings.preset,
+            config.output,
+            config.webhook.as_deref().unwrap_or("-")
+        ));
+        Some(config)
+    });
+
+    let metrics_server = match api::metrics::bind_metrics(opts.metrics_addr).await {
+        Ok(listener) => {
+            utils::log(format!(
+                "Metrics endpoint: http://{}/metrics",
+                opts.metrics_addr
+            ));
+            Some(tokio::spawn(api::metrics::serve_metrics(listener)))
+        }
+        Err(e) => {
+            utils::log(format!("Metrics endpoint disabled: {:?}", e));
+            None
+        }
+    };
+
+    let mut sessions: Vec<JoinHandle<()>> = Vec::new();
+    let mut scan_interval = time::interval(Duration::from_secs(opts.scan_interval.max(1)));
+    scan_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let sampling = opts.sample_interval > 0;
+    // 샘플 주기 안에 스캔하며 가져온 라이브 목록은 다시 요청하지 않음
+    let sample_max_age = Duration::from_secs(opts.sample_interval);
+    let mut sample_interval = time::interval(Duration::from_secs(opts.sample_interval.max(1)));
+    sample_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let shutdown_signal = wait_for_shutdown_signal();
+    tokio::pin!(shutdown_signal);
+
+    loop {
+        tokio::select! {
+            _ = scan_interval.tick() => {
+                // 종료된 세션 정리
+                let before = sessions.len();
+                sessions.retain(|handle| !handle.is_finished());
+                let reaped = before - sessions.len();
+
+                let scan_started = time::Instant::now();
+                let scanned = api::scan_channels(min_live_user).await;
+                api::metrics::METRICS.set_last_scan_duration(scan_started.elapsed());
+
+                match scanned {
+                    Ok(mut started) => {
+                        utils::log(format!(
+                            "Scan done: started={} reaped={}",
+                            started.len(),
+                            reaped
+                        ));
+                        sessions.append(&mut started);
+                    }
+                    Err(e) => utils::log(format!("Scan failed: {:?}", e)),
+                }
+
+                report_scraping_channels();
+                save_migrations(&opts.migration_output);
+            }
+
+            _ = sample_interval.tick(), if sampling => {
+                match api::viewer_sampler::sample_viewers(min_live_user, sample_max_age).await {
+                    Ok(count) => utils::log(format!("Viewer samples recorded: {}", count)),
+                    Err(e) => utils::log(format!("Viewer sampling failed: {:?}", e)),
+                }
+            }
+
+            result = &mut shutdown_signal => {
+                result?;
+                utils::log("종료 신호 수신, 모든 세션을 정리합니다...");
+                break;
+            }
+        }
+    }
+
+    utils::request_shutdown();
+
+    let remaining = sessions.len();
+    let join_all = futures::future::join_all(sessions);
+    if time::timeout(SHUTDOWN_TIMEOUT, join_all).await.is_err() {
+        utils::log(format!(
+            "{}초 안에 종료되지 않은 세션이 있습니다. scrapingChannels={}",
+            SHUTDOWN_TIMEOUT.as_secs(),
+            SCRAPING_CHANNELS.len()
+        ));
+    } else {
+        utils::log(format!("{}개 세션 종료 완료", remaining));
+    }
+
+    save_migrations(&opts.migration_output);
+
+    // 세션이 마지막으로 보낸 이벤트 알림까지 기록하고 보냄
+    if time::timeout(SHUTDOWN_TIMEOUT, api::live_events::shutdown())
+        .await
+        .is_err()
+    {
+        utils::log("이벤트 알림을 모두 보내지 못하고 종료합니다");
+    }
+
+    if let Some(metrics_server) = metrics_server {
+        metrics_server.abort();
+    }
+
+    Ok(())
+}
+
+/// 시청자 이동 집계 결과를 저장합니다.
+fn save_migrations(path: &str) {
+    match api::migration::save_migrations(path, Utc::now().timestamp_millis()) {
+        Ok(flows) => utils::log(format!("Viewer migrations saved: flows={}", flows)),
+        Err(e) => utils::log(format!("Viewer migration save failed: {:?}", e)),
+    }
+}
+
+/// 현재 스크래핑 중인 채널 목록을 출력합니다.
+fn report_scraping_channels() {
+    let mut channels: Vec<String> = SCRAPING_CHANNELS.iter().map(|c| c.clone()).collect();
+    channels.sort();
+
+    utils::log(format!(
+        "scrapingChannels={} [{}]",
+        channels.len(),
+        channels.join(", ")
+    ));
+}
@@ -1,2 +1,11 @@
+pub mod align_events;
+pub mod cache;
+pub mod convert;
+pub mod download_chat;
+pub mod export_parquet;
 pub mod extract_event;
 pub mod interactive;
+pub mod list_replays;
+pub mod live;
+pub mod similarity_report;
+pub mod tune_events;
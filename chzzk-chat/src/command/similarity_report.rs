@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::Result;
+use rayon::prelude::*;
+use structopt::StructOpt;
+
+use crate::data::chat::minhash::{ApproxError, LshParams, SimilarityMode};
+use crate::data::chat::replay_cluster::ReplayCluster;
+use crate::data::chat::{cluster_similar_replays, filter_chat_logs_by_user_count};
+use crate::data::video_analyzer::{analyze_all_video_relations, VideoRelation};
+use crate::utils;
+use crate::{load_channels_and_chat_logs, AnalysisChatOpt};
+
+/// `analysis-chat`의 유사 다시보기 클러스터링 기준 (Jaccard)
+const CLUSTER_THRESHOLD: f64 = 0.1;
+
+/// 유사도 근사 오차 보고 옵션
+#[derive(StructOpt, Debug)]
+pub struct SimilarityReportOpt {
+    /// 채널 및 리플레이 데이터 파일 경로 (여러 개 지정 가능)
+    #[structopt(long)]
+    pub files: Vec<String>,
+
+    /// LSH band 수
+    #[structopt(long, default_value = "256")]
+    pub lsh_bands: usize,
+
+    /// LSH band당 row 수
+    #[structopt(long, default_value = "2")]
+    pub lsh_rows: usize,
+}
+
+/// 유사도 근사 오차 보고 모드 실행
+/// `analysis-chat`과 같은 입력으로 근사 모드를 쓰는 비디오 연관도와 클러스터링을 exact/approx 두 방식으로 계산해 비교합니다.
+/// 채널 거리는 항상 exact로 계산하므로 비교하지 않습니다.
+pub fn run_similarity_report(opts: &SimilarityReportOpt) -> Result<()> {
+    let (channels, chat_logs) = load_channels_and_chat_logs(&AnalysisChatOpt {
+        files: opts.files.clone(),
+        ..Default::default()
+    })?;
+    // analysis-chat과 같은 필터링 기준
+    let chat_logs = filter_chat_logs_by_user_count(chat_logs, 10000);
+
+    let video_users: HashMap<u64, Vec<u32>> = chat_logs
+        .logs
+        .par_iter()
+        .map(|chat_log| (chat_log.video_id, chat_log.unique_users()))
+        .collect();
+
+    let params = LshParams {
+        bands: opts.lsh_bands,
+        rows: opts.lsh_rows,
+    };
+    let approx = SimilarityMode::Approx(params);
+
+    // 비디오 연관도
+    utils::log("비디오 연관도 계산 중 (exact)...");
+    let (exact_relations, exact_video_time) =
+        timed(|| analyze_all_video_relations(&channels, &video_users, None, SimilarityMode::Exact));
+    utils::log("비디오 연관도 계산 중 (approx)...");
+    let (approx_relations, approx_video_time) =
+        timed(|| analyze_all_video_relations(&channels, &video_users, None, approx));
+    let (exact_relations, approx_relations) = (exact_relations?, approx_relations?);
+
+    let relation_error = |min_similarity: f64, max_per_video: usize| {
+        ApproxError::compare(
+            relation_pairs(&exact_relations, max_per_video)
+                .filter(|(_, similarity)| *similarity >= min_similarity),
+            &relation_pairs(&approx_relations, max_per_video)
+                .map(|(pair, _)| pair)
+                .collect(),
+        )
+    };
+
+    // 유사 다시보기 클러스터링 (analysis-chat과 같은 기준)
+    utils::log("유사 다시보기 클러스터링 중 (exact)...");
+    let (exact_clusters, exact_cluster_time) = timed(|| {
+        cluster_similar_replays(
+            &channels,
+            &chat_logs,
+            CLUSTER_THRESHOLD,
+            SimilarityMode::Exact,
+        )
+    });
+    utils::log("유사 다시보기 클러스터링 중 (approx)...");
+    let (approx_clusters, approx_cluster_time) =
+        timed(|| cluster_similar_replays(&channels, &chat_logs, CLUSTER_THRESHOLD, approx));
+    let cluster_error = ApproxError::compare(
+        cluster_pairs(&exact_clusters),
+        &cluster_pairs(&approx_clusters)
+            .into_iter()
+            .map(|(pair, _)| pair)
+            .collect(),
+    );
+
+    println!(
+        "\n=== 유사도 근사 오차 (MinHash LSH, {} bands × {} rows) ===",
+        params.bands, params.rows
+    );
+    println!("후보가 될 확률 (Jaccard 유사도 기준):");
+    for similarity in [0.01, 0.02, 0.05, 0.1, 0.2] {
+        println!(
+            "  Jaccard {:.2}: {:.4}",
+            similarity,
+            params.candidate_probability(similarity)
+        );
+    }
+    println!();
+
+    print_timing("비디오 연관도", exact_video_time, approx_video_time);
+    print_error(
+        "비디오 연관도 (similarity ≥ 0.02)",
+        &relation_error(0.0, usize::MAX),
+    );
+    print_error(
+        "비디오 연관도 (similarity ≥ 0.1)",
+        &relation_error(CLUSTER_THRESHOLD, usize::MAX),
+    );
+    print_error("비디오 연관도 상위 20개", &relation_error(0.0, 20));
+    println!();
+
+    print_timing(
+        "유사 다시보기 클러스터링",
+        exact_cluster_time,
+        approx_cluster_time,
+    );
+    println!(
+        "  클러스터 수: exact {}개, approx {}개",
+        exact_clusters.len(),
+        approx_clusters.len()
+    );
+    print_error("같은 클러스터에 속한 다시보기 쌍", &cluster_error);
+
+    Ok(())
+}
+
+/// 함수 실행 시간을 잽니다.
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// 비디오별 상위 `max_per_video`개 연관도를 ((대상, 연관 비디오), 유사도) 목록으로 펼칩니다.
+fn relation_pairs(
+    relations: &HashMap<u64, Vec<VideoRelation>>,
+    max_per_video: usize,
+) -> impl Iterator<Item = ((u64, u64), f64)> + '_ {
+    relations.iter().flat_map(move |(video_no, relations)| {
+        relations
+            .iter()
+            .take(max_per_video)
+            .map(move |relation| ((*video_no, relation.video_no), relation.similarity))
+    })
+}
+
+/// 같은 클러스터에 속한 다시보기 쌍을 ((작은 번호, 큰 번호), 클러스터 평균 유사도) 목록으로 펼칩니다.
+fn cluster_pairs(clusters: &[ReplayCluster]) -> Vec<((u64, u64), f64)> {
+    let mut pairs = Vec::new();
+    for cluster in clusters {
+        let mut videos: Vec<u64> = cluster
+            .replays
+            .iter()
+            .map(|replay| replay.replay.video_no)
+            .collect();
+        videos.sort_unstable();
+        for (a, &video_a) in videos.iter().enumerate() {
+            for &video_b in &videos[a + 1..] {
+                pairs.push(((video_a, video_b), cluster.average_similarity));
+            }
+        }
+    }
+    pairs
+}
+
+fn print_timing(name: &str, exact: Duration, approx: Duration) {
+    println!(
+        "{} 계산 시간: exact {:.2}s, approx {:.2}s ({:.1}배)",
+        name,
+        exact.as_secs_f64(),
+        approx.as_secs_f64(),
+        exact.as_secs_f64() / approx.as_secs_f64().max(f64::EPSILON)
+    );
+}
+
+fn print_error(name: &str, error: &ApproxError) {
+    println!(
+        "  {}: exact {}개 중 {}개 찾음 (recall {:.4}, 점수 가중 recall {:.4}), 놓친 쌍 최대 점수 {:.4}",
+        name,
+        error.exact,
+        error.found,
+        error.recall(),
+        error.weighted_recall(),
+        error.max_missed
+    );
+}
@@ -0,0 +1,333 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+use color_eyre::eyre::{eyre, Context, Result};
+use rayon::prelude::*;
+use structopt::StructOpt;
+
+use crate::data::chat::event::detect_event_intervals;
+use crate::data::chat::event_config::{
+    chat_rate, EventConfigFile, EventConfigOpt, EventDetectionOverrides, EventPreset, EventSettings,
+};
+use crate::data::chat::event_eval::{read_highlight_labels, score_events, EventScore, LabelTime};
+use crate::data::chat::loader::{chat_log_source_path, load_chat_log};
+use crate::data::chat::store::{ChatColumns, ChatStore};
+use crate::data::utils::parse_replay_time;
+use crate::load_channels;
+use crate::utils;
+
+/// 이벤트 탐지 파라미터 튜닝 옵션
+#[derive(StructOpt, Debug)]
+pub struct TuneEventsOpt {
+    /// 하이라이트 라벨 CSV 파일 (`video_id,time` 헤더, time은 `HH:MM:SS`/초 또는 절대 시간)
+    #[structopt(long)]
+    pub labels: PathBuf,
+
+    /// 채널 및 리플레이 데이터 파일 경로 (다시보기 기준 라벨 시간 변환용, 여러 개 지정 가능)
+    #[structopt(long)]
+    pub files: Vec<String>,
+
+    /// 기준 이벤트 설정 파일 (스윕하지 않는 파라미터에 사용)
+    #[structopt(long)]
+    pub event_config: Option<PathBuf>,
+
+    /// 기준 채팅량 preset (auto, small, medium, large)
+    #[structopt(long)]
+    pub event_preset: Option<EventPreset>,
+
+    /// 라벨과 이벤트 구간 사이 허용 오차 (초)
+    #[structopt(long, default_value = "30")]
+    pub tolerance_sec: i64,
+
+    /// 스윕할 피크 임계값 목록 (쉼표 구분)
+    #[structopt(long, use_delimiter = true)]
+    pub z_th: Vec<f64>,
+
+    /// 스윕할 구간 확장 임계값 목록
+    #[structopt(long, use_delimiter = true)]
+    pub z_end: Vec<f64>,
+
+    /// 스윕할 피크 병합 기준 목록 (초)
+    #[structopt(long, use_delimiter = true)]
+    pub gap_threshold_sec: Vec<i64>,
+
+    /// 스윕할 baseline 윈도우 길이 목록 (초)
+    #[structopt(long, use_delimiter = true)]
+    pub baseline_window_sec: Vec<i64>,
+
+    /// 스윕할 baseline 제외 구간 목록 (초)
+    #[structopt(long, use_delimiter = true)]
+    pub baseline_lag_sec: Vec<i64>,
+
+    /// 스윕할 EWMA alpha 목록
+    #[structopt(long, use_delimiter = true)]
+    pub smooth_alpha: Vec<f64>,
+
+    /// 출력할 상위 조합 수
+    #[structopt(long, default_value = "20")]
+    pub top: usize,
+
+    /// F1이 가장 높은 조합을 이벤트 설정 파일로 저장
+    #[structopt(long)]
+    pub save_best: Option<PathBuf>,
+}
+
+/// 라벨이 있는 비디오 하나
+struct LabeledVideo<'a> {
+    chat_log: &'a ChatColumns,
+    /// 첫 메시지 기준 라벨 시간 (초)
+    label_secs: Vec<i64>,
+}
+
+/// 이벤트 탐지 파라미터 튜닝 모드 실행
+/// 파라미터 조합마다 라벨이 있는 비디오의 이벤트를 탐지해 precision/recall을 계산합니다.
+pub fn run_tune_events(opts: &TuneEventsOpt) -> Result<()> {
+    let settings = EventConfigOpt {
+        event_config: opts.event_config.clone(),
+        event_preset: opts.event_preset,
+        ..Default::default()
+    }
+    .resolve()?;
+
+    let file = fs::File::open(&opts.labels)
+        .with_context(|| format!("Failed to open labels: {:?}", opts.labels))?;
+    let labels = read_highlight_labels(file)
+        .with_context(|| format!("Failed to read labels: {:?}", opts.labels))?;
+    let mut labels_by_video: BTreeMap<u64, Vec<LabelTime>> = BTreeMap::new();
+    for label in &labels {
+        labels_by_video
+            .entry(label.video_id)
+            .or_default()
+            .push(label.time);
+    }
+    utils::log(format!(
+        "라벨 {}개 로드 (비디오 {}개)",
+        labels.len(),
+        labels_by_video.len()
+    ));
+
+    // 다시보기 기준 라벨이 있으면 다시보기 시작 시간 필요
+    let needs_vod_start = labels
+        .iter()
+        .any(|label| matches!(label.time, LabelTime::Offset(_)));
+    let vod_starts: HashMap<u64, i64> = if needs_vod_start {
+        load_channels(&opts.files)?
+            .iter()
+            .flat_map(|channel| &channel.replays)
+            .filter(|replay| labels_by_video.contains_key(&replay.video_no))
+            .filter_map(|replay| {
+                parse_replay_time(&replay.start)
+                    .ok()
+                    .map(|start| (replay.video_no, start.timestamp()))
+            })
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    let mut chat_logs = Vec::new();
+    for &video_id in labels_by_video.keys() {
+        let path = chat_log_source_path("../chat_logs", video_id);
+        if !path.exists() {
+            utils::log(format!("채팅 로그 없음, 건너뜀: {}", video_id));
+            continue;
+        }
+        chat_logs.push(load_chat_log(&path)?.0);
+    }
+    let store = ChatStore::from_chat_logs(chat_logs);
+
+    let videos: Vec<LabeledVideo> = store
+        .logs
+        .iter()
+        .filter_map(|chat_log| {
+            let first_time = chat_log.first_timestamp()?;
+            let vod_start = vod_starts
+                .get(&chat_log.video_id)
+                .copied()
+                .unwrap_or_else(|| {
+                    if needs_vod_start {
+                        utils::log(format!(
+                            "다시보기 시작 시간 없음, 첫 메시지 기준: {}",
+                            chat_log.video_id
+                        ));
+                    }
+                    first_time
+                });
+            let label_secs = labels_by_video[&chat_log.video_id]
+                .iter()
+                .map(|time| time.event_sec(vod_start, first_time))
+                .collect();
+            Some(LabeledVideo {
+                chat_log,
+                label_secs,
+            })
+        })
+        .collect();
+    if videos.is_empty() {
+        return Err(eyre!("No chat logs found for labeled videos"));
+    }
+
+    println!("\n=== 라벨 비디오 ===");
+    for video in &videos {
+        let rate = chat_rate(video.chat_log);
+        println!(
+            "  {}: 라벨 {}개, 초당 메시지 {:.2}개 (preset {:?})",
+            video.chat_log.video_id,
+            video.label_secs.len(),
+            rate,
+            match settings.preset {
+                EventPreset::Auto => EventPreset::for_chat_rate(rate),
+                preset => preset,
+            }
+        );
+    }
+
+    let grid = sweep_grid(opts, &settings)?;
+    let pb = utils::create_progress_bar(grid.len() as u64, "Sweeping event parameters...");
+    let mut results: Vec<(EventDetectionOverrides, EventScore)> = grid
+        .into_par_iter()
+        .map(|overrides| {
+            let score = evaluate(&settings, &overrides, &videos, opts.tolerance_sec);
+            pb.inc(1);
+            (overrides, score)
+        })
+        .collect();
+    pb.finish_with_message("Event parameters swept!");
+
+    results.sort_by(|a, b| {
+        b.1.f1()
+            .partial_cmp(&a.1.f1())
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(
+                b.1.precision()
+                    .partial_cmp(&a.1.precision())
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            )
+    });
+
+    println!(
+        "\n=== 이벤트 탐지 파라미터 (허용 오차 ±{}초, 상위 {}개 / {}개) ===",
+        opts.tolerance_sec,
+        opts.top.min(results.len()),
+        results.len()
+    );
+    for (i, (overrides, score)) in results.iter().take(opts.top).enumerate() {
+        println!(
+            "{:>3}. {} -> 이벤트 {}개, precision {:.3} ({}/{}), recall {:.3} ({}/{}), F1 {:.3}",
+            i + 1,
+            describe(overrides),
+            score.events,
+            score.precision(),
+            score.matched_events,
+            score.events,
+            score.recall(),
+            score.found_labels,
+            score.labels,
+            score.f1()
+        );
+    }
+
+    if let (Some(path), Some((best, _))) = (&opts.save_best, results.first()) {
+        let mut file = match &opts.event_config {
+            Some(path) => EventConfigFile::load(path)?,
+            None => EventConfigFile::default(),
+        };
+        file.preset = Some(settings.preset);
+        file.detection = settings.detection.merged(best);
+        file.save(path)?;
+        utils::log(format!("최적 이벤트 설정 저장: {:?}", path));
+    }
+
+    Ok(())
+}
+
+/// 스윕할 파라미터 조합 (지정하지 않은 파라미터는 기준 설정 값 사용)
+/// 기준 설정에 덮어썼을 때 쓸 수 없는 값이 있으면 에러를 반환합니다.
+fn sweep_grid(
+    opts: &TuneEventsOpt,
+    settings: &EventSettings,
+) -> Result<Vec<EventDetectionOverrides>> {
+    let grid = vec![EventDetectionOverrides::default()];
+    let grid = expand(grid, &opts.z_th, |o, v| o.z_th = Some(v));
+    let grid = expand(grid, &opts.z_end, |o, v| o.z_end = Some(v));
+    let grid = expand(grid, &opts.gap_threshold_sec, |o, v| {
+        o.gap_threshold_sec = Some(v)
+    });
+    let grid = expand(grid, &opts.baseline_window_sec, |o, v| {
+        o.baseline_window_sec = Some(v)
+    });
+    let grid = expand(grid, &opts.baseline_lag_sec, |o, v| {
+        o.baseline_lag_sec = Some(v)
+    });
+    let grid = expand(grid, &opts.smooth_alpha, |o, v| o.smooth_alpha = Some(v));
+
+    for overrides in &grid {
+        EventSettings {
+            detection: settings.detection.merged(overrides),
+            ..settings.clone()
+        }
+        .validate()
+        .with_context(|| format!("Invalid sweep combination: {}", describe(overrides)))?;
+    }
+    Ok(grid)
+}
+
+/// 조합마다 `values`의 값을 하나씩 지정한 조합으로 늘립니다.
+fn expand<T: Copy>(
+    grid: Vec<EventDetectionOverrides>,
+    values: &[T],
+    set: impl Fn(&mut EventDetectionOverrides, T),
+) -> Vec<EventDetectionOverrides> {
+    if values.is_empty() {
+        return grid;
+    }
+    grid.iter()
+        .flat_map(|overrides| {
+            values.iter().map(|&value| {
+                let mut overrides = overrides.clone();
+                set(&mut overrides, value);
+                overrides
+            })
+        })
+        .collect()
+}
+
+/// 기준 설정에 `overrides`를 덮어써서 모든 라벨 비디오의 점수를 합칩니다.
+fn evaluate(
+    settings: &EventSettings,
+    overrides: &EventDetectionOverrides,
+    videos: &[LabeledVideo],
+    tolerance_sec: i64,
+) -> EventScore {
+    let settings = EventSettings {
+        detection: settings.detection.merged(overrides),
+        ..settings.clone()
+    };
+
+    let mut total = EventScore::default();
+    for video in videos {
+        let config = settings.detection_config(video.chat_log);
+        let events = detect_event_intervals(video.chat_log, &config)
+            .map(|result| result.events)
+            .unwrap_or_default();
+        total.add(score_events(&events, &video.label_secs, tolerance_sec));
+    }
+    total
+}
+
+/// 조합에서 지정한 파라미터만 출력용 문자열로 만듭니다.
+fn describe(overrides: &EventDetectionOverrides) -> String {
+    let values = serde_json::to_value(overrides).unwrap_or_default();
+    let parts: Vec<String> = values
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    if parts.is_empty() {
+        "기준 설정".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
@@ -0,0 +1,368 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use color_eyre::eyre::{Context, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data::chat::channel_distance::{
+    build_channel_user_map, calculate_channel_distances, ChannelLink, ChannelNode, PreviousLinks,
+};
+use crate::data::chat::minhash::SimilarityMode;
+use crate::data::chat::store::{ChatStore, Interner};
+use crate::data::models::ChannelWithReplays;
+use crate::data::video_analyzer::{analyze_all_video_relations, PreviousRelations, VideoRelation};
+use crate::utils;
+
+/// 분석 상태 파일 형식 버전 (구조가 바뀌면 올려서 다음 실행을 전체 재계산으로 만듭니다)
+pub const ANALYSIS_STATE_VERSION: u32 = 2;
+
+/// 증분 분석을 위해 저장하는 이전 실행 상태
+/// 사용자 번호는 상태 파일의 `users` 테이블 기준이라 실행마다 달라지는 `ChatStore` 번호와 무관합니다.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AnalysisState {
+    version: u32,
+    /// 이전 결과를 계산한 유사도 계산 방식
+    similarity: SimilarityMode,
+    /// 사용자 번호 -> user_id
+    users: Interner,
+    /// 비디오별 사용자 번호 목록 (정렬, 중복 제거)
+    video_users: HashMap<u64, Vec<u32>>,
+    /// 비디오별 다시보기 시간 범위 (start, end 원문)
+    video_ranges: HashMap<u64, (String, String)>,
+    /// 채널별 사용자 번호 목록 (정렬, 중복 제거)
+    channel_users: HashMap<String, Vec<u32>>,
+    /// 이전 실행의 채널 링크
+    channel_links: Vec<ChannelLink>,
+    /// 이전 실행의 비디오 연관도
+    video_relations: HashMap<u64, Vec<VideoRelation>>,
+}
+
+/// 이전 실행과 비교해 바뀐 항목
+#[derive(Debug, Default)]
+pub struct AnalysisChanges {
+    /// 사용자 집합이나 시간 범위가 바뀌었거나 새로 생긴 비디오
+    pub videos: HashSet<u64>,
+    /// 사용자 집합이 바뀌었거나 새로 생긴 채널
+    pub channels: HashSet<String>,
+}
+
+/// 채널 거리와 비디오 연관도 분석 결과
+pub struct AnalysisOutput {
+    pub nodes: Vec<ChannelNode>,
+    pub links: Vec<ChannelLink>,
+    pub relations: HashMap<u64, Vec<VideoRelation>>,
+    #[allow(dead_code)]
+    pub changes: AnalysisChanges,
+}
+
+impl AnalysisState {
+    /// 빈 상태 (다음 분석에서 모든 쌍을 계산)
+    pub fn new() -> Self {
+        Self {
+            version: ANALYSIS_STATE_VERSION,
+            ..Default::default()
+        }
+    }
+
+    /// 상태 파일을 읽습니다.
+    /// 파일이 없거나, 읽을 수 없거나, 형식 버전이 다르면 빈 상태를 반환합니다.
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        if !path.exists() {
+            utils::log(format!("분석 상태 파일 없음, 전체 계산: {:?}", path));
+            return Self::new();
+        }
+
+        let state: Result<Self> = fs::File::open(path)
+            .with_context(|| format!("Failed to open analysis state: {:?}", path))
+            .and_then(|file| {
+                ciborium::de::from_reader(BufReader::new(file))
+                    .with_context(|| format!("Failed to read analysis state: {:?}", path))
+            });
+        match state {
+            Ok(state) if state.version == ANALYSIS_STATE_VERSION => state,
+            Ok(state) => {
+                utils::log(format!(
+                    "분석 상태 형식 버전이 다름 ({} != {}), 전체 계산",
+                    state.version, ANALYSIS_STATE_VERSION
+                ));
+                Self::new()
+            }
+            Err(e) => {
+                utils::log(format!("분석 상태를 읽을 수 없음, 전체 계산: {:?}", e));
+                Self::new()
+            }
+        }
+    }
+
+    /// 상태 파일을 저장합니다. 임시 파일에 먼저 쓰고 이름을 바꿉니다.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+        }
+        let tmp_path = path.with_extension("tmp");
+
+        let file = fs::File::create(&tmp_path)
+            .with_context(|| format!("Failed to create analysis state: {:?}", tmp_path))?;
+        let mut writer = BufWriter::new(file);
+        ciborium::ser::into_writer(self, &mut writer)
+            .with_context(|| format!("Failed to serialize analysis state: {:?}", tmp_path))?;
+        writer
+            .flush()
+            .with_context(|| format!("Failed to write analysis state: {:?}", tmp_path))?;
+        drop(writer);
+
+        fs::rename(&tmp_path, path)
+            .with_context(|| format!("Failed to move analysis state: {:?}", path))?;
+        Ok(())
+    }
+
+    /// 채팅 저장소의 비디오별 사용자 집합을 상태의 사용자 번호로 바꿔 구합니다.
+    fn video_user_sets(&mut self, store: &ChatStore) -> HashMap<u64, Vec<u32>> {
+        // ChatStore 번호 -> 상태 번호
+        let user_map: Vec<u32> = (0..store.users.len() as u32)
+            .map(|user| self.users.intern(store.user_id(user)))
+            .collect();
+
+        store
+            .logs
+            .par_iter()
+            .map(|chat_log| {
+                let mut users: Vec<u32> = chat_log
+                    .unique_users()
+                    .into_iter()
+                    .map(|user| user_map[user as usize])
+                    .collect();
+                users.sort_unstable();
+                (chat_log.video_id, users)
+            })
+            .collect()
+    }
+
+    /// 이전 실행과 비교해 바뀐 비디오와 채널을 구합니다.
+    fn changes(
+        &self,
+        channels: &[ChannelWithReplays],
+        video_users: &HashMap<u64, Vec<u32>>,
+        channel_users: &HashMap<String, Vec<u32>>,
+    ) -> AnalysisChanges {
+        let videos = channels
+            .iter()
+            .flat_map(|channel| &channel.replays)
+            .filter(|replay| {
+                let range_changed = self
+                    .video_ranges
+                    .get(&replay.video_no)
+                    .is_none_or(|(start, end)| *start != replay.start || *end != replay.end);
+                range_changed
+                    || self.video_users.get(&replay.video_no) != video_users.get(&replay.video_no)
+            })
+            .map(|replay| replay.video_no)
+            .collect();
+
+        let channels = channel_users
+            .iter()
+            .filter(|(channel_id, users)| self.channel_users.get(*channel_id) != Some(*users))
+            .map(|(channel_id, _)| channel_id.clone())
+            .collect();
+
+        AnalysisChanges { videos, channels }
+    }
+
+    /// 채널 거리와 비디오 연관도를 계산하고 상태를 이번 결과로 갱신합니다.
+    /// 바뀐 비디오나 채널이 포함된 쌍만 다시 계산하고 나머지는 이전 결과를 사용합니다.
+    /// 이전 결과와 유사도 계산 방식이 다르면 모든 쌍을 다시 계산합니다.
+    pub fn analyze(
+        &mut self,
+        store: &ChatStore,
+        channels: &[ChannelWithReplays],
+        mode: SimilarityMode,
+    ) -> Result<AnalysisOutput> {
+        if self.similarity != mode {
+            utils::log(format!(
+                "유사도 계산 방식이 바뀜 ({:?} -> {:?}), 전체 계산",
+                self.similarity, mode
+            ));
+            *self = Self {
+                users: std::mem::take(&mut self.users),
+                similarity: mode,
+                ..Self::new()
+            };
+        }
+
+        let video_users = self.video_user_sets(store);
+        let channel_users = build_channel_user_map(&video_users, channels);
+        let changes = self.changes(channels, &video_users, &channel_users);
+        utils::log(format!(
+            "증분 분석: 바뀐 비디오 {}개 / {}개, 바뀐 채널 {}개 / {}개",
+            changes.videos.len(),
+            channels.iter().map(|c| c.replays.len()).sum::<usize>(),
+            changes.channels.len(),
+            channel_users.len()
+        ));
+
+        utils::log("채널 간 거리 계산 중...");
+        let previous_links = PreviousLinks {
+            links: &self.channel_links,
+            changed: &changes.channels,
+        };
+        let (nodes, links) =
+            calculate_channel_distances(&channel_users, channels, None, Some(&previous_links));
+
+        let previous_relations = PreviousRelations {
+            relations: &self.video_relations,
+            changed: &changes.videos,
+        };
+        let relations =
+            analyze_all_video_relations(channels, &video_users, Some(&previous_relations), mode)?;
+
+        self.video_ranges = channels
+            .iter()
+            .flat_map(|channel| &channel.replays)
+            .map(|replay| (replay.video_no, (replay.start.clone(), replay.end.clone())))
+            .collect();
+        self.video_users = video_users;
+        self.channel_users = channel_users;
+        self.channel_links = links.clone();
+        self.video_relations = relations.clone();
+
+        Ok(AnalysisOutput {
+            nodes,
+            links,
+            relations,
+            changes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::chat::loader::parse_chat_line;
+    use crate::data::models::{ChatLog, Replay};
+
+    fn chat_log(video_id: u64, users: &[&str]) -> ChatLog {
+        ChatLog {
+            video_id,
+            messages: users
+                .iter()
+                .map(|user| parse_chat_line(&format!("[2025-01-01 12:00:00] n: m ({})", user)))
+                .map(Option::unwrap)
+                .collect(),
+        }
+    }
+
+    fn channel(channel_id: &str, video_nos: &[u64]) -> ChannelWithReplays {
+        ChannelWithReplays {
+            name: channel_id.to_string(),
+            follower: 0,
+            channel_id: channel_id.to_string(),
+            image: None,
+            replays: video_nos
+                .iter()
+                .map(|&video_no| Replay {
+                    title: video_no.to_string(),
+                    start: "2025-01-01 11:00:00".to_string(),
+                    end: "2025-01-01 14:00:00".to_string(),
+                    video_no,
+                    thumbnail: None,
+                    tags: Vec::new(),
+                    category_ko: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// 비교용으로 링크와 연관도를 정렬된 값 목록으로 바꿉니다.
+    fn summarize(output: &AnalysisOutput) -> (Vec<String>, Vec<String>) {
+        let mut links: Vec<String> = output
+            .links
+            .iter()
+            .map(|l| format!("{}-{} {} {}", l.source, l.target, l.inter, l.distance))
+            .collect();
+        links.sort();
+        let mut relations: Vec<String> = output
+            .relations
+            .iter()
+            .flat_map(|(video_no, relations)| {
+                relations.iter().map(move |r| {
+                    format!(
+                        "{} {} {} {}",
+                        video_no, r.video_no, r.similarity, r.shared_users
+                    )
+                })
+            })
+            .collect();
+        relations.sort();
+        (links, relations)
+    }
+
+    #[test]
+    fn test_incremental_analysis_matches_full() {
+        let path = std::env::temp_dir().join(format!(
+            "chzzk-chat-analysis-state-{}.cbor",
+            std::process::id()
+        ));
+        let channels = vec![
+            channel("a", &[1, 2]),
+            channel("b", &[3]),
+            channel("c", &[4]),
+        ];
+        let store = ChatStore::from_chat_logs(vec![
+            chat_log(1, &["u1", "u2", "u3"]),
+            chat_log(2, &["u4"]),
+            chat_log(3, &["u2", "u3", "u5"]),
+            chat_log(4, &["u6", "u7"]),
+        ]);
+
+        let mut state = AnalysisState::new();
+        let first = state
+            .analyze(&store, &channels, SimilarityMode::Exact)
+            .unwrap();
+        assert_eq!(first.changes.videos.len(), 4);
+        state.save(&path).unwrap();
+
+        // 비디오 4에 사용자가 늘고, 채널 c에 새 비디오 5가 추가됨
+        let channels = vec![
+            channel("a", &[1, 2]),
+            channel("b", &[3]),
+            channel("c", &[4, 5]),
+        ];
+        let store = ChatStore::from_chat_logs(vec![
+            chat_log(1, &["u1", "u2", "u3"]),
+            chat_log(2, &["u4"]),
+            chat_log(3, &["u2", "u3", "u5"]),
+            chat_log(4, &["u6", "u7", "u1"]),
+            chat_log(5, &["u4", "u5"]),
+        ]);
+
+        let mut state = AnalysisState::load(&path);
+        let incremental = state
+            .analyze(&store, &channels, SimilarityMode::Exact)
+            .unwrap();
+        assert_eq!(incremental.changes.videos, HashSet::from([4, 5]));
+        assert_eq!(
+            incremental.changes.channels,
+            HashSet::from(["c".to_string()])
+        );
+
+        let full = AnalysisState::new()
+            .analyze(&store, &channels, SimilarityMode::Exact)
+            .unwrap();
+        assert_eq!(summarize(&incremental), summarize(&full));
+
+        // 바뀐 것이 없으면 이전 결과를 그대로 사용
+        let unchanged = state
+            .analyze(&store, &channels, SimilarityMode::Exact)
+            .unwrap();
+        assert!(unchanged.changes.videos.is_empty() && unchanged.changes.channels.is_empty());
+        assert_eq!(summarize(&unchanged), summarize(&full));
+
+        fs::remove_file(&path).ok();
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use crate::data::models::{ChannelWithReplays, ChatLog};
+use crate::data::chat::store::{kst_datetime, ChatColumns, ChatStore};
+use crate::data::models::ChannelWithReplays;
 
 /// 채팅 로그 분석 결과
 #[derive(Debug, Clone)]
@@ -16,48 +17,34 @@ pub struct ChatAnalysis {
 }
 
 /// 단일 채팅 로그를 분석합니다.
-pub fn analyze_chat_log(chat_log: &ChatLog) -> ChatAnalysis {
-    let total_messages = chat_log.messages.len();
-
-    // 고유 사용자 수 (user_id 기준)
-    let unique_users: std::collections::HashSet<String> = chat_log
-        .messages
-        .iter()
-        .map(|msg| msg.user_id.clone())
-        .collect();
-    let unique_users_count = unique_users.len();
-
-    // 고유 닉네임 수
-    let unique_nicknames: std::collections::HashSet<String> = chat_log
-        .messages
-        .iter()
-        .map(|msg| msg.nickname.clone())
-        .collect();
-    let unique_nicknames_count = unique_nicknames.len();
-
-    // 사용자별 메시지 수
-    let mut messages_per_user: HashMap<String, usize> = HashMap::new();
-    for message in &chat_log.messages {
-        *messages_per_user
-            .entry(message.user_id.clone())
-            .or_insert(0) += 1;
+/// 사용자와 닉네임은 번호로 센 뒤 마지막에 문자열로 바꿉니다.
+pub fn analyze_chat_log(store: &ChatStore, chat_log: &ChatColumns) -> ChatAnalysis {
+    let total_messages = chat_log.len();
+
+    // 사용자별 메시지 수 (user_id 기준)
+    let mut user_counts: HashMap<u32, usize> = HashMap::new();
+    for &user in chat_log.users() {
+        *user_counts.entry(user).or_insert(0) += 1;
     }
 
     // 닉네임별 메시지 수
-    let mut messages_per_nickname: HashMap<String, usize> = HashMap::new();
-    for message in &chat_log.messages {
-        *messages_per_nickname
-            .entry(message.nickname.clone())
-            .or_insert(0) += 1;
+    let mut nickname_counts: HashMap<u32, usize> = HashMap::new();
+    for i in 0..chat_log.len() {
+        *nickname_counts.entry(chat_log.nickname(i)).or_insert(0) += 1;
     }
 
-    // 시간 정보
-    let mut timestamps: Vec<chrono::DateTime<chrono::FixedOffset>> =
-        chat_log.messages.iter().map(|msg| msg.timestamp).collect();
-    timestamps.sort();
+    let messages_per_user: HashMap<String, usize> = user_counts
+        .into_iter()
+        .map(|(user, count)| (store.user_id(user).to_string(), count))
+        .collect();
+    let messages_per_nickname: HashMap<String, usize> = nickname_counts
+        .into_iter()
+        .map(|(nickname, count)| (store.nickname(nickname).to_string(), count))
+        .collect();
 
-    let first_message_time = timestamps.first().copied();
-    let last_message_time = timestamps.last().copied();
+    // 시간 정보
+    let first_message_time = chat_log.timestamps().iter().min().map(|&t| kst_datetime(t));
+    let last_message_time = chat_log.timestamps().iter().max().map(|&t| kst_datetime(t));
     let duration_seconds =
         if let (Some(first), Some(last)) = (first_message_time, last_message_time) {
             Some((last - first).num_seconds())
@@ -67,8 +54,8 @@ pub fn analyze_chat_log(chat_log: &ChatLog) -> ChatAnalysis {
 
     ChatAnalysis {
         total_messages,
-        unique_users: unique_users_count,
-        unique_nicknames: unique_nicknames_count,
+        unique_users: messages_per_user.len(),
+        unique_nicknames: messages_per_nickname.len(),
         messages_per_user,
         messages_per_nickname,
         first_message_time,
@@ -79,8 +66,12 @@ pub fn analyze_chat_log(chat_log: &ChatLog) -> ChatAnalysis {
 
 /// 여러 채팅 로그를 분석합니다.
 #[allow(dead_code)]
-pub fn analyze_chat_logs(chat_logs: &[ChatLog]) -> Vec<ChatAnalysis> {
-    chat_logs.iter().map(analyze_chat_log).collect()
+pub fn analyze_chat_logs(store: &ChatStore) -> Vec<ChatAnalysis> {
+    store
+        .logs
+        .iter()
+        .map(|chat_log| analyze_chat_log(store, chat_log))
+        .collect()
 }
 
 /// video_id로 채널 및 리플레이 정보를 찾습니다.
@@ -101,7 +92,7 @@ fn find_channel_and_replay(
 
 /// 채팅 로그 분석 결과를 요약 출력합니다.
 pub fn print_analysis_summary(
-    chat_log: &ChatLog,
+    chat_log: &ChatColumns,
     analysis: &ChatAnalysis,
     channels: &[ChannelWithReplays],
 ) {
@@ -175,27 +166,13 @@ pub fn print_analysis_summary(
 }
 
 /// 고유 사용자 수가 기준값 이상인 chat_log를 필터링합니다.
-pub fn filter_chat_logs_by_user_count(
-    chat_logs: Vec<ChatLog>,
-    max_user_count: usize,
-) -> Vec<ChatLog> {
-    use std::collections::HashSet;
-
-    let initial_count = chat_logs.len();
-    let filtered: Vec<_> = chat_logs
-        .into_iter()
-        .filter(|chat_log| {
-            let unique_users: HashSet<String> = chat_log
-                .messages
-                .iter()
-                .map(|msg| msg.user_id.clone())
-                .collect();
-            let user_count = unique_users.len();
-            user_count < max_user_count
-        })
-        .collect();
+pub fn filter_chat_logs_by_user_count(mut store: ChatStore, max_user_count: usize) -> ChatStore {
+    let initial_count = store.logs.len();
+    store
+        .logs
+        .retain(|chat_log| chat_log.unique_users().len() < max_user_count);
 
-    let filtered_count = filtered.len();
+    let filtered_count = store.logs.len();
     let excluded_count = initial_count - filtered_count;
 
     // 필터링된 항목 로그 출력
@@ -210,5 +187,5 @@ pub fn filter_chat_logs_by_user_count(
         initial_count, excluded_count, filtered_count
     );
 
-    filtered
+    store
 }
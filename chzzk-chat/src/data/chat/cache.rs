@@ -0,0 +1,325 @@
+use std::fs;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use color_eyre::eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::data::chat::loader::{chat_log_source_path, extract_video_id_from_filename};
+use crate::data::models::ChatLog;
+
+/// 캐시 파일 형식 버전 (`ChatLog` 직렬화 형식이 바뀌면 올려서 기존 캐시를 다시 만들게 합니다)
+pub const CACHE_FORMAT_VERSION: u32 = 2;
+/// 캐시 파일 헤더 식별자
+const CACHE_MAGIC: &str = "chzzk-chat-cache";
+
+/// 캐시 파일 앞에 붙는 헤더 (원본 로그 파일 정보)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheHeader {
+    pub magic: String,
+    pub version: u32,
+    /// 원본 로그 파일 크기 (바이트)
+    pub source_size: u64,
+    /// 원본 로그 파일 수정 시간 (UNIX epoch 기준 나노초)
+    pub source_mtime: u64,
+    /// 원본 로그 파일 내용의 FNV-1a 64비트 해시
+    pub source_hash: u64,
+}
+
+impl CacheHeader {
+    /// 원본 로그 파일의 크기, 수정 시간, 해시로 헤더를 만듭니다.
+    pub fn from_source<P: AsRef<Path>>(source_path: P) -> Result<Self> {
+        let source_path = source_path.as_ref();
+        let (source_size, source_mtime) = source_stat(source_path)?;
+
+        Ok(Self {
+            magic: CACHE_MAGIC.to_string(),
+            version: CACHE_FORMAT_VERSION,
+            source_size,
+            source_mtime,
+            source_hash: hash_file(source_path)?,
+        })
+    }
+}
+
+/// 캐시 파일 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// 원본과 일치
+    Fresh,
+    /// 원본 파일이 바뀜 (다시 받았거나 이어서 기록됨)
+    Stale,
+    /// 캐시 형식 버전이 다름
+    Outdated,
+    /// 헤더나 본문을 읽을 수 없음
+    Corrupt,
+    /// 원본 로그 파일이 없음
+    Orphan,
+}
+
+impl CacheStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheStatus::Fresh => "fresh",
+            CacheStatus::Stale => "stale",
+            CacheStatus::Outdated => "outdated",
+            CacheStatus::Corrupt => "corrupt",
+            CacheStatus::Orphan => "orphan",
+        }
+    }
+}
+
+/// 캐시 디렉토리의 캐시 파일 하나
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub video_id: u64,
+    pub cache_path: PathBuf,
+    pub source_path: PathBuf,
+    /// 캐시 파일 크기 (바이트)
+    pub cache_size: u64,
+    pub status: CacheStatus,
+}
+
+/// `chatLog-{video_id}.log`(`.gz`, `.zst` 포함)에 대응하는 캐시 파일 경로
+pub fn cache_file_path<P: AsRef<Path>>(cache_dir: P, video_id: u64) -> PathBuf {
+    cache_dir
+        .as_ref()
+        .join(format!("chatLog-{}.log.cache", video_id))
+}
+
+/// 파일 크기와 수정 시간(나노초)을 읽습니다.
+fn source_stat(path: &Path) -> Result<(u64, u64)> {
+    let metadata =
+        fs::metadata(path).with_context(|| format!("Failed to stat chat log file: {:?}", path))?;
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0);
+
+    Ok((metadata.len(), mtime))
+}
+
+/// 파일 내용의 FNV-1a 64비트 해시를 계산합니다. (Rust 버전과 무관하게 값이 고정됨)
+pub fn hash_file<P: AsRef<Path>>(path: P) -> Result<u64> {
+    const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    let path = path.as_ref();
+    let mut file = fs::File::open(path)
+        .with_context(|| format!("Failed to open chat log file: {:?}", path))?;
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut hash = FNV_OFFSET;
+    loop {
+        let n = file
+            .read(&mut buf)
+            .with_context(|| format!("Failed to read chat log file: {:?}", path))?;
+        if n == 0 {
+            break;
+        }
+        for byte in &buf[..n] {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    Ok(hash)
+}
+
+/// 헤더가 현재 원본 파일과 맞는지 확인합니다.
+/// 크기와 수정 시간이 같으면 바로 일치로 보고, 수정 시간만 다르면 해시를 비교합니다.
+/// `full`이면 수정 시간이 같아도 해시를 비교합니다.
+fn header_status(header: &CacheHeader, source_path: &Path, full: bool) -> CacheStatus {
+    if header.magic != CACHE_MAGIC {
+        return CacheStatus::Corrupt;
+    }
+    if header.version != CACHE_FORMAT_VERSION {
+        return CacheStatus::Outdated;
+    }
+
+    let Ok((size, mtime)) = source_stat(source_path) else {
+        return CacheStatus::Orphan;
+    };
+    if size != header.source_size {
+        return CacheStatus::Stale;
+    }
+    if mtime == header.source_mtime && !full {
+        return CacheStatus::Fresh;
+    }
+
+    match hash_file(source_path) {
+        Ok(hash) if hash == header.source_hash => CacheStatus::Fresh,
+        Ok(_) => CacheStatus::Stale,
+        Err(_) => CacheStatus::Orphan,
+    }
+}
+
+/// 캐시 파일을 열어 헤더까지 읽은 reader를 반환합니다.
+fn open_cache(cache_path: &Path) -> Result<(CacheHeader, BufReader<fs::File>)> {
+    let file = fs::File::open(cache_path)
+        .with_context(|| format!("Failed to open cache file: {:?}", cache_path))?;
+    let mut reader = BufReader::new(file);
+    let header = ciborium::de::from_reader(&mut reader)
+        .with_context(|| format!("Failed to read cache header: {:?}", cache_path))?;
+
+    Ok((header, reader))
+}
+
+/// 원본과 일치하는 캐시가 있으면 `ChatLog`를 로드합니다.
+/// 캐시가 없거나, 원본이 바뀌었거나, 손상되었으면 None을 반환합니다.
+pub fn load_cached_chat_log<P: AsRef<Path>, Q: AsRef<Path>>(
+    cache_path: P,
+    source_path: Q,
+) -> Option<ChatLog> {
+    let (header, mut reader) = open_cache(cache_path.as_ref()).ok()?;
+    if header_status(&header, source_path.as_ref(), false) != CacheStatus::Fresh {
+        return None;
+    }
+
+    ciborium::de::from_reader(&mut reader).ok()
+}
+
+/// 헤더와 `ChatLog`를 캐시 파일에 저장합니다.
+/// 임시 파일에 먼저 쓰고 이름을 바꾸므로 중간에 멈춰도 손상된 캐시가 남지 않습니다.
+/// `header`는 원본을 파싱하기 전에 만들어야 파싱 중에 원본이 바뀌어도 다음 실행에서 다시 만듭니다.
+pub fn save_cached_chat_log<P: AsRef<Path>>(
+    chat_log: &ChatLog,
+    header: &CacheHeader,
+    cache_path: P,
+) -> Result<()> {
+    let cache_path = cache_path.as_ref();
+    let tmp_path = cache_path.with_extension("cache.tmp");
+
+    let file = fs::File::create(&tmp_path)
+        .with_context(|| format!("Failed to create cache file: {:?}", tmp_path))?;
+    let mut writer = BufWriter::new(file);
+    ciborium::ser::into_writer(header, &mut writer)
+        .with_context(|| format!("Failed to serialize cache header: {:?}", tmp_path))?;
+    ciborium::ser::into_writer(chat_log, &mut writer)
+        .with_context(|| format!("Failed to serialize to cache file: {:?}", tmp_path))?;
+    writer
+        .flush()
+        .with_context(|| format!("Failed to write cache file: {:?}", tmp_path))?;
+    drop(writer);
+
+    fs::rename(&tmp_path, cache_path)
+        .with_context(|| format!("Failed to move cache file: {:?}", cache_path))?;
+    Ok(())
+}
+
+/// 캐시 파일 하나의 상태를 확인합니다.
+/// `full`이면 원본 해시를 항상 비교하고 본문까지 읽어봅니다.
+pub fn check_cache(cache_path: &Path, source_path: &Path, full: bool) -> CacheStatus {
+    let Ok((header, mut reader)) = open_cache(cache_path) else {
+        return if source_path.exists() {
+            CacheStatus::Corrupt
+        } else {
+            CacheStatus::Orphan
+        };
+    };
+
+    let status = header_status(&header, source_path, full);
+    if status == CacheStatus::Fresh
+        && full
+        && ciborium::de::from_reader::<ChatLog, _>(&mut reader).is_err()
+    {
+        return CacheStatus::Corrupt;
+    }
+    status
+}
+
+/// 캐시 디렉토리의 모든 캐시 파일(쓰다 남은 임시 파일 포함)을 확인합니다.
+pub fn scan_cache_dir<P: AsRef<Path>, Q: AsRef<Path>>(
+    cache_dir: P,
+    chat_logs_dir: Q,
+    full: bool,
+) -> Result<Vec<CacheEntry>> {
+    let cache_dir = cache_dir.as_ref();
+    let entries = fs::read_dir(cache_dir)
+        .with_context(|| format!("Failed to read cache directory: {:?}", cache_dir))?;
+
+    let mut caches: Vec<CacheEntry> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let filename = path.file_name()?.to_str()?;
+            let is_tmp = filename.ends_with(".log.cache.tmp");
+            if !filename.ends_with(".log.cache") && !is_tmp {
+                return None;
+            }
+            let video_id = extract_video_id_from_filename(filename)?;
+            let source_path = chat_log_source_path(chat_logs_dir.as_ref(), video_id);
+
+            let status = if is_tmp {
+                CacheStatus::Corrupt
+            } else {
+                check_cache(&path, &source_path, full)
+            };
+
+            Some(CacheEntry {
+                video_id,
+                cache_size: fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                cache_path: path,
+                source_path,
+                status,
+            })
+        })
+        .collect();
+
+    caches.sort_by(|a, b| (a.video_id, &a.cache_path).cmp(&(b.video_id, &b.cache_path)));
+    Ok(caches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::chat::loader::load_chat_log;
+
+    #[test]
+    fn test_cache_rebuilt_when_source_changes() {
+        let dir = std::env::temp_dir().join(format!("chzzk-chat-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source_path = dir.join("chatLog-7.log");
+        let cache_path = cache_file_path(&dir, 7);
+
+        fs::write(&source_path, "[2025-01-01 12:00:00] a: 하나 (u1)\n").unwrap();
+        let header = CacheHeader::from_source(&source_path).unwrap();
+        let (chat_log, _) = load_chat_log(&source_path).unwrap();
+        save_cached_chat_log(&chat_log, &header, &cache_path).unwrap();
+
+        let cached = load_cached_chat_log(&cache_path, &source_path).unwrap();
+        assert_eq!(cached.messages.len(), 1);
+        assert_eq!(
+            check_cache(&cache_path, &source_path, true),
+            CacheStatus::Fresh
+        );
+
+        // 원본에 이어서 기록되면 stale
+        fs::write(
+            &source_path,
+            "[2025-01-01 12:00:00] a: 하나 (u1)\n[2025-01-01 12:00:01] b: 둘 (u2)\n",
+        )
+        .unwrap();
+        assert!(load_cached_chat_log(&cache_path, &source_path).is_none());
+        assert_eq!(
+            check_cache(&cache_path, &source_path, false),
+            CacheStatus::Stale
+        );
+
+        // 헤더가 없는 이전 형식이나 손상된 캐시는 panic 없이 corrupt
+        fs::write(&cache_path, b"not cbor").unwrap();
+        assert!(load_cached_chat_log(&cache_path, &source_path).is_none());
+        assert_eq!(
+            check_cache(&cache_path, &source_path, false),
+            CacheStatus::Corrupt
+        );
+
+        fs::remove_file(&source_path).unwrap();
+        let entries = scan_cache_dir(&dir, &dir, false).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].status, CacheStatus::Orphan);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -5,14 +5,15 @@ use std::sync::Arc;
 
 use chrono::{Duration as ChronoDuration, Utc};
 use color_eyre::eyre::{Context, Result};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::data::models::{ChannelWithReplays, ChatLog};
+use crate::data::chat::store::intersection_count;
+use crate::data::models::ChannelWithReplays;
 use crate::utils;
 use rayon::prelude::*;
 
 /// 채널 간 연결 정보 (링크)
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelLink {
     pub source: String,
     pub target: String,
@@ -41,48 +42,62 @@ struct ChannelDistanceJson {
     links: Vec<ChannelLink>,
 }
 
-/// 채널별 고유 사용자 집합을 구합니다.
-fn build_channel_user_map(
-    chat_logs: &[ChatLog],
+/// 비디오별 사용자 번호 목록으로 채널별 고유 사용자 번호 목록(정렬, 중복 제거)을 구합니다.
+/// 채팅 로그가 있는 비디오가 하나도 없는 채널은 포함하지 않습니다.
+pub fn build_channel_user_map(
+    video_users: &HashMap<u64, Vec<u32>>,
     channels: &[ChannelWithReplays],
-) -> HashMap<String, HashSet<String>> {
-    // video_id -> channel_id 매핑 생성
-    let video_to_channel: HashMap<u64, &str> = channels
-        .iter()
-        .flat_map(|channel| {
-            channel
-                .replays
-                .iter()
-                .map(move |replay| (replay.video_no, channel.channel_id.as_str()))
-        })
-        .collect();
-
-    // 채널별 고유 사용자 집합
-    let mut channel_users: HashMap<String, HashSet<String>> = HashMap::new();
-
-    for chat_log in chat_logs {
-        if let Some(channel_id) = video_to_channel.get(&chat_log.video_id) {
-            let users = channel_users.entry(channel_id.to_string()).or_default();
-
-            // 이 채팅 로그의 모든 고유 user_id 추가
-            for message in &chat_log.messages {
-                users.insert(message.user_id.clone());
+) -> HashMap<String, Vec<u32>> {
+    let mut channel_users: HashMap<String, Vec<u32>> = HashMap::new();
+
+    for channel in channels {
+        for replay in &channel.replays {
+            if let Some(users) = video_users.get(&replay.video_no) {
+                // 이 비디오의 모든 user 번호 추가
+                channel_users
+                    .entry(channel.channel_id.clone())
+                    .or_default()
+                    .extend_from_slice(users);
             }
         }
     }
 
+    for users in channel_users.values_mut() {
+        users.sort_unstable();
+        users.dedup();
+    }
+
     channel_users
 }
 
+/// 증분 계산에 사용할 이전 실행의 채널 링크
+pub struct PreviousLinks<'a> {
+    /// 이전 실행의 링크 (`max_nodes` 없이 계산한 것)
+    pub links: &'a [ChannelLink],
+    /// 사용자 집합이 바뀌었거나 새로 생긴 채널
+    pub changed: &'a HashSet<String>,
+}
+
+/// 순서와 관계없이 같은 채널 쌍을 가리키는 key
+fn pair_key<'a>(a: &'a str, b: &'a str) -> (&'a str, &'a str) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
 /// 채널 간 distance와 inter를 계산합니다.
+/// `previous`가 있으면 두 채널 모두 바뀌지 않은 쌍은 이전 inter를 그대로 쓰고,
+/// 바뀐 채널이 포함된 쌍만 교집합을 다시 계산합니다.
+/// distance는 Jaccard가 아닌 `inter / min(채팅 수)`라서 크기가 많이 다른 채널 쌍을 MinHash LSH로 고를 수 없으므로
+/// 유사도 계산 방식과 관계없이 항상 모든 쌍을 비교합니다.
 pub fn calculate_channel_distances(
-    chat_logs: &[ChatLog],
+    channel_users: &HashMap<String, Vec<u32>>,
     channels: &[ChannelWithReplays],
     max_nodes: Option<usize>,
+    previous: Option<&PreviousLinks>,
 ) -> (Vec<ChannelNode>, Vec<ChannelLink>) {
-    // 채널별 고유 사용자 집합 구하기
-    let channel_users = build_channel_user_map(chat_logs, channels);
-
     // 채널별 채팅 수 계산 (고유 사용자 수 = chat_count)
     let mut channel_nodes: Vec<ChannelNode> = channels
         .iter()
@@ -106,7 +121,7 @@ pub fn calculate_channel_distances(
         .collect();
 
     // 채팅 수 기준으로 정렬
-    channel_nodes.sort_by(|a, b| b.chat_count.cmp(&a.chat_count));
+    channel_nodes.sort_by_key(|node| std::cmp::Reverse(node.chat_count));
 
     // 상위 max_nodes개만 선택
     if let Some(max) = max_nodes {
@@ -119,6 +134,19 @@ pub fn calculate_channel_distances(
     let channel_users_arc = Arc::new(channel_users);
     let n = channel_nodes_arc.len();
 
+    // 이전 실행의 inter (채널 쌍 -> inter), 링크가 없던 쌍은 inter가 0
+    let previous_inters: HashMap<(&str, &str), usize> = previous
+        .map(|previous| {
+            previous
+                .links
+                .iter()
+                .map(|link| (pair_key(&link.source, &link.target), link.inter))
+                .collect()
+        })
+        .unwrap_or_default();
+    let previous_inters = &previous_inters;
+    let changed = previous.map(|previous| previous.changed);
+
     // Progress bar 생성 (총 쌍 수: n * (n - 1) / 2)
     let total_pairs = n * (n - 1) / 2;
     let pb = utils::create_progress_bar(total_pairs as u64, "Calculating channel distances...");
@@ -133,10 +161,10 @@ pub fn calculate_channel_distances(
             let pb_ref = Arc::clone(&pb_arc);
 
             let source_node = &channel_nodes_ref[i];
-            let source_users = channel_users_ref
+            let source_users = channel_users_arc
                 .get(&source_node.channel_id)
-                .cloned()
-                .unwrap_or_default();
+                .map(Vec::as_slice)
+                .unwrap_or(&[]);
             let source_channel_id = source_node.channel_id.clone();
             let source_chat_count = source_node.chat_count;
 
@@ -148,13 +176,24 @@ pub fn calculate_channel_distances(
                     let target_node = &channel_nodes_ref[j];
                     let target_users = channel_users_ref
                         .get(&target_node.channel_id)
-                        .cloned()
-                        .unwrap_or_default();
+                        .map(Vec::as_slice)
+                        .unwrap_or(&[]);
                     let target_channel_id = target_node.channel_id.clone();
                     let target_chat_count = target_node.chat_count;
 
-                    // 교집합 계산 (inter)
-                    let inter = source_users.intersection(&target_users).count();
+                    // 교집합 계산 (inter), 두 채널 모두 그대로면 이전 값 사용
+                    let inter = match changed {
+                        Some(changed)
+                            if !changed.contains(&source_channel_id)
+                                && !changed.contains(&target_channel_id) =>
+                        {
+                            previous_inters
+                                .get(&pair_key(&source_channel_id, &target_channel_id))
+                                .copied()
+                                .unwrap_or(0)
+                        }
+                        _ => intersection_count(source_users, target_users),
+                    };
 
                     // distance 계산: inter / MIN(source_cnt, target_cnt)
                     let min_count = source_chat_count.min(target_chat_count);
@@ -1,9 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
-use crate::data::models::ChatLog;
+use crate::data::chat::event_config::{EventDetectionConfig, EventMatchConfig};
+use crate::data::chat::order_stats::OrderStatisticTree;
+use crate::data::chat::store::{kst_datetime, ChatColumns};
 
 /// 이벤트 구간 (peak/폭발 구간)
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct EventInterval {
     /// 구간 시작 시간 (초, 첫 메시지 기준 0초)
     pub start_sec: i64,
@@ -30,37 +32,17 @@ pub struct EventDetectionResult {
 }
 
 /// 초 단위로 메시지를 resample합니다.
-fn resample_to_seconds(
-    messages: &[&crate::data::models::ChatMessage],
-    first_time: chrono::DateTime<chrono::FixedOffset>,
-) -> HashMap<i64, usize> {
+fn resample_to_seconds(timestamps: &[i64], first_time: i64) -> HashMap<i64, usize> {
     let mut count_map: HashMap<i64, usize> = HashMap::new();
 
-    for message in messages {
-        let elapsed_seconds = (message.timestamp - first_time).num_seconds();
+    for &timestamp in timestamps {
+        let elapsed_seconds = timestamp - first_time;
         *count_map.entry(elapsed_seconds).or_insert(0) += 1;
     }
 
     count_map
 }
 
-/// EWMA (Exponentially Weighted Moving Average) 계산
-fn ewma(values: &[f64], alpha: f64) -> Vec<f64> {
-    if values.is_empty() {
-        return Vec::new();
-    }
-
-    let mut smoothed = Vec::with_capacity(values.len());
-    let mut prev = values[0];
-
-    for &value in values {
-        prev = alpha * value + (1.0 - alpha) * prev;
-        smoothed.push(prev);
-    }
-
-    smoothed
-}
-
 /// Moving Average 계산
 #[allow(dead_code)]
 fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
@@ -81,41 +63,6 @@ fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
     smoothed
 }
 
-/// Median 계산
-fn median(values: &[f64]) -> f64 {
-    if values.is_empty() {
-        return 0.0;
-    }
-
-    let mut sorted = values.to_vec();
-    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-
-    let mid = sorted.len() / 2;
-    if sorted.len().is_multiple_of(2) {
-        (sorted[mid - 1] + sorted[mid]) / 2.0
-    } else {
-        sorted[mid]
-    }
-}
-
-/// MAD (Median Absolute Deviation) 계산
-fn mad(values: &[f64]) -> f64 {
-    if values.is_empty() {
-        return 1.0; // 기본값
-    }
-
-    let med = median(values);
-    let deviations: Vec<f64> = values.iter().map(|&v| (v - med).abs()).collect();
-    let mad_value = median(&deviations);
-
-    // MAD가 0이면 기본값 반환
-    if mad_value < 1e-10 {
-        1.0
-    } else {
-        mad_value
-    }
-}
-
 /// Local maximum 찾기
 #[allow(dead_code)]
 fn find_local_maxima(z_scores: &[f64], threshold: f64) -> Vec<usize> {
@@ -250,35 +197,19 @@ fn expand_intervals(
     events
 }
 
-/// ChatLog에서 이벤트 후보 구간을 탐지합니다.
-pub fn detect_event_intervals(chat_log: &ChatLog) -> Option<EventDetectionResult> {
-    if chat_log.messages.is_empty() {
-        return None;
-    }
-
-    // 상수 설정
-    const Z_TH: f64 = 8.0; // 피크 임계값
-    const Z_END: f64 = 2.5; // 구간 확장 임계값
-    const GAP_THRESHOLD_SEC: i64 = 12; // 병합 기준 (초)
-    const BASELINE_WINDOW_SEC: i64 = 600; // 10분
-    const BASELINE_LAG_SEC: i64 = 60; // 최근 60초 제외
-    const SMOOTH_ALPHA: f64 = 0.2; // EWMA alpha
-    const EPS: f64 = 1e-6; // MAD 분모 보정값
+/// MAD 분모 보정값
+const EPS: f64 = 1e-6;
 
-    // 메시지를 시간 순으로 정렬
-    let mut sorted_messages: Vec<_> = chat_log.messages.iter().collect();
-    sorted_messages.sort_by_key(|msg| msg.timestamp);
-
-    let first_time = sorted_messages[0].timestamp;
-
-    // 1. 초 단위로 resample
-    let count_map = resample_to_seconds(&sorted_messages, first_time);
+/// 초 단위 메시지 수 (time_sec 목록, rate 목록)
+/// 첫 메시지부터 마지막 메시지까지 1초 간격으로 빈 초도 0으로 채웁니다.
+fn per_second_rate(timestamps: &[i64], first_time: i64) -> (Vec<i64>, Vec<f64>) {
+    let count_map = resample_to_seconds(timestamps, first_time);
 
     // 시간 범위 찾기
     let min_sec = *count_map.keys().min().unwrap_or(&0);
     let max_sec = *count_map.keys().max().unwrap_or(&0);
 
-    // 2. rate[t] = count[t] (1초 bin) - 이미 초 단위로 되어 있음
+    // rate[t] = count[t] (1초 bin) - 이미 초 단위로 되어 있음
     let mut rate = Vec::new();
     let mut time_points = Vec::new();
     for sec in min_sec..=max_sec {
@@ -287,57 +218,99 @@ pub fn detect_event_intervals(chat_log: &ChatLog) -> Option<EventDetectionResult
         time_points.push(sec);
     }
 
-    if rate.is_empty() {
-        return None;
-    }
-
-    // 3. smooth[t] = EWMA(rate, alpha=0.2)
-    let smooth = ewma(&rate, SMOOTH_ALPHA);
-
-    // 4. base[t]와 mad[t] 계산 (10분 윈도우)
-    let mut base = Vec::new();
-    let mut mad_values = Vec::new();
-    let mut z_scores = Vec::new();
+    (time_points, rate)
+}
 
-    for i in 0..smooth.len() {
-        let current_time = time_points[i];
-        let window_start = (current_time - BASELINE_WINDOW_SEC).max(min_sec);
-        let window_end = (current_time - BASELINE_LAG_SEC).max(min_sec);
+/// 초 단위 메시지 수를 한 초씩 받아 z-score를 계산하는 baseline
+/// smooth[t] = EWMA(rate, alpha), z[t] = (smooth[t] - base[t]) / (mad[t] + eps)
+/// base[t]와 mad[t]는 smooth[t-window..t-lag] 구간의 median과 MAD입니다.
+/// 시간이 지나면 윈도우 양 끝이 앞으로만 움직이므로, 순서 통계 트리에 들어오는 값을 넣고
+/// 나가는 값을 빼면서 초마다 O(log² n)에 계산합니다. 과거 값만 쓰므로 라이브 방송에도 그대로 씁니다.
+#[derive(Debug, Clone)]
+pub struct RollingBaseline {
+    config: EventDetectionConfig,
+    /// 다음에 받을 초의 인덱스
+    index: usize,
+    /// 직전 smooth 값
+    smooth: Option<f64>,
+    /// 아직 윈도우에서 빠지지 않은 smooth 값 (`history_start`번째부터)
+    history: VecDeque<f64>,
+    history_start: usize,
+    window: OrderStatisticTree,
+    /// 트리에 들어 있는 smooth 인덱스 범위 [window_start, window_end)
+    window_start: usize,
+    window_end: usize,
+}
 
-        // 윈도우 내의 smooth 값들 수집
-        let mut window_values = Vec::new();
-        for j in 0..=i {
-            if time_points[j] >= window_start && time_points[j] <= window_end {
-                window_values.push(smooth[j]);
-            }
+impl RollingBaseline {
+    pub fn new(config: &EventDetectionConfig) -> Self {
+        Self {
+            config: *config,
+            index: 0,
+            smooth: None,
+            history: VecDeque::new(),
+            history_start: 0,
+            window: OrderStatisticTree::new(),
+            window_start: 0,
+            window_end: 0,
         }
+    }
 
-        // base[t] = median(smooth[t-10min..t])
-        let base_value = if window_values.is_empty() {
-            smooth[i]
-        } else {
-            median(&window_values)
-        };
-        base.push(base_value);
+    /// 다음 1초의 메시지 수를 넣고 그 초의 z-score를 반환합니다.
+    pub fn push(&mut self, rate: f64) -> f64 {
+        let alpha = self.config.smooth_alpha;
+        let prev = self.smooth.unwrap_or(rate);
+        let value = alpha * rate + (1.0 - alpha) * prev;
+        self.smooth = Some(value);
+        self.history.push_back(value);
+        let i = self.index;
+        self.index += 1;
+
+        // 1초 간격이므로 인덱스 차이가 시간 차이 (첫 시간 이전은 첫 시간으로 자름)
+        // 윈도우 시작이 끝보다 늦으면(윈도우 길이가 음수) 빈 윈도우
+        let end = ((i as i64 - self.config.baseline_lag_sec).max(0) as usize).min(i) + 1;
+        let start = ((i as i64 - self.config.baseline_window_sec).max(0) as usize).min(end);
+
+        while self.window_end < end {
+            self.window
+                .insert(self.history[self.window_end - self.history_start]);
+            self.window_end += 1;
+        }
+        while self.window_start < start {
+            self.window
+                .remove(self.history[self.window_start - self.history_start]);
+            self.window_start += 1;
+        }
+        // 윈도우 시작 이전 값은 다시 쓰지 않음
+        while self.history_start < self.window_start {
+            self.history.pop_front();
+            self.history_start += 1;
+        }
 
-        // mad[t] = MAD(smooth[t-10min..t])
-        let mad_value = if window_values.is_empty() {
-            1.0
-        } else {
-            mad(&window_values)
+        let (base_value, mad_value) = match (
+            self.window.median(),
+            self.window.median_absolute_deviation(),
+        ) {
+            // MAD가 0이면 기본값 1.0
+            (Some(base), Some(mad)) => (base, if mad < 1e-10 { 1.0 } else { mad }),
+            _ => (value, 1.0),
         };
-        mad_values.push(mad_value);
-
-        // z[t] = (smooth[t] - base[t]) / (mad[t] + eps)
-        let z = (smooth[i] - base_value) / (mad_value + EPS);
-        z_scores.push(z);
+        (value - base_value) / (mad_value + EPS)
     }
+}
 
-    // 5. z[t] > Z_TH 인 지점들을 이벤트 후보로 잡고 local maximum만 남기기
-    // let peak_indices = find_local_maxima(&z_scores, Z_TH);
-    let peak_indices = pick_peaks_from_runs(&z_scores, &rate, Z_TH);
+/// z-score에서 피크를 찾아 병합하고 구간을 확장합니다.
+fn events_from_z_scores(
+    time_points: &[i64],
+    rate: &[f64],
+    z_scores: &[f64],
+    config: &EventDetectionConfig,
+) -> Vec<EventInterval> {
+    // z[t] > z_th 인 지점들을 이벤트 후보로 잡고 local maximum만 남기기
+    // let peak_indices = find_local_maxima(z_scores, config.z_th);
+    let peak_indices = pick_peaks_from_runs(z_scores, rate, config.z_th);
 
-    // 6. 피크 정보 수집
+    // 피크 정보 수집
     let mut peaks: Vec<(usize, i64, f64, usize)> = Vec::new();
     for &idx in &peak_indices {
         let peak_sec = time_points[idx];
@@ -346,17 +319,38 @@ pub fn detect_event_intervals(chat_log: &ChatLog) -> Option<EventDetectionResult
         peaks.push((idx, peak_sec, peak_z, peak_count));
     }
 
-    // 7. 가까운 피크끼리 병합
-    let merged_peaks = merge_nearby_peaks(peaks, GAP_THRESHOLD_SEC);
+    // 가까운 피크끼리 병합
+    let merged_peaks = merge_nearby_peaks(peaks, config.gap_threshold_sec);
 
-    // 8. 구간 확장 (z[t] > Z_END 인 구간으로)
+    // 구간 확장 (z[t] > z_end 인 구간으로)
     let z_scores_with_time: Vec<(i64, f64)> = time_points
         .iter()
         .zip(z_scores.iter())
         .map(|(&t, &z)| (t, z))
         .collect();
 
-    let events = expand_intervals(merged_peaks, &z_scores_with_time, Z_END);
+    expand_intervals(merged_peaks, &z_scores_with_time, config.z_end)
+}
+
+/// 채팅 로그에서 이벤트 후보 구간을 탐지합니다.
+pub fn detect_event_intervals(
+    chat_log: &ChatColumns,
+    config: &EventDetectionConfig,
+) -> Option<EventDetectionResult> {
+    let first_time = chat_log.first_timestamp()?;
+
+    // 1. 초 단위로 resample
+    let (time_points, rate) = per_second_rate(chat_log.timestamps(), first_time);
+    if rate.is_empty() {
+        return None;
+    }
+
+    // 2. EWMA 후 baseline 윈도우의 median/MAD로 z-score 계산
+    let mut baseline = RollingBaseline::new(config);
+    let z_scores: Vec<f64> = rate.iter().map(|&r| baseline.push(r)).collect();
+
+    // 3. 피크 탐지, 병합, 구간 확장
+    let events = events_from_z_scores(&time_points, &rate, &z_scores, config);
 
     // 타임라인 데이터 생성 (디버깅용)
     let timeline: Vec<(i64, usize, f64)> = time_points
@@ -367,7 +361,7 @@ pub fn detect_event_intervals(chat_log: &ChatLog) -> Option<EventDetectionResult
         .collect();
 
     Some(EventDetectionResult {
-        first_message_time: first_time,
+        first_message_time: kst_datetime(first_time),
         events,
         timeline,
     })
@@ -471,10 +465,8 @@ fn estimate_offset(
     b_events: &[(usize, &EventInterval)],
     a_base_time: i64,
     b_base_time: i64,
-    _bin_size_sec: i64,
+    bin_size_sec: i64,
 ) -> f64 {
-    const BIN_SIZE: i64 = 10; // 10초 bin
-
     // 모든 쌍에 대해 delta 계산
     let mut deltas_with_weights: Vec<(i64, f64)> = Vec::new();
 
@@ -501,7 +493,7 @@ fn estimate_offset(
     let mut bin_map: std::collections::HashMap<i64, f64> = std::collections::HashMap::new();
 
     for (delta, weight) in &deltas_with_weights {
-        let bin = (delta / BIN_SIZE) * BIN_SIZE;
+        let bin = (delta / bin_size_sec) * bin_size_sec;
         *bin_map.entry(bin).or_insert(0.0) += weight;
     }
 
@@ -517,7 +509,7 @@ fn estimate_offset(
     let mut weight_sum = 0.0;
 
     for (delta, weight) in &deltas_with_weights {
-        if (delta - best_bin).abs() <= BIN_SIZE {
+        if (delta - best_bin).abs() <= bin_size_sec {
             weighted_sum += *delta as f64 * weight;
             weight_sum += weight;
         }
@@ -531,28 +523,24 @@ fn estimate_offset(
 }
 
 /// 두 VOD의 이벤트를 시간 정보만으로 매칭합니다.
-pub fn match_events_time_only(a: &EventDetectionResult, b: &EventDetectionResult) -> MatchResult {
-    // 상수 설정
-    const TOP_K: usize = 40; // 상위 K개 이벤트만 사용
-    const PEAK_WINDOW: i64 = 90; // 피크 근접 필터 (초)
-    const MIN_OVERLAP: i64 = 15; // 최소 겹침 (초)
-    const RATIO_TH: f64 = 0.35; // 최소 겹침 비율
-    const Z_MIN: f64 = 3.0; // 최소 z-score
-    const BIN_SIZE: i64 = 10; // offset 추정 bin 크기
-
+pub fn match_events_time_only(
+    a: &EventDetectionResult,
+    b: &EventDetectionResult,
+    config: &EventMatchConfig,
+) -> MatchResult {
     // 약한 이벤트 제외
     let a_events_filtered: Vec<(usize, &EventInterval)> = a
         .events
         .iter()
         .enumerate()
-        .filter(|(_, ev)| ev.peak_z_score >= Z_MIN)
+        .filter(|(_, ev)| ev.peak_z_score >= config.z_min)
         .collect();
 
     let b_events_filtered: Vec<(usize, &EventInterval)> = b
         .events
         .iter()
         .enumerate()
-        .filter(|(_, ev)| ev.peak_z_score >= Z_MIN)
+        .filter(|(_, ev)| ev.peak_z_score >= config.z_min)
         .collect();
 
     if a_events_filtered.is_empty() || b_events_filtered.is_empty() {
@@ -566,10 +554,16 @@ pub fn match_events_time_only(a: &EventDetectionResult, b: &EventDetectionResult
     let a_base_time = a.first_message_time.timestamp();
     let b_base_time = b.first_message_time.timestamp();
 
-    let a_top = select_top_events(&a.events, TOP_K);
-    let b_top = select_top_events(&b.events, TOP_K);
+    let a_top = select_top_events(&a.events, config.top_k);
+    let b_top = select_top_events(&b.events, config.top_k);
 
-    let offset_sec = estimate_offset(&a_top, &b_top, a_base_time, b_base_time, BIN_SIZE);
+    let offset_sec = estimate_offset(
+        &a_top,
+        &b_top,
+        a_base_time,
+        b_base_time,
+        config.bin_size_sec,
+    );
 
     // 2. B 이벤트를 abs_peak로 정렬 (binary search를 위해)
     let mut b_sorted: Vec<(usize, i64, &EventInterval)> = b_events_filtered
@@ -589,9 +583,9 @@ pub fn match_events_time_only(a: &EventDetectionResult, b: &EventDetectionResult
         let a_abs_peak = a_base_time + a_ev.peak_sec;
         let a_abs_peak_aligned = a_abs_peak + offset_sec as i64;
 
-        // Binary search로 PEAK_WINDOW 범위 내의 B 이벤트 찾기
-        let target_min = a_abs_peak_aligned - PEAK_WINDOW;
-        let target_max = a_abs_peak_aligned + PEAK_WINDOW;
+        // Binary search로 peak_window_sec 범위 내의 B 이벤트 찾기
+        let target_min = a_abs_peak_aligned - config.peak_window_sec;
+        let target_max = a_abs_peak_aligned + config.peak_window_sec;
 
         // Lower bound 찾기
         let start_idx = b_sorted
@@ -607,7 +601,7 @@ pub fn match_events_time_only(a: &EventDetectionResult, b: &EventDetectionResult
         for (b_idx, b_abs_peak, b_ev) in &b_sorted[start_idx..end_idx.min(b_sorted.len())] {
             let delta_peak = (b_abs_peak - a_abs_peak_aligned).abs();
 
-            if delta_peak > PEAK_WINDOW {
+            if delta_peak > config.peak_window_sec {
                 continue;
             }
 
@@ -621,7 +615,7 @@ pub fn match_events_time_only(a: &EventDetectionResult, b: &EventDetectionResult
             let overlap_end = a_abs_end.min(b_abs_end);
             let overlap_sec = (overlap_end - overlap_start).max(0);
 
-            if overlap_sec < MIN_OVERLAP {
+            if overlap_sec < config.min_overlap_sec {
                 continue;
             }
 
@@ -635,7 +629,7 @@ pub fn match_events_time_only(a: &EventDetectionResult, b: &EventDetectionResult
 
             let overlap_ratio = overlap_sec as f64 / min_len as f64;
 
-            if overlap_ratio < RATIO_TH {
+            if overlap_ratio < config.ratio_th {
                 continue;
             }
 
@@ -767,3 +761,180 @@ pub fn print_match_result(
         println!();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::chat::event_config::EventPreset;
+    use crate::data::chat::loader::parse_chat_line;
+    use crate::data::chat::store::ChatStore;
+    use crate::data::models::ChatLog;
+
+    /// EWMA (Exponentially Weighted Moving Average) 계산
+    fn ewma(values: &[f64], alpha: f64) -> Vec<f64> {
+        if values.is_empty() {
+            return Vec::new();
+        }
+
+        let mut smoothed = Vec::with_capacity(values.len());
+        let mut prev = values[0];
+
+        for &value in values {
+            prev = alpha * value + (1.0 - alpha) * prev;
+            smoothed.push(prev);
+        }
+
+        smoothed
+    }
+
+    /// Median 계산
+    fn median(values: &[f64]) -> f64 {
+        if values.is_empty() {
+            return 0.0;
+        }
+
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mid = sorted.len() / 2;
+        if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2.0
+        } else {
+            sorted[mid]
+        }
+    }
+
+    /// MAD (Median Absolute Deviation) 계산
+    fn mad(values: &[f64]) -> f64 {
+        if values.is_empty() {
+            return 1.0; // 기본값
+        }
+
+        let med = median(values);
+        let deviations: Vec<f64> = values.iter().map(|&v| (v - med).abs()).collect();
+        let mad_value = median(&deviations);
+
+        // MAD가 0이면 기본값 반환
+        if mad_value < 1e-10 {
+            1.0
+        } else {
+            mad_value
+        }
+    }
+
+    /// 기존 구현: 초마다 윈도우 값을 모두 모아 정렬해서 median/MAD를 계산 (O(n² log n))
+    fn naive_z_scores(
+        smooth: &[f64],
+        time_points: &[i64],
+        config: &EventDetectionConfig,
+    ) -> Vec<f64> {
+        let min_sec = time_points[0];
+        let mut z_scores = Vec::new();
+        for i in 0..smooth.len() {
+            let current_time = time_points[i];
+            let window_start = (current_time - config.baseline_window_sec).max(min_sec);
+            let window_end = (current_time - config.baseline_lag_sec).max(min_sec);
+
+            let mut window_values = Vec::new();
+            for j in 0..=i {
+                if time_points[j] >= window_start && time_points[j] <= window_end {
+                    window_values.push(smooth[j]);
+                }
+            }
+
+            let base_value = if window_values.is_empty() {
+                smooth[i]
+            } else {
+                median(&window_values)
+            };
+            let mad_value = if window_values.is_empty() {
+                1.0
+            } else {
+                mad(&window_values)
+            };
+            z_scores.push((smooth[i] - base_value) / (mad_value + EPS));
+        }
+        z_scores
+    }
+
+    #[test]
+    fn test_rolling_baseline_matches_naive() {
+        // 50분 분량, 평소 초당 0~3개에 가끔 폭발 구간
+        let mut seed = 11u64;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            seed >> 33
+        };
+        let mut messages = Vec::new();
+        for sec in 0..3000i64 {
+            let mut count = next() % 4;
+            if sec % 700 < 25 && sec > 600 {
+                count += 20 + next() % 20;
+            }
+            for _ in 0..count {
+                let time = chrono::NaiveDate::from_ymd_opt(2025, 1, 1)
+                    .unwrap()
+                    .and_hms_opt(12, 0, 0)
+                    .unwrap()
+                    + chrono::Duration::seconds(sec);
+                let line = format!(
+                    "[{}] n: m (u{})",
+                    time.format("%Y-%m-%d %H:%M:%S"),
+                    next() % 100
+                );
+                messages.push(parse_chat_line(&line).unwrap());
+            }
+        }
+        let store = ChatStore::from_chat_logs(vec![ChatLog {
+            video_id: 1,
+            messages,
+        }]);
+        let chat_log = store.get(1).unwrap();
+
+        let configs = [
+            EventDetectionConfig::default(),
+            EventPreset::Small.detection_config(0.0),
+            EventPreset::Medium.detection_config(0.0),
+            // lag 0, 윈도우가 lag보다 짧아 항상 빈 윈도우
+            EventDetectionConfig {
+                baseline_lag_sec: 0,
+                ..Default::default()
+            },
+            EventDetectionConfig {
+                baseline_window_sec: 30,
+                baseline_lag_sec: 60,
+                ..Default::default()
+            },
+            // 검증을 거치지 않은 값도 범위를 벗어나지 않음
+            EventDetectionConfig {
+                baseline_window_sec: -60,
+                ..Default::default()
+            },
+            EventDetectionConfig {
+                baseline_lag_sec: -30,
+                ..Default::default()
+            },
+        ];
+        for config in &configs {
+            let result = detect_event_intervals(chat_log, config).unwrap();
+
+            let first_time = chat_log.first_timestamp().unwrap();
+            let (time_points, rate) = per_second_rate(chat_log.timestamps(), first_time);
+            let smooth = ewma(&rate, config.smooth_alpha);
+            let naive = naive_z_scores(&smooth, &time_points, config);
+
+            if *config == EventDetectionConfig::default() {
+                assert!(!result.events.is_empty());
+            }
+
+            let z_scores: Vec<f64> = result.timeline.iter().map(|&(_, _, z)| z).collect();
+            assert_eq!(z_scores, naive);
+            assert_eq!(
+                result.events,
+                events_from_z_scores(&time_points, &rate, &naive, config)
+            );
+        }
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::{HashMap, VecDeque};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data::chat::event::{match_events_time_only, EventDetectionResult, EventInterval};
+use crate::data::chat::event_config::EventMatchConfig;
+
+/// 두 VOD의 offset을 믿으려면 필요한 최소 매칭 이벤트 수
+const MIN_PAIR_MATCHES: usize = 2;
+
+/// 정렬할 VOD 하나
+pub struct AlignVod<'a> {
+    pub video_id: u64,
+    /// 다시보기 시작 시간 (unix timestamp, 로컬 시간 계산 기준)
+    pub vod_start: i64,
+    pub result: &'a EventDetectionResult,
+}
+
+/// 두 VOD 사이의 offset 관측 (`a`의 절대 시간 + offset_sec ≈ `b`의 절대 시간)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairOffset {
+    /// VOD 인덱스
+    pub a: usize,
+    pub b: usize,
+    pub offset_sec: f64,
+    /// 매칭된 이벤트 수
+    pub matches: usize,
+    /// 매칭 점수 합 (최소제곱 가중치)
+    pub weight: f64,
+    /// 전체 정렬 결과와의 차이 (초, 정렬 후 채움)
+    pub residual_sec: Option<f64>,
+}
+
+/// VOD별 보정값 (기준 VOD 시계에 맞추려면 절대 시간에 더할 초)
+#[derive(Debug, Clone, PartialEq)]
+pub struct JointAlignment {
+    /// 기준 VOD 인덱스 (다른 VOD와 가장 많이 매칭된 VOD)
+    pub reference: usize,
+    /// 기준 VOD와 연결되지 않은 VOD는 None
+    pub corrections: Vec<Option<f64>>,
+    pub pairs: Vec<PairOffset>,
+}
+
+/// 여러 VOD에서 같은 시점에 터진 이벤트
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedMoment {
+    /// 기준 VOD 시계로 맞춘 피크 시간 (unix timestamp)
+    pub aligned_time: i64,
+    pub streams: Vec<MomentStream>,
+}
+
+/// 공통 순간에 포함된 VOD 하나의 이벤트
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MomentStream {
+    pub video_id: u64,
+    /// 다시보기 시작 기준 피크 시간 (초)
+    pub local_sec: i64,
+    pub event: EventInterval,
+}
+
+/// 모든 VOD 쌍의 이벤트를 매칭해 offset을 구합니다. 매칭이 적은 쌍은 버립니다.
+pub fn pairwise_offsets(vods: &[AlignVod], config: &EventMatchConfig) -> Vec<PairOffset> {
+    let pairs: Vec<(usize, usize)> = (0..vods.len())
+        .flat_map(|a| (a + 1..vods.len()).map(move |b| (a, b)))
+        .collect();
+
+    pairs
+        .into_par_iter()
+        .filter_map(|(a, b)| {
+            let result = match_events_time_only(vods[a].result, vods[b].result, config);
+            if result.matches.len() < MIN_PAIR_MATCHES {
+                return None;
+            }
+            Some(PairOffset {
+                a,
+                b,
+                offset_sec: result.offset_sec,
+                matches: result.matches.len(),
+                weight: result.matches.iter().map(|m| m.score).sum(),
+                residual_sec: None,
+            })
+        })
+        .collect()
+}
+
+/// 쌍별 offset을 함께 만족하는 VOD별 보정값을 가중 최소제곱으로 구합니다.
+/// c_a - c_b ≈ offset_ab 를 모든 쌍에 대해 Σ w (c_a - c_b - offset_ab)² 가 최소가 되도록 풀고,
+/// 기준 VOD의 보정값은 0으로 고정합니다. 쌍마다 따로 맞춘 offset이 서로 어긋나도 한 시계로 맞춰집니다.
+pub fn joint_alignment(vod_count: usize, mut pairs: Vec<PairOffset>) -> JointAlignment {
+    let mut degree = vec![0.0; vod_count];
+    let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); vod_count];
+    for pair in &pairs {
+        degree[pair.a] += pair.weight;
+        degree[pair.b] += pair.weight;
+        neighbors[pair.a].push(pair.b);
+        neighbors[pair.b].push(pair.a);
+    }
+    let reference = (0..vod_count)
+        .max_by(|&a, &b| {
+            degree[a]
+                .partial_cmp(&degree[b])
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.cmp(&a))
+        })
+        .unwrap_or(0);
+
+    // 기준 VOD와 연결된 VOD
+    let mut connected = vec![false; vod_count];
+    let mut queue = VecDeque::new();
+    if vod_count > 0 {
+        connected[reference] = true;
+        queue.push_back(reference);
+    }
+    while let Some(v) = queue.pop_front() {
+        for &next in &neighbors[v] {
+            if !connected[next] {
+                connected[next] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+
+    // 기준 VOD를 뺀 연결된 VOD의 정규 방정식 L c = r
+    let unknowns: Vec<usize> = (0..vod_count)
+        .filter(|&v| connected[v] && v != reference)
+        .collect();
+    let column: HashMap<usize, usize> = unknowns.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+    let n = unknowns.len();
+    let mut matrix = vec![vec![0.0; n + 1]; n];
+    for pair in pairs.iter().filter(|pair| connected[pair.a]) {
+        let (w, d) = (pair.weight, pair.offset_sec);
+        for (v, other, sign) in [(pair.a, pair.b, 1.0), (pair.b, pair.a, -1.0)] {
+            if let Some(&row) = column.get(&v) {
+                matrix[row][row] += w;
+                matrix[row][n] += sign * w * d;
+                if let Some(&col) = column.get(&other) {
+                    matrix[row][col] -= w;
+                }
+            }
+        }
+    }
+    let solution = solve_linear(matrix);
+
+    let mut corrections = vec![None; vod_count];
+    if vod_count > 0 {
+        corrections[reference] = Some(0.0);
+    }
+    for (i, &v) in unknowns.iter().enumerate() {
+        corrections[v] = Some(solution[i]);
+    }
+    for pair in &mut pairs {
+        if let (Some(ca), Some(cb)) = (corrections[pair.a], corrections[pair.b]) {
+            pair.residual_sec = Some(ca - cb - pair.offset_sec);
+        }
+    }
+
+    JointAlignment {
+        reference,
+        corrections,
+        pairs,
+    }
+}
+
+/// 첨가 행렬 [A | b]의 A x = b를 부분 피벗 가우스 소거로 풉니다.
+fn solve_linear(mut matrix: Vec<Vec<f64>>) -> Vec<f64> {
+    let n = matrix.len();
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&a, &b| {
+                matrix[a][col]
+                    .abs()
+                    .partial_cmp(&matrix[b][col].abs())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or(col);
+        matrix.swap(col, pivot);
+        let value = matrix[col][col];
+        if value.abs() < 1e-12 {
+            continue;
+        }
+        let pivot_row = matrix[col].clone();
+        for (row, values) in matrix.iter_mut().enumerate() {
+            let factor = values[col] / value;
+            if row != col && factor != 0.0 {
+                for (v, p) in values[col..].iter_mut().zip(&pivot_row[col..]) {
+                    *v -= factor * p;
+                }
+            }
+        }
+    }
+    (0..n)
+        .map(|i| {
+            if matrix[i][i].abs() < 1e-12 {
+                0.0
+            } else {
+                matrix[i][n] / matrix[i][i]
+            }
+        })
+        .collect()
+}
+
+/// 보정값으로 맞춘 피크 시간이 `window_sec` 안에 모인 이벤트를 공통 순간으로 묶습니다.
+/// VOD마다 z-score가 가장 높은 이벤트 하나만 쓰며, `min_streams`개 이상의 VOD에 나온 순간만 반환합니다.
+pub fn find_shared_moments(
+    vods: &[AlignVod],
+    corrections: &[Option<f64>],
+    config: &EventMatchConfig,
+    window_sec: i64,
+    min_streams: usize,
+) -> Vec<SharedMoment> {
+    // (맞춘 피크 시간, VOD 인덱스, 이벤트)
+    let mut peaks: Vec<(i64, usize, &EventInterval)> = Vec::new();
+    for (v, vod) in vods.iter().enumerate() {
+        let Some(correction) = corrections[v] else {
+            continue;
+        };
+        let base_time = vod.result.first_message_time.timestamp();
+        for event in &vod.result.events {
+            if event.peak_z_score >= config.z_min {
+                let aligned = base_time + event.peak_sec + correction.round() as i64;
+                peaks.push((aligned, v, event));
+            }
+        }
+    }
+    peaks.sort_by_key(|&(aligned, v, _)| (aligned, v));
+
+    let mut moments = Vec::new();
+    let mut i = 0;
+    while i < peaks.len() {
+        let first = peaks[i].0;
+        let mut end = i;
+        while end < peaks.len() && peaks[end].0 - first <= window_sec {
+            end += 1;
+        }
+
+        // VOD마다 가장 강한 이벤트
+        let mut best: HashMap<usize, (i64, &EventInterval)> = HashMap::new();
+        for &(aligned, v, event) in &peaks[i..end] {
+            let entry = best.entry(v).or_insert((aligned, event));
+            if event.peak_z_score > entry.1.peak_z_score {
+                *entry = (aligned, event);
+            }
+        }
+        if best.len() >= min_streams.max(2) {
+            let aligned_time =
+                best.values().map(|&(aligned, _)| aligned).sum::<i64>() / best.len() as i64;
+            let mut streams: Vec<MomentStream> = best
+                .into_iter()
+                .map(|(v, (_, event))| MomentStream {
+                    video_id: vods[v].video_id,
+                    local_sec: vods[v].result.first_message_time.timestamp() + event.peak_sec
+                        - vods[v].vod_start,
+                    event: event.clone(),
+                })
+                .collect();
+            streams.sort_by_key(|stream| stream.video_id);
+            moments.push(SharedMoment {
+                aligned_time,
+                streams,
+            });
+        }
+        i = end;
+    }
+    moments
+}
+
+/// 공통 순간에 포함된 VOD 수별 순간 수
+pub fn stream_count_histogram(moments: &[SharedMoment]) -> Vec<(usize, usize)> {
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for moment in moments {
+        *counts.entry(moment.streams.len()).or_insert(0) += 1;
+    }
+    let mut counts: Vec<(usize, usize)> = counts.into_iter().collect();
+    counts.sort();
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn result_at(first_message_time: i64, peaks: &[(i64, f64)]) -> EventDetectionResult {
+        EventDetectionResult {
+            first_message_time: chrono::FixedOffset::east_opt(9 * 3600)
+                .unwrap()
+                .timestamp_opt(first_message_time, 0)
+                .unwrap(),
+            events: peaks
+                .iter()
+                .map(|&(peak_sec, peak_z_score)| EventInterval {
+                    start_sec: peak_sec - 5,
+                    end_sec: peak_sec + 5,
+                    peak_sec,
+                    peak_z_score,
+                    peak_count: 100,
+                })
+                .collect(),
+            timeline: Vec::new(),
+        }
+    }
+
+    fn pair(a: usize, b: usize, offset_sec: f64, weight: f64) -> PairOffset {
+        PairOffset {
+            a,
+            b,
+            offset_sec,
+            matches: 3,
+            weight,
+            residual_sec: None,
+        }
+    }
+
+    #[test]
+    fn test_joint_alignment_reconciles_inconsistent_pairs() {
+        // 0→1 10초, 1→2 10초, 0→2는 23초로 관측 (쌍별로는 3초 어긋남)
+        let alignment = joint_alignment(
+            3,
+            vec![
+                pair(0, 1, 10.0, 1.0),
+                pair(1, 2, 10.0, 1.0),
+                pair(0, 2, 23.0, 1.0),
+            ],
+        );
+
+        let corrections: Vec<f64> = alignment.corrections.iter().map(|c| c.unwrap()).collect();
+        assert_eq!(corrections[alignment.reference], 0.0);
+        let d01 = corrections[0] - corrections[1];
+        let d12 = corrections[1] - corrections[2];
+        let d02 = corrections[0] - corrections[2];
+        assert!((d01 - 11.0).abs() < 1e-9, "{d01}");
+        assert!((d12 - 11.0).abs() < 1e-9, "{d12}");
+        assert!((d02 - 22.0).abs() < 1e-9, "{d02}");
+        for pair in &alignment.pairs {
+            assert!(pair.residual_sec.unwrap().abs() <= 1.0 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_joint_alignment_leaves_disconnected_vods_unaligned() {
+        let alignment = joint_alignment(3, vec![pair(0, 1, -5.0, 2.0)]);
+
+        assert!(alignment.corrections[2].is_none());
+        let c0 = alignment.corrections[0].unwrap();
+        let c1 = alignment.corrections[1].unwrap();
+        assert!((c0 - c1 + 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_shared_moments_report_local_times() {
+        // B는 A보다 채팅이 20초 늦게 올라오는 방송
+        let a = result_at(1_000, &[(100, 8.0), (500, 6.0), (900, 5.0)]);
+        let b = result_at(1_050, &[(70, 7.0), (470, 9.0), (2_000, 6.0)]);
+        let vods = [
+            AlignVod {
+                video_id: 1,
+                vod_start: 900,
+                result: &a,
+            },
+            AlignVod {
+                video_id: 2,
+                vod_start: 1_000,
+                result: &b,
+            },
+        ];
+        let corrections = [Some(20.0), Some(0.0)];
+
+        let moments = find_shared_moments(&vods, &corrections, &EventMatchConfig::default(), 10, 2);
+
+        assert_eq!(moments.len(), 2);
+        assert_eq!(moments[0].aligned_time, 1_120);
+        assert_eq!(
+            moments[0]
+                .streams
+                .iter()
+                .map(|stream| (stream.video_id, stream.local_sec))
+                .collect::<Vec<_>>(),
+            vec![(1, 200), (2, 120)]
+        );
+        assert_eq!(moments[1].streams[1].local_sec, 520);
+        assert_eq!(stream_count_histogram(&moments), vec![(2, 2)]);
+    }
+}
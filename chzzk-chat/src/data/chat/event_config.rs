@@ -0,0 +1,564 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use color_eyre::eyre::{eyre, Context, Result};
+use serde::{Deserialize, Serialize};
+use structopt::StructOpt;
+
+use crate::data::chat::store::ChatColumns;
+
+/// 이벤트 탐지 파라미터
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EventDetectionConfig {
+    /// 피크 임계값 (z-score)
+    pub z_th: f64,
+    /// 구간 확장 임계값 (z-score)
+    pub z_end: f64,
+    /// 피크 병합 기준 (초)
+    pub gap_threshold_sec: i64,
+    /// baseline 윈도우 길이 (초)
+    pub baseline_window_sec: i64,
+    /// baseline에서 제외할 최근 구간 (초)
+    pub baseline_lag_sec: i64,
+    /// EWMA alpha
+    pub smooth_alpha: f64,
+}
+
+impl Default for EventDetectionConfig {
+    /// 시청자 수 만 명 규모 방송 기준 값 (`large` preset)
+    fn default() -> Self {
+        Self {
+            z_th: 8.0,
+            z_end: 2.5,
+            gap_threshold_sec: 12,
+            baseline_window_sec: 600,
+            baseline_lag_sec: 60,
+            smooth_alpha: 0.2,
+        }
+    }
+}
+
+impl EventDetectionConfig {
+    /// baseline 윈도우와 EWMA alpha가 탐지에 쓸 수 있는 범위인지 확인합니다.
+    pub fn validate(&self) -> Result<()> {
+        if self.baseline_window_sec <= 0 {
+            return Err(eyre!(
+                "baseline_window_sec must be positive: {}",
+                self.baseline_window_sec
+            ));
+        }
+        if self.baseline_lag_sec < 0 {
+            return Err(eyre!(
+                "baseline_lag_sec must not be negative: {}",
+                self.baseline_lag_sec
+            ));
+        }
+        if !(self.smooth_alpha > 0.0 && self.smooth_alpha <= 1.0) {
+            return Err(eyre!(
+                "smooth_alpha must be in (0, 1]: {}",
+                self.smooth_alpha
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 두 VOD 이벤트 매칭 파라미터
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventMatchConfig {
+    /// offset 추정에 사용할 상위 이벤트 수 (peak_z_score 기준)
+    pub top_k: usize,
+    /// 피크 근접 필터 (초)
+    pub peak_window_sec: i64,
+    /// 최소 겹침 (초)
+    pub min_overlap_sec: i64,
+    /// 최소 겹침 비율
+    pub ratio_th: f64,
+    /// 매칭에 사용할 최소 z-score
+    pub z_min: f64,
+    /// offset 추정 bin 크기 (초)
+    pub bin_size_sec: i64,
+}
+
+impl Default for EventMatchConfig {
+    fn default() -> Self {
+        Self {
+            top_k: 40,
+            peak_window_sec: 90,
+            min_overlap_sec: 15,
+            ratio_th: 0.35,
+            z_min: 3.0,
+            bin_size_sec: 10,
+        }
+    }
+}
+
+impl EventMatchConfig {
+    /// offset 추정 bin 크기가 양수인지 확인합니다.
+    pub fn validate(&self) -> Result<()> {
+        if self.bin_size_sec <= 0 {
+            return Err(eyre!(
+                "bin_size_sec must be positive: {}",
+                self.bin_size_sec
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 채팅량별 이벤트 탐지 preset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventPreset {
+    /// 비디오마다 채팅량으로 small, medium, large 중 선택
+    Auto,
+    /// 초당 평균 메시지 1개 미만
+    Small,
+    /// 초당 평균 메시지 1개 이상 5개 미만
+    Medium,
+    /// 초당 평균 메시지 5개 이상 (기존 고정값)
+    #[default]
+    Large,
+}
+
+impl EventPreset {
+    /// 초당 평균 메시지 수에 맞는 preset
+    pub fn for_chat_rate(messages_per_sec: f64) -> Self {
+        if messages_per_sec < 1.0 {
+            EventPreset::Small
+        } else if messages_per_sec < 5.0 {
+            EventPreset::Medium
+        } else {
+            EventPreset::Large
+        }
+    }
+
+    /// preset의 탐지 파라미터 (`Auto`면 초당 평균 메시지 수로 preset을 고릅니다)
+    /// 채팅이 적으면 초당 메시지 수가 0, 1 근처에 몰려 MAD가 작고 z-score가 낮게 나오므로
+    /// 임계값을 낮추고 병합 간격과 baseline 윈도우를 늘립니다.
+    pub fn detection_config(self, messages_per_sec: f64) -> EventDetectionConfig {
+        match self {
+            EventPreset::Auto => {
+                Self::for_chat_rate(messages_per_sec).detection_config(messages_per_sec)
+            }
+            EventPreset::Small => EventDetectionConfig {
+                z_th: 3.0,
+                z_end: 1.0,
+                gap_threshold_sec: 30,
+                baseline_window_sec: 900,
+                baseline_lag_sec: 60,
+                smooth_alpha: 0.3,
+            },
+            EventPreset::Medium => EventDetectionConfig {
+                z_th: 5.0,
+                z_end: 2.0,
+                gap_threshold_sec: 20,
+                baseline_window_sec: 600,
+                baseline_lag_sec: 60,
+                smooth_alpha: 0.25,
+            },
+            EventPreset::Large => EventDetectionConfig::default(),
+        }
+    }
+}
+
+impl FromStr for EventPreset {
+    type Err = color_eyre::eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "auto" => Ok(EventPreset::Auto),
+            "small" => Ok(EventPreset::Small),
+            "medium" => Ok(EventPreset::Medium),
+            "large" => Ok(EventPreset::Large),
+            _ => Err(eyre!(
+                "Unknown event preset: {} (auto, small, medium, large)",
+                s
+            )),
+        }
+    }
+}
+
+/// 채팅 로그의 초당 평균 메시지 수 (첫 메시지부터 마지막 메시지까지)
+pub fn chat_rate(chat_log: &ChatColumns) -> f64 {
+    let timestamps = chat_log.timestamps();
+    let (Some(first), Some(last)) = (timestamps.iter().min(), timestamps.iter().max()) else {
+        return 0.0;
+    };
+    timestamps.len() as f64 / (last - first + 1) as f64
+}
+
+// 이벤트 탐지 파라미터 덮어쓰기 (설정 파일과 CLI 플래그 공통, 지정한 값만 적용)
+// flatten하는 StructOpt 구조체라 doc comment를 쓰면 subcommand 설명을 덮어씀
+#[derive(StructOpt, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventDetectionOverrides {
+    /// 피크 임계값 (z-score)
+    #[structopt(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z_th: Option<f64>,
+
+    /// 구간 확장 임계값 (z-score)
+    #[structopt(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z_end: Option<f64>,
+
+    /// 피크 병합 기준 (초)
+    #[structopt(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gap_threshold_sec: Option<i64>,
+
+    /// baseline 윈도우 길이 (초)
+    #[structopt(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_window_sec: Option<i64>,
+
+    /// baseline에서 제외할 최근 구간 (초)
+    #[structopt(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_lag_sec: Option<i64>,
+
+    /// EWMA alpha
+    #[structopt(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smooth_alpha: Option<f64>,
+}
+
+impl EventDetectionOverrides {
+    /// 지정한 값을 `config`에 덮어씁니다.
+    pub fn apply(&self, config: &mut EventDetectionConfig) {
+        if let Some(z_th) = self.z_th {
+            config.z_th = z_th;
+        }
+        if let Some(z_end) = self.z_end {
+            config.z_end = z_end;
+        }
+        if let Some(gap_threshold_sec) = self.gap_threshold_sec {
+            config.gap_threshold_sec = gap_threshold_sec;
+        }
+        if let Some(baseline_window_sec) = self.baseline_window_sec {
+            config.baseline_window_sec = baseline_window_sec;
+        }
+        if let Some(baseline_lag_sec) = self.baseline_lag_sec {
+            config.baseline_lag_sec = baseline_lag_sec;
+        }
+        if let Some(smooth_alpha) = self.smooth_alpha {
+            config.smooth_alpha = smooth_alpha;
+        }
+    }
+
+    /// `other`에서 지정한 값으로 덮어쓴 새 덮어쓰기 값
+    pub fn merged(&self, other: &Self) -> Self {
+        Self {
+            z_th: other.z_th.or(self.z_th),
+            z_end: other.z_end.or(self.z_end),
+            gap_threshold_sec: other.gap_threshold_sec.or(self.gap_threshold_sec),
+            baseline_window_sec: other.baseline_window_sec.or(self.baseline_window_sec),
+            baseline_lag_sec: other.baseline_lag_sec.or(self.baseline_lag_sec),
+            smooth_alpha: other.smooth_alpha.or(self.smooth_alpha),
+        }
+    }
+}
+
+// 이벤트 매칭 파라미터 덮어쓰기 (CLI 플래그, 지정한 값만 적용)
+// flatten하는 StructOpt 구조체라 doc comment를 쓰면 subcommand 설명을 덮어씀
+#[derive(StructOpt, Debug, Clone, Default, PartialEq)]
+pub struct EventMatchOverrides {
+    /// offset 추정에 사용할 상위 이벤트 수 (peak_z_score 기준)
+    #[structopt(long = "match-top-k")]
+    pub top_k: Option<usize>,
+
+    /// 매칭 피크 근접 필터 (초)
+    #[structopt(long = "match-peak-window-sec")]
+    pub peak_window_sec: Option<i64>,
+
+    /// 매칭 최소 겹침 (초)
+    #[structopt(long = "match-min-overlap-sec")]
+    pub min_overlap_sec: Option<i64>,
+
+    /// 매칭 최소 겹침 비율
+    #[structopt(long = "match-ratio-th")]
+    pub ratio_th: Option<f64>,
+
+    /// 매칭에 사용할 최소 z-score
+    #[structopt(long = "match-z-min")]
+    pub z_min: Option<f64>,
+
+    /// offset 추정 bin 크기 (초)
+    #[structopt(long = "match-bin-size-sec")]
+    pub bin_size_sec: Option<i64>,
+}
+
+impl EventMatchOverrides {
+    /// 지정한 값을 `config`에 덮어씁니다.
+    pub fn apply(&self, config: &mut EventMatchConfig) {
+        if let Some(top_k) = self.top_k {
+            config.top_k = top_k;
+        }
+        if let Some(peak_window_sec) = self.peak_window_sec {
+            config.peak_window_sec = peak_window_sec;
+        }
+        if let Some(min_overlap_sec) = self.min_overlap_sec {
+            config.min_overlap_sec = min_overlap_sec;
+        }
+        if let Some(ratio_th) = self.ratio_th {
+            config.ratio_th = ratio_th;
+        }
+        if let Some(z_min) = self.z_min {
+            config.z_min = z_min;
+        }
+        if let Some(bin_size_sec) = self.bin_size_sec {
+            config.bin_size_sec = bin_size_sec;
+        }
+    }
+}
+
+/// 이벤트 설정 파일 (JSON)
+/// 예: `{"preset": "auto", "detection": {"z_th": 6.0}, "matching": {"top_k": 20}}`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventConfigFile {
+    /// 채팅량 preset (없으면 `large`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset: Option<EventPreset>,
+    /// preset 위에 덮어쓸 탐지 파라미터
+    pub detection: EventDetectionOverrides,
+    /// 매칭 파라미터 (지정하지 않은 값은 기본값)
+    pub matching: EventMatchConfig,
+}
+
+impl EventConfigFile {
+    /// 설정 파일을 읽습니다.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read event config: {:?}", path))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse event config: {:?}", path))
+    }
+
+    /// 설정 파일을 저장합니다.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let json_string = serde_json::to_string_pretty(self)
+            .context("Failed to serialize event config to JSON")?;
+        fs::write(path, json_string)
+            .with_context(|| format!("Failed to write event config: {:?}", path))
+    }
+}
+
+// 이벤트 탐지 설정 옵션 (설정 파일, preset, 파라미터 플래그)
+#[derive(StructOpt, Debug, Clone, Default)]
+pub struct EventConfigOpt {
+    /// 이벤트 설정 파일 경로 (JSON, CLI 플래그가 파일 값보다 우선)
+    #[structopt(long)]
+    pub event_config: Option<PathBuf>,
+
+    /// 채팅량 preset (auto, small, medium, large, 기본값: 설정 파일 또는 large)
+    #[structopt(long)]
+    pub event_preset: Option<EventPreset>,
+
+    #[structopt(flatten)]
+    pub detection: EventDetectionOverrides,
+
+    #[structopt(flatten)]
+    pub matching: EventMatchOverrides,
+}
+
+impl EventConfigOpt {
+    /// 설정 파일과 CLI 플래그를 합칩니다. (preset < 설정 파일 < CLI 플래그, 매칭은 기본값 < 설정 파일 < CLI 플래그)
+    pub fn resolve(&self) -> Result<EventSettings> {
+        let file = match &self.event_config {
+            Some(path) => EventConfigFile::load(path)?,
+            None => EventConfigFile::default(),
+        };
+        let mut matching = file.matching;
+        self.matching.apply(&mut matching);
+        let settings = EventSettings {
+            preset: self.event_preset.or(file.preset).unwrap_or_default(),
+            detection: file.detection.merged(&self.detection),
+            matching,
+        };
+        settings.validate()?;
+        Ok(settings)
+    }
+}
+
+/// 설정 파일과 CLI 플래그를 합친 이벤트 설정
+#[derive(Debug, Clone, Default)]
+pub struct EventSettings {
+    pub preset: EventPreset,
+    pub detection: EventDetectionOverrides,
+    pub matching: EventMatchConfig,
+}
+
+impl EventSettings {
+    /// 채팅 로그에 적용할 탐지 파라미터 (preset 값에 덮어쓰기 값 적용)
+    pub fn detection_config(&self, chat_log: &ChatColumns) -> EventDetectionConfig {
+        self.detection_config_for_rate(chat_rate(chat_log))
+    }
+
+    /// 초당 평균 메시지 수가 `messages_per_sec`인 방송에 적용할 탐지 파라미터
+    pub fn detection_config_for_rate(&self, messages_per_sec: f64) -> EventDetectionConfig {
+        let mut config = self.preset.detection_config(messages_per_sec);
+        self.detection.apply(&mut config);
+        config
+    }
+
+    /// 이 설정으로 만들 수 있는 모든 탐지 파라미터(`auto`면 모든 preset)와 매칭 파라미터를 확인합니다.
+    pub fn validate(&self) -> Result<()> {
+        let presets = match self.preset {
+            EventPreset::Auto => vec![EventPreset::Small, EventPreset::Medium, EventPreset::Large],
+            preset => vec![preset],
+        };
+        for preset in presets {
+            let mut config = preset.detection_config(0.0);
+            self.detection.apply(&mut config);
+            config
+                .validate()
+                .context("Invalid event detection config")?;
+        }
+        self.matching
+            .validate()
+            .context("Invalid event matching config")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::chat::loader::parse_chat_line;
+    use crate::data::chat::store::ChatStore;
+    use crate::data::models::ChatLog;
+
+    #[test]
+    fn test_event_settings_layering() {
+        // 10초 동안 메시지 5개 -> 초당 0.5개
+        let store = ChatStore::from_chat_logs(vec![ChatLog {
+            video_id: 1,
+            messages: [0, 2, 4, 6, 9]
+                .iter()
+                .map(|sec| parse_chat_line(&format!("[2025-01-01 12:00:0{}] n: m (u)", sec)))
+                .map(Option::unwrap)
+                .collect(),
+        }]);
+        let chat_log = store.get(1).unwrap();
+        assert!((chat_rate(chat_log) - 0.5).abs() < 1e-9);
+        assert_eq!(
+            EventPreset::Auto.detection_config(0.5),
+            EventPreset::Small.detection_config(0.5)
+        );
+
+        let path = std::env::temp_dir().join(format!(
+            "chzzk-chat-event-config-{}.json",
+            std::process::id()
+        ));
+        fs::write(
+            &path,
+            r#"{"preset": "auto", "detection": {"z_th": 4.5, "z_end": 1.5}, "matching": {"top_k": 20}}"#,
+        )
+        .unwrap();
+
+        let opt = EventConfigOpt {
+            event_config: Some(path.clone()),
+            event_preset: None,
+            detection: EventDetectionOverrides {
+                z_th: Some(6.0),
+                ..Default::default()
+            },
+            matching: EventMatchOverrides {
+                ratio_th: Some(0.5),
+                ..Default::default()
+            },
+        };
+        let settings = opt.resolve().unwrap();
+        assert_eq!(settings.preset, EventPreset::Auto);
+        assert_eq!(settings.matching.top_k, 20);
+        assert_eq!(settings.matching.ratio_th, 0.5);
+        assert_eq!(settings.matching.z_min, EventMatchConfig::default().z_min);
+
+        // CLI 플래그 > 설정 파일 > preset(small)
+        let config = settings.detection_config(chat_log);
+        assert_eq!(config.z_th, 6.0);
+        assert_eq!(config.z_end, 1.5);
+        assert_eq!(config.gap_threshold_sec, 30);
+
+        // 설정 파일 없이 기본값은 기존 고정값
+        let config = EventConfigOpt::default()
+            .resolve()
+            .unwrap()
+            .detection_config(chat_log);
+        assert_eq!(config, EventDetectionConfig::default());
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_params() {
+        let invalid = [
+            EventDetectionOverrides {
+                baseline_window_sec: Some(0),
+                ..Default::default()
+            },
+            EventDetectionOverrides {
+                baseline_window_sec: Some(-60),
+                ..Default::default()
+            },
+            EventDetectionOverrides {
+                baseline_lag_sec: Some(-1),
+                ..Default::default()
+            },
+            EventDetectionOverrides {
+                smooth_alpha: Some(0.0),
+                ..Default::default()
+            },
+            EventDetectionOverrides {
+                smooth_alpha: Some(1.5),
+                ..Default::default()
+            },
+        ];
+        for detection in invalid {
+            let opt = EventConfigOpt {
+                event_preset: Some(EventPreset::Auto),
+                detection,
+                ..Default::default()
+            };
+            assert!(opt.resolve().is_err(), "{:?}", opt.detection);
+        }
+
+        let opt = EventConfigOpt {
+            matching: EventMatchOverrides {
+                bin_size_sec: Some(0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(opt.resolve().is_err());
+
+        // 설정 파일의 값도 확인
+        let path = std::env::temp_dir().join(format!(
+            "chzzk-chat-event-config-invalid-{}.json",
+            std::process::id()
+        ));
+        fs::write(&path, r#"{"matching": {"bin_size_sec": 0}}"#).unwrap();
+        let opt = EventConfigOpt {
+            event_config: Some(path.clone()),
+            ..Default::default()
+        };
+        assert!(opt.resolve().is_err());
+        fs::remove_file(&path).ok();
+
+        // 경계값은 허용
+        let opt = EventConfigOpt {
+            detection: EventDetectionOverrides {
+                baseline_lag_sec: Some(0),
+                smooth_alpha: Some(1.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(opt.resolve().is_ok());
+    }
+}
@@ -0,0 +1,213 @@
+use std::io::Read;
+
+use color_eyre::eyre::{eyre, Context, Result};
+
+use crate::data::chat::event::EventInterval;
+use crate::data::utils::parse_replay_time;
+
+/// 하이라이트 라벨 시간
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelTime {
+    /// 다시보기 시작 기준 초 (`1:23:45`, `83:45`, `5025`)
+    Offset(i64),
+    /// 절대 시간 (unix timestamp, `2025-01-01 12:34:56`)
+    Absolute(i64),
+}
+
+impl LabelTime {
+    /// `HH:MM:SS`, `MM:SS`, 초, 또는 `parse_replay_time` 형식의 시간을 파싱합니다.
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.contains('-') {
+            return Ok(LabelTime::Absolute(parse_replay_time(s)?.timestamp()));
+        }
+
+        let parts: Vec<&str> = s.split(':').collect();
+        if parts.len() > 3 {
+            return Err(eyre!("Invalid label time: {}", s));
+        }
+        let mut offset = 0;
+        for part in parts {
+            let value: i64 = part
+                .parse()
+                .map_err(|_| eyre!("Invalid label time: {}", s))?;
+            offset = offset * 60 + value;
+        }
+        Ok(LabelTime::Offset(offset))
+    }
+
+    /// 첫 메시지 기준 초로 바꿉니다. `Offset`은 `vod_start`(unix timestamp) 기준입니다.
+    pub fn event_sec(self, vod_start: i64, first_message_time: i64) -> i64 {
+        match self {
+            LabelTime::Offset(offset) => vod_start + offset - first_message_time,
+            LabelTime::Absolute(timestamp) => timestamp - first_message_time,
+        }
+    }
+}
+
+/// 알려진 하이라이트 라벨
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HighlightLabel {
+    pub video_id: u64,
+    pub time: LabelTime,
+}
+
+/// 하이라이트 라벨 CSV를 읽습니다. 첫 줄은 `video_id`, `time` 열이 있는 헤더이고 다른 열은 무시합니다.
+pub fn read_highlight_labels<R: Read>(reader: R) -> Result<Vec<HighlightLabel>> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let headers = reader
+        .headers()
+        .context("Failed to read label header")?
+        .clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim() == name)
+            .ok_or_else(|| eyre!("Missing label column: {}", name))
+    };
+    let (video_id_column, time_column) = (column("video_id")?, column("time")?);
+
+    let mut labels = Vec::new();
+    for (line, record) in reader.records().enumerate() {
+        // 헤더가 1번째 줄
+        let line = line + 2;
+        let record = record.with_context(|| format!("Invalid label at line {}", line))?;
+        let field = |column: usize| {
+            record
+                .get(column)
+                .map(str::trim)
+                .ok_or_else(|| eyre!("Invalid label at line {}", line))
+        };
+        labels.push(HighlightLabel {
+            video_id: field(video_id_column)?
+                .parse()
+                .with_context(|| format!("Invalid video_id at line {}", line))?,
+            time: LabelTime::parse(field(time_column)?)
+                .with_context(|| format!("Invalid label at line {}", line))?,
+        });
+    }
+    Ok(labels)
+}
+
+/// 라벨 대비 이벤트 탐지 성능
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EventScore {
+    /// 탐지한 이벤트 수
+    pub events: usize,
+    /// 라벨이 하나 이상 들어간 이벤트 수
+    pub matched_events: usize,
+    /// 라벨 수
+    pub labels: usize,
+    /// 이벤트에 들어간 라벨 수
+    pub found_labels: usize,
+}
+
+impl EventScore {
+    /// 라벨이 있는 이벤트 비율 (이벤트가 없으면 0)
+    pub fn precision(&self) -> f64 {
+        if self.events == 0 {
+            0.0
+        } else {
+            self.matched_events as f64 / self.events as f64
+        }
+    }
+
+    /// 찾은 라벨 비율 (라벨이 없으면 0)
+    pub fn recall(&self) -> f64 {
+        if self.labels == 0 {
+            0.0
+        } else {
+            self.found_labels as f64 / self.labels as f64
+        }
+    }
+
+    pub fn f1(&self) -> f64 {
+        let (precision, recall) = (self.precision(), self.recall());
+        if precision + recall > 0.0 {
+            2.0 * precision * recall / (precision + recall)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn add(&mut self, other: EventScore) {
+        self.events += other.events;
+        self.matched_events += other.matched_events;
+        self.labels += other.labels;
+        self.found_labels += other.found_labels;
+    }
+}
+
+/// 한 비디오의 이벤트 구간을 라벨 시간(첫 메시지 기준 초)과 비교합니다.
+/// 라벨이 구간 앞뒤로 `tolerance_sec`까지 넓힌 범위 안에 있으면 맞은 것으로 봅니다.
+pub fn score_events(
+    events: &[EventInterval],
+    label_secs: &[i64],
+    tolerance_sec: i64,
+) -> EventScore {
+    let covers = |event: &EventInterval, label: i64| {
+        event.start_sec - tolerance_sec <= label && label <= event.end_sec + tolerance_sec
+    };
+
+    EventScore {
+        events: events.len(),
+        matched_events: events
+            .iter()
+            .filter(|event| label_secs.iter().any(|&label| covers(event, label)))
+            .count(),
+        labels: label_secs.len(),
+        found_labels: label_secs
+            .iter()
+            .filter(|&&label| events.iter().any(|event| covers(event, label)))
+            .count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(start_sec: i64, end_sec: i64) -> EventInterval {
+        EventInterval {
+            start_sec,
+            end_sec,
+            peak_sec: start_sec,
+            peak_z_score: 10.0,
+            peak_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_score_events_with_labels() {
+        let labels = read_highlight_labels(
+            "video_id,time,note\n1,1:00:05,goal\n1,90\n2,2025-01-01 12:00:30,\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(labels[0].time, LabelTime::Offset(3605));
+        assert_eq!(labels[1].time, LabelTime::Offset(90));
+        assert_eq!(labels[2].video_id, 2);
+
+        // 다시보기 시작 100초 뒤에 첫 메시지
+        let label_secs: Vec<i64> = labels[..2]
+            .iter()
+            .map(|label| label.time.event_sec(1000, 1100))
+            .collect();
+        assert_eq!(label_secs, vec![3505, -10]);
+
+        let events = [event(0, 20), event(3400, 3480), event(5000, 5010)];
+        let score = score_events(&events, &label_secs, 30);
+        assert_eq!(
+            score,
+            EventScore {
+                events: 3,
+                matched_events: 2,
+                labels: 2,
+                found_labels: 2,
+            }
+        );
+        assert!((score.f1() - 0.8).abs() < 1e-9);
+
+        assert_eq!(score_events(&events, &label_secs, 0).found_labels, 0);
+        assert!(LabelTime::parse("1:2:3:4").is_err());
+    }
+}
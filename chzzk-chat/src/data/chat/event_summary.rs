@@ -0,0 +1,432 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::data::chat::store::ChatColumns;
+
+/// log-odds 사전 분포 가중치 (VOD 전체 빈도 × 가중치를 가상 관측으로 더함)
+const PRIOR_WEIGHT: f64 = 0.1;
+/// 라벨로 쓰려면 이벤트 안에서 최소 이만큼의 메시지에 나와야 함
+const MIN_LABEL_COUNT: usize = 3;
+/// 메시지 전체를 반복 문구로 볼 최대 길이 (글자)
+const MAX_PHRASE_CHARS: usize = 30;
+/// 대표 반응으로 보려면 이벤트 메시지 중 이 비율 이상이 해당 반응이어야 함
+const MIN_REACTION_SHARE: f64 = 0.1;
+/// 후원 반응으로 보려면 이벤트 구간에 최소 이만큼의 후원/구독 기록이 있어야 함
+const MIN_EVENT_DONATIONS: usize = 2;
+/// 후원 반응으로 보려면 이벤트 구간의 초당 후원 수가 나머지 구간의 이 배수 이상이어야 함
+const MIN_DONATION_LIFT: f64 = 3.0;
+
+/// 라벨 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelKind {
+    /// 공백으로 나눈 단어 (`ㅋㅋㅋ`, `?`)
+    Token,
+    /// 이모티콘 (`{:d_12:}`)
+    Emote,
+    /// 두 단어 이상의 짧은 메시지 전체 (반복되는 밈)
+    Phrase,
+}
+
+/// 이벤트에서 VOD 평소보다 많이 나온 단어/이모티콘/문구
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventLabel {
+    pub text: String,
+    pub kind: LabelKind,
+    /// 이벤트에서 이 라벨이 나온 메시지 수
+    pub count: usize,
+    /// log-odds z-score (평소 대비 과대 표현 정도)
+    pub score: f64,
+}
+
+/// 이벤트 반응 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReactionClass {
+    /// `ㅋㅋ`, `ㅎㅎ`, `lol`
+    Laughter,
+    /// `??`, `?!`, `ㄷㄷ`, `헐`, `ㄴㅇㄱ`
+    Surprise,
+    /// `gg`, `ㄱㄱ`, `나이스`, `축하`
+    Cheering,
+    /// 이벤트 구간에 몰린 후원/구독 기록 (`chatLog-{id}.donations.jsonl`)
+    Donation,
+}
+
+impl ReactionClass {
+    const ALL: [ReactionClass; 4] = [
+        ReactionClass::Laughter,
+        ReactionClass::Surprise,
+        ReactionClass::Cheering,
+        ReactionClass::Donation,
+    ];
+
+    /// 채팅 메시지로 판별하는 반응의 키워드 (`Donation`은 후원 기록으로 판별하므로 없음)
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            ReactionClass::Laughter => &["ㅋㅋ", "ㅎㅎ", "크크", "하하", "lol", "lmao", "😂", "🤣"],
+            ReactionClass::Surprise => &[
+                "??",
+                "?!",
+                "!?",
+                "ㄷㄷ",
+                "ㄴㅇㄱ",
+                "헐",
+                "미친",
+                "실화",
+                "ㅗㅜㅑ",
+                "😮",
+                "😱",
+            ],
+            ReactionClass::Cheering => &[
+                "gg",
+                "ㄱㄱ",
+                "ㅊㅊ",
+                "나이스",
+                "nice",
+                "가보자",
+                "축하",
+                "최고",
+                "👏",
+                "❤",
+            ],
+            ReactionClass::Donation => &[],
+        }
+    }
+
+    /// 메시지에 나타난 반응 (여러 개일 수 있음)
+    pub fn classify(message: &str) -> Vec<ReactionClass> {
+        let message = message.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .filter(|class| {
+                class
+                    .keywords()
+                    .iter()
+                    .any(|keyword| message.contains(keyword))
+            })
+            .collect()
+    }
+}
+
+/// 이벤트 요약 (`EventInterval`에 붙여서 저장)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventSummary {
+    /// 평소 대비 과대 표현 점수가 높은 순
+    pub labels: Vec<EventLabel>,
+    /// 평소보다 비율이 가장 많이 늘어난 반응
+    pub reaction: Option<ReactionClass>,
+}
+
+/// 메시지 하나의 라벨 후보 (중복 제거)
+/// 같은 글자가 3번 넘게 반복되면 3번으로 줄여서(`ㅋㅋㅋㅋㅋ` → `ㅋㅋㅋ`) 같은 라벨로 셉니다.
+pub fn message_features(message: &str) -> Vec<(LabelKind, String)> {
+    let mut features = Vec::new();
+    let mut text = String::new();
+
+    // 이모티콘 `{:name:}` 분리
+    let mut rest = message;
+    while let Some(start) = rest.find("{:") {
+        match rest[start + 2..].find(":}") {
+            Some(len) => {
+                let end = start + 2 + len + 2;
+                features.push((LabelKind::Emote, rest[start..end].to_string()));
+                text.push_str(&rest[..start]);
+                text.push(' ');
+                rest = &rest[end..];
+            }
+            None => break,
+        }
+    }
+    text.push_str(rest);
+
+    let normalized = collapse_repeats(&text.to_lowercase());
+    let tokens: Vec<&str> = normalized.split_whitespace().collect();
+    for token in &tokens {
+        features.push((LabelKind::Token, token.to_string()));
+    }
+    if tokens.len() >= 2 {
+        let phrase = tokens.join(" ");
+        if phrase.chars().count() <= MAX_PHRASE_CHARS {
+            features.push((LabelKind::Phrase, phrase));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    features.retain(|feature| seen.insert(feature.clone()));
+    features
+}
+
+/// 같은 글자가 3번 넘게 연속되면 3번으로 줄입니다.
+fn collapse_repeats(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut prev = None;
+    let mut run = 0;
+    for c in text.chars() {
+        if Some(c) == prev {
+            run += 1;
+        } else {
+            prev = Some(c);
+            run = 1;
+        }
+        if run <= 3 {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// VOD 전체 메시지를 baseline으로 이벤트 구간의 라벨과 반응을 계산합니다.
+/// 후원 반응은 메시지 대신 후원/구독 기록의 시간으로 이벤트 구간과 나머지 구간의 초당 후원 수를 비교합니다.
+pub struct EventSummarizer {
+    /// 라벨 후보별 VOD 전체 메시지 수
+    features: HashMap<(LabelKind, String), usize>,
+    /// 라벨 후보 수의 합
+    total_features: usize,
+    /// 반응별 VOD 전체 메시지 수
+    reactions: [usize; 4],
+    messages: usize,
+    /// 후원/구독 시간 (유닉스 초, 오름차순)
+    donation_times: Vec<i64>,
+    /// VOD 채팅 구간 길이 (초)
+    duration: i64,
+}
+
+impl EventSummarizer {
+    pub fn new(chat_log: &ChatColumns, mut donation_times: Vec<i64>) -> Self {
+        let mut features: HashMap<(LabelKind, String), usize> = HashMap::new();
+        let mut total_features = 0;
+        let mut reactions = [0; 4];
+        for i in 0..chat_log.len() {
+            let message = chat_log.message(i);
+            for feature in message_features(message) {
+                *features.entry(feature).or_insert(0) += 1;
+                total_features += 1;
+            }
+            for class in ReactionClass::classify(message) {
+                reactions[class as usize] += 1;
+            }
+        }
+        donation_times.sort_unstable();
+        let duration = match (
+            chat_log.timestamps().iter().min(),
+            chat_log.timestamps().iter().max(),
+        ) {
+            (Some(first), Some(last)) => last - first + 1,
+            _ => 0,
+        };
+        Self {
+            features,
+            total_features,
+            reactions,
+            messages: chat_log.len(),
+            donation_times,
+            duration,
+        }
+    }
+
+    /// 이벤트 구간 `[start, end]`(유닉스 초)의 메시지(`indices`)에서 상위 `top_k`개 라벨과 대표 반응을 계산합니다.
+    /// 라벨 점수는 informative Dirichlet prior를 둔 log-odds 비(Monroe et al., 2008)의 z-score로,
+    /// 이벤트 구간과 나머지 VOD 구간을 비교합니다.
+    pub fn summarize(
+        &self,
+        chat_log: &ChatColumns,
+        indices: &[usize],
+        start: i64,
+        end: i64,
+        top_k: usize,
+    ) -> EventSummary {
+        let mut event_features: HashMap<(LabelKind, String), usize> = HashMap::new();
+        let mut event_total = 0;
+        let mut event_reactions = [0; 4];
+        for &i in indices {
+            let message = chat_log.message(i);
+            for feature in message_features(message) {
+                *event_features.entry(feature).or_insert(0) += 1;
+                event_total += 1;
+            }
+            for class in ReactionClass::classify(message) {
+                event_reactions[class as usize] += 1;
+            }
+        }
+
+        let rest_total = self.total_features.saturating_sub(event_total) as f64;
+        let prior_total = PRIOR_WEIGHT * self.total_features as f64;
+        let event_total = event_total as f64;
+        let mut labels: Vec<EventLabel> = event_features
+            .into_iter()
+            .filter(|&(_, count)| count >= MIN_LABEL_COUNT)
+            .filter_map(|(feature, count)| {
+                let total = self.features.get(&feature).copied().unwrap_or(count);
+                let prior = PRIOR_WEIGHT * total as f64;
+                let event = count as f64;
+                let rest = total.saturating_sub(count) as f64;
+
+                let log_odds = |y: f64, n: f64| ((y + prior) / (n + prior_total - y - prior)).ln();
+                let delta = log_odds(event, event_total) - log_odds(rest, rest_total);
+                let variance = 1.0 / (event + prior) + 1.0 / (rest + prior);
+                let score = delta / variance.sqrt();
+                (score > 0.0).then_some(EventLabel {
+                    text: feature.1,
+                    kind: feature.0,
+                    count,
+                    score,
+                })
+            })
+            .collect();
+        labels.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.text.cmp(&b.text))
+        });
+        labels.truncate(top_k);
+
+        // 이벤트 비율 - 나머지 구간 비율이 가장 큰 반응
+        let share = |count: usize, total: usize| count as f64 / total.max(1) as f64;
+        let rest_messages = self.messages.saturating_sub(indices.len());
+        let reaction = ReactionClass::ALL
+            .into_iter()
+            .map(|class| {
+                let event_share = share(event_reactions[class as usize], indices.len());
+                let rest_share = share(
+                    self.reactions[class as usize].saturating_sub(event_reactions[class as usize]),
+                    rest_messages,
+                );
+                (class, event_share, event_share - rest_share)
+            })
+            .filter(|&(_, event_share, lift)| event_share >= MIN_REACTION_SHARE && lift > 0.0)
+            .max_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(class, _, _)| class);
+        let reaction = if self.is_donation_burst(start, end) {
+            Some(ReactionClass::Donation)
+        } else {
+            reaction
+        };
+
+        EventSummary { labels, reaction }
+    }
+
+    /// `[start, end]` 구간에 후원/구독이 나머지 구간보다 몰렸는지 확인합니다.
+    fn is_donation_burst(&self, start: i64, end: i64) -> bool {
+        let from = self.donation_times.partition_point(|&t| t < start);
+        let to = self.donation_times.partition_point(|&t| t <= end);
+        let event_count = to - from;
+        if event_count < MIN_EVENT_DONATIONS {
+            return false;
+        }
+
+        let event_seconds = (end - start + 1).max(1);
+        let rest_seconds = (self.duration - event_seconds).max(1);
+        let event_rate = event_count as f64 / event_seconds as f64;
+        let rest_rate = (self.donation_times.len() - event_count) as f64 / rest_seconds as f64;
+        event_rate >= MIN_DONATION_LIFT * rest_rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::chat::loader::parse_chat_line;
+    use crate::data::chat::store::ChatStore;
+    use crate::data::models::ChatLog;
+
+    #[test]
+    fn test_event_labels_and_reaction() {
+        assert_eq!(
+            message_features("ㅋㅋㅋㅋㅋ {:d_1:} 미쳤다{:d_1:}"),
+            vec![
+                (LabelKind::Emote, "{:d_1:}".to_string()),
+                (LabelKind::Token, "ㅋㅋㅋ".to_string()),
+                (LabelKind::Token, "미쳤다".to_string()),
+                (LabelKind::Phrase, "ㅋㅋㅋ 미쳤다".to_string()),
+            ]
+        );
+
+        // 평소에는 인사와 잡담, 100초부터 20초 동안 웃음과 이모티콘
+        let mut lines = Vec::new();
+        for sec in 0..200 {
+            let message = if (100..120).contains(&sec) {
+                match sec % 3 {
+                    0 => "ㅋㅋㅋㅋㅋㅋ".to_string(),
+                    1 => "ㅋㅋㅋ {:d_7:}".to_string(),
+                    _ => "아니 이게 되네 ㅋㅋㅋㅋ".to_string(),
+                }
+            } else {
+                match sec % 4 {
+                    0 => "안녕하세요".to_string(),
+                    1 => "오늘 뭐해요?".to_string(),
+                    2 => format!("잡담 {}", sec),
+                    _ => "ㅋㅋ".to_string(),
+                }
+            };
+            lines.push(format!(
+                "[2025-01-01 12:{:02}:{:02}] n: {} (u{})",
+                sec / 60,
+                sec % 60,
+                message,
+                sec % 7
+            ));
+        }
+        let messages = lines
+            .iter()
+            .map(|line| parse_chat_line(line).unwrap())
+            .collect();
+        let store = ChatStore::from_chat_logs(vec![ChatLog {
+            video_id: 1,
+            messages,
+        }]);
+        let chat_log = store.get(1).unwrap();
+
+        let base = chat_log.first_timestamp().unwrap();
+
+        let summarizer = EventSummarizer::new(chat_log, Vec::new());
+        let indices: Vec<usize> = (100..120).collect();
+        let summary = summarizer.summarize(chat_log, &indices, base + 100, base + 119, 3);
+
+        assert_eq!(summary.reaction, Some(ReactionClass::Laughter));
+        assert_eq!(summary.labels.len(), 3);
+        assert_eq!(summary.labels[0].text, "ㅋㅋㅋ");
+        assert_eq!(summary.labels[0].count, 20);
+        assert!(summary
+            .labels
+            .iter()
+            .any(|label| label.kind == LabelKind::Emote && label.text == "{:d_7:}"));
+        // 평소에도 나오는 말은 라벨이 아님
+        assert!(summary
+            .labels
+            .iter()
+            .all(|label| label.text != "안녕하세요"));
+
+        // 평소 구간은 과대 표현된 라벨과 반응이 없음
+        let quiet: Vec<usize> = (0..40).collect();
+        let summary = summarizer.summarize(chat_log, &quiet, base, base + 39, 3);
+        assert!(summary.labels.iter().all(|label| label.text != "ㅋㅋㅋ"));
+        assert_eq!(summary.reaction, None);
+
+        // 후원 기록이 이벤트 구간에 몰리면 채팅 내용과 상관없이 후원 반응
+        let summarizer = EventSummarizer::new(
+            chat_log,
+            vec![base + 117, base + 10, base + 105, base + 110],
+        );
+        let summary = summarizer.summarize(chat_log, &indices, base + 100, base + 119, 3);
+        assert_eq!(summary.reaction, Some(ReactionClass::Donation));
+        // 후원이 한 건뿐인 구간은 후원 반응이 아님
+        let summary = summarizer.summarize(chat_log, &quiet, base, base + 39, 3);
+        assert_eq!(summary.reaction, None);
+    }
+
+    #[test]
+    fn test_reaction_keywords() {
+        // 물음표 하나는 질문일 뿐, 연속된 물음표/느낌표만 놀람
+        assert!(ReactionClass::classify("오늘 뭐해요?").is_empty());
+        assert_eq!(ReactionClass::classify("??"), vec![ReactionClass::Surprise]);
+        assert_eq!(
+            ReactionClass::classify("이게 되네?!"),
+            vec![ReactionClass::Surprise]
+        );
+        // 후원은 채팅 키워드로 판별하지 않음
+        assert!(ReactionClass::classify("후원 감사합니다").is_empty());
+    }
+}
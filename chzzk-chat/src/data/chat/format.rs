@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::SecondsFormat;
+use color_eyre::eyre::{eyre, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::data::chat::loader::{format_chat_line, ChatLineReader, ParseStats};
+use crate::data::models::{ChatLog, ChatMessage};
+use crate::data::utils::parse_replay_time;
+
+/// 채팅 로그 파일 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatLogFormat {
+    /// `[YYYY-MM-DD HH:MM:SS] 닉네임: 메시지 (user_id)` 텍스트 (파일 하나에 비디오 하나)
+    Text,
+    /// 한 줄에 `ChatRecord` JSON 하나
+    Jsonl,
+    /// `ChatRecord` 열을 헤더로 갖는 CSV
+    Csv,
+}
+
+impl ChatLogFormat {
+    /// 파일 확장자 (`.gz`, `.zst` 압축 확장자 제외)
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ChatLogFormat::Text => "log",
+            ChatLogFormat::Jsonl => "jsonl",
+            ChatLogFormat::Csv => "csv",
+        }
+    }
+
+    /// 파일 경로의 확장자로 형식을 판단합니다. `.gz`, `.zst`는 건너뛰고 그 앞의 확장자를 봅니다.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let filename = path.as_ref().file_name()?.to_str()?;
+        let filename = filename
+            .strip_suffix(".gz")
+            .or_else(|| filename.strip_suffix(".zst"))
+            .unwrap_or(filename);
+        let (_, extension) = filename.rsplit_once('.')?;
+        extension.parse().ok()
+    }
+}
+
+impl FromStr for ChatLogFormat {
+    type Err = color_eyre::eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" | "log" => Ok(ChatLogFormat::Text),
+            "jsonl" => Ok(ChatLogFormat::Jsonl),
+            "csv" => Ok(ChatLogFormat::Csv),
+            _ => Err(eyre!("Unknown chat log format: {} (text, jsonl, csv)", s)),
+        }
+    }
+}
+
+/// JSONL/CSV 한 줄 (메시지 하나)
+/// 다른 도구(pandas, DuckDB 등)에서 바로 읽을 수 있도록 열 이름을 풀어 쓰고, 시간은 RFC 3339로 씁니다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatRecord {
+    pub video_id: u64,
+    /// 예: `2025-10-24T18:03:15+09:00`
+    pub timestamp: String,
+    pub nickname: String,
+    pub message: String,
+    pub user_id: String,
+}
+
+impl ChatRecord {
+    pub fn new(video_id: u64, message: &ChatMessage) -> Self {
+        Self {
+            video_id,
+            timestamp: message
+                .timestamp
+                .to_rfc3339_opts(SecondsFormat::AutoSi, false),
+            nickname: message.nickname.clone(),
+            message: message.message.clone(),
+            user_id: message.user_id.clone(),
+        }
+    }
+
+    /// `ChatMessage`로 변환합니다. 시간을 읽을 수 없으면 None을 반환합니다.
+    pub fn into_message(self) -> Option<ChatMessage> {
+        Some(ChatMessage {
+            timestamp: parse_replay_time(&self.timestamp).ok()?,
+            nickname: self.nickname,
+            message: self.message,
+            user_id: self.user_id,
+        })
+    }
+}
+
+/// 읽은 메시지를 video_id별 `ChatLog`로 모읍니다. (video_id 순으로 정렬)
+#[derive(Default)]
+struct ChatLogGroups {
+    logs: Vec<ChatLog>,
+    index: HashMap<u64, usize>,
+}
+
+impl ChatLogGroups {
+    fn push(&mut self, video_id: u64, message: ChatMessage) {
+        let idx = *self.index.entry(video_id).or_insert_with(|| {
+            self.logs.push(ChatLog {
+                video_id,
+                messages: Vec::new(),
+            });
+            self.logs.len() - 1
+        });
+        self.logs[idx].messages.push(message);
+    }
+
+    /// `filter`가 있으면 해당 video_id의 줄만 추가합니다.
+    /// 다른 video_id의 줄은 `ParseStats::skipped`로 셉니다.
+    fn push_record(&mut self, record: ChatRecord, filter: Option<u64>, stats: &mut ParseStats) {
+        if filter.is_some_and(|id| id != record.video_id) {
+            stats.skipped += 1;
+            return;
+        }
+        let video_id = record.video_id;
+        match record.into_message() {
+            Some(message) => {
+                stats.parsed += 1;
+                self.push(video_id, message);
+            }
+            None => stats.malformed += 1,
+        }
+    }
+
+    fn into_logs(mut self) -> Vec<ChatLog> {
+        self.logs.sort_by_key(|log| log.video_id);
+        self.logs
+    }
+}
+
+/// 채팅 로그를 형식에 맞게 읽어 video_id별 `ChatLog`로 반환합니다.
+/// 텍스트 형식은 줄에 video_id가 없으므로 `video_id`가 필요하고,
+/// JSONL/CSV는 `video_id`를 주면 해당 비디오의 줄만 읽고, 다른 비디오의 줄은 `ParseStats::skipped`로 셉니다.
+/// 읽을 수 없는 줄은 건너뛰고 `ParseStats::malformed`로 셉니다.
+pub fn read_chat_logs<R: BufRead>(
+    reader: R,
+    format: ChatLogFormat,
+    video_id: Option<u64>,
+) -> Result<(Vec<ChatLog>, ParseStats)> {
+    let mut groups = ChatLogGroups::default();
+    let mut stats = ParseStats::default();
+
+    match format {
+        ChatLogFormat::Text => {
+            let video_id =
+                video_id.ok_or_else(|| eyre!("Text chat logs need a video_id (file name)"))?;
+            let mut lines = ChatLineReader::new(reader);
+            let messages = lines
+                .by_ref()
+                .collect::<std::io::Result<Vec<ChatMessage>>>()?;
+            groups.logs.push(ChatLog { video_id, messages });
+            stats = lines.stats();
+        }
+        ChatLogFormat::Jsonl => {
+            for line in reader.split(b'\n') {
+                let line = line?;
+                stats.lines += 1;
+                if line.iter().all(u8::is_ascii_whitespace) {
+                    continue;
+                }
+                match serde_json::from_slice::<ChatRecord>(&line) {
+                    Ok(record) => groups.push_record(record, video_id, &mut stats),
+                    Err(_) => stats.malformed += 1,
+                }
+            }
+        }
+        ChatLogFormat::Csv => {
+            let mut csv_reader = csv::Reader::from_reader(reader);
+            for record in csv_reader.deserialize::<ChatRecord>() {
+                stats.lines += 1;
+                match record {
+                    Ok(record) => groups.push_record(record, video_id, &mut stats),
+                    Err(e) if e.is_io_error() => {
+                        return Err(e).context("Failed to read CSV chat log");
+                    }
+                    Err(_) => stats.malformed += 1,
+                }
+            }
+        }
+    }
+
+    Ok((groups.into_logs(), stats))
+}
+
+/// 채팅 로그를 JSONL/CSV 형식으로 이어서 씁니다.
+/// 여러 번 호출해도 CSV 헤더는 처음 한 번만 씁니다.
+pub struct ChatRecordWriter<W: Write> {
+    inner: RecordWriterInner<W>,
+}
+
+enum RecordWriterInner<W: Write> {
+    Jsonl(W),
+    Csv(Box<csv::Writer<W>>),
+}
+
+impl<W: Write> ChatRecordWriter<W> {
+    /// JSONL 또는 CSV writer를 만듭니다. 텍스트 형식은 `write_text_chat_log`를 사용합니다.
+    pub fn new(writer: W, format: ChatLogFormat) -> Result<Self> {
+        Self::with_header(writer, format, true)
+    }
+
+    /// 이미 내용이 있는 파일 뒤에 이어 쓰는 writer를 만듭니다. (CSV 헤더를 쓰지 않음)
+    pub fn appending(writer: W, format: ChatLogFormat) -> Result<Self> {
+        Self::with_header(writer, format, false)
+    }
+
+    fn with_header(writer: W, format: ChatLogFormat, header: bool) -> Result<Self> {
+        let inner = match format {
+            ChatLogFormat::Jsonl => RecordWriterInner::Jsonl(writer),
+            ChatLogFormat::Csv => RecordWriterInner::Csv(Box::new(
+                csv::WriterBuilder::new()
+                    .has_headers(header)
+                    .from_writer(writer),
+            )),
+            ChatLogFormat::Text => {
+                return Err(eyre!("Text chat logs are written one file per video"));
+            }
+        };
+        Ok(Self { inner })
+    }
+
+    /// 채팅 로그의 모든 메시지를 씁니다.
+    pub fn write_chat_log(&mut self, chat_log: &ChatLog) -> Result<()> {
+        for message in &chat_log.messages {
+            let record = ChatRecord::new(chat_log.video_id, message);
+            match &mut self.inner {
+                RecordWriterInner::Jsonl(writer) => {
+                    serde_json::to_writer(&mut *writer, &record)?;
+                    writer.write_all(b"\n")?;
+                }
+                RecordWriterInner::Csv(writer) => writer.serialize(&record)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// 버퍼에 남은 내용을 씁니다.
+    pub fn flush(&mut self) -> Result<()> {
+        match &mut self.inner {
+            RecordWriterInner::Jsonl(writer) => writer.flush()?,
+            RecordWriterInner::Csv(writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+}
+
+/// 채팅 로그를 `parse_chat_line`이 읽는 텍스트 형식으로 씁니다.
+pub fn write_text_chat_log<W: Write>(chat_log: &ChatLog, mut writer: W) -> Result<()> {
+    for message in &chat_log.messages {
+        writeln!(
+            writer,
+            "{}",
+            format_chat_line(
+                &message.timestamp,
+                &message.nickname,
+                &message.message,
+                &message.user_id
+            )
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::chat::loader::parse_chat_line;
+
+    const LINES: [&str; 3] = [
+        "[2025-10-24 18:03:15] 1연지: 머타타 (f2959e925442442d133ed215d603786d)",
+        "[2025-10-24 18:03:16] a, \"b\": 쉼표, \"따옴표\" 포함 (u2)",
+        "[2025-10-24 18:03:17] c: {\"json\": 1} (u3)",
+    ];
+
+    #[test]
+    fn test_jsonl_and_csv_round_trip() {
+        let chat_log = ChatLog {
+            video_id: 42,
+            messages: LINES.iter().map(|l| parse_chat_line(l).unwrap()).collect(),
+        };
+
+        for format in [ChatLogFormat::Jsonl, ChatLogFormat::Csv] {
+            let mut writer = ChatRecordWriter::new(Vec::new(), format).unwrap();
+            writer.write_chat_log(&chat_log).unwrap();
+            writer.flush().unwrap();
+            let bytes = match writer.inner {
+                RecordWriterInner::Jsonl(bytes) => bytes,
+                RecordWriterInner::Csv(csv) => (*csv).into_inner().unwrap(),
+            };
+
+            let (logs, stats) = read_chat_logs(bytes.as_slice(), format, None).unwrap();
+            assert_eq!(stats.parsed, LINES.len());
+            assert_eq!(stats.malformed, 0);
+            assert_eq!(logs.len(), 1);
+            assert_eq!(logs[0].video_id, 42);
+
+            let mut text = Vec::new();
+            write_text_chat_log(&logs[0], &mut text).unwrap();
+            assert_eq!(
+                String::from_utf8(text).unwrap().lines().collect::<Vec<_>>(),
+                LINES
+            );
+        }
+
+        assert_eq!(
+            ChatLogFormat::from_path("export/chatLog-1.jsonl.zst"),
+            Some(ChatLogFormat::Jsonl)
+        );
+        assert_eq!(
+            ChatLogFormat::from_path("chatLog-1.log"),
+            Some(ChatLogFormat::Text)
+        );
+    }
+}
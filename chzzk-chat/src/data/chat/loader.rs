@@ -1,18 +1,39 @@
 use color_eyre::eyre::{Context, Result};
+use flate2::read::MultiGzDecoder;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::collections::HashMap;
 use std::fs;
-use std::io::{BufReader, BufWriter};
+use std::io::{self, BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 
+use crate::data::chat::cache;
+use crate::data::chat::format::{read_chat_logs, ChatLogFormat};
+use crate::data::chat::store::ChatStore;
 use crate::data::models::{ChatLog, ChatMessage};
 use crate::utils;
-use chrono::{FixedOffset, TimeZone};
+use chrono::{DateTime, FixedOffset, TimeZone};
 use rayon::prelude::*;
+use serde::Deserialize;
 
 /// 파일 이름에서 video_id를 추출하기 위한 정규표현식
 static FILENAME_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"chatLog-(\d+)\.log").expect("Invalid filename regex"));
+    Lazy::new(|| Regex::new(r"chatLog-(\d+)\.(?:log|jsonl|csv)").expect("Invalid filename regex"));
+
+/// 로더가 읽는 채팅 로그 파일 확장자 (같은 video_id의 파일이 여러 개면 앞의 것을 사용)
+pub const CHAT_LOG_EXTENSIONS: [&str; 9] = [
+    ".log",
+    ".log.gz",
+    ".log.zst",
+    ".jsonl",
+    ".jsonl.gz",
+    ".jsonl.zst",
+    ".csv",
+    ".csv.gz",
+    ".csv.zst",
+];
 
 /// 채팅 로그 한 줄을 파싱하기 위한 정규표현식
 static CHAT_LINE_REGEX: Lazy<Regex> = Lazy::new(|| {
@@ -27,9 +48,77 @@ pub fn extract_video_id_from_filename(filename: &str) -> Option<u64> {
     caps.get(1)?.as_str().parse().ok()
 }
 
+/// `CHAT_LOG_EXTENSIONS` 확장자의 `chatLog-{video_id}` 채팅 로그 파일인지 확인합니다.
+pub fn is_chat_log_filename(filename: &str) -> bool {
+    filename.starts_with("chatLog-")
+        && CHAT_LOG_EXTENSIONS
+            .iter()
+            .any(|extension| filename.ends_with(extension))
+}
+
+/// video_id의 채팅 로그 파일 경로
+/// `CHAT_LOG_EXTENSIONS` 순으로 존재하는 파일을 찾고, 없으면 `.log` 경로를 반환합니다.
+pub fn chat_log_source_path<P: AsRef<Path>>(chat_logs_dir: P, video_id: u64) -> PathBuf {
+    let chat_logs_dir = chat_logs_dir.as_ref();
+    CHAT_LOG_EXTENSIONS
+        .iter()
+        .map(|extension| chat_logs_dir.join(format!("chatLog-{}{}", video_id, extension)))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| chat_logs_dir.join(format!("chatLog-{}.log", video_id)))
+}
+
+/// `chatLog-{video_id}.donations.jsonl`의 후원/구독 시간(유닉스 초)을 시간 순으로 반환합니다.
+/// 파일이 없으면 빈 목록을 반환하고, 시간을 읽을 수 없는 줄은 건너뜁니다.
+pub fn load_donation_times<P: AsRef<Path>>(chat_logs_dir: P, video_id: u64) -> Result<Vec<i64>> {
+    #[derive(Deserialize)]
+    struct DonationTime {
+        time: String,
+    }
+
+    let path = chat_logs_dir
+        .as_ref()
+        .join(format!("chatLog-{}.donations.jsonl", video_id));
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read donations file: {:?}", path))?;
+
+    let kst_offset = FixedOffset::east_opt(9 * 3600).expect("Invalid KST offset");
+    let mut times: Vec<i64> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<DonationTime>(line).ok())
+        .filter_map(|record| {
+            let naive =
+                chrono::NaiveDateTime::parse_from_str(&record.time, "%Y-%m-%d %H:%M:%S").ok()?;
+            Some(kst_offset.from_local_datetime(&naive).single()?.timestamp())
+        })
+        .collect();
+    times.sort_unstable();
+    Ok(times)
+}
+
+/// 채팅 로그 파일을 엽니다. 확장자가 `.gz`면 gzip, `.zst`면 zstd로 풀면서 읽습니다.
+pub fn open_chat_log<P: AsRef<Path>>(path: P) -> Result<Box<dyn BufRead>> {
+    let path = path.as_ref();
+    let file = fs::File::open(path)
+        .with_context(|| format!("Failed to read chat log file: {:?}", path))?;
+
+    let reader: Box<dyn BufRead> = match path.extension().and_then(|ext| ext.to_str()) {
+        // 여러 멤버로 이어붙인 gzip 파일도 끝까지 읽음
+        Some("gz") => Box::new(BufReader::new(MultiGzDecoder::new(BufReader::new(file)))),
+        Some("zst") => Box::new(BufReader::new(
+            zstd::stream::read::Decoder::new(file)
+                .with_context(|| format!("Failed to open zstd chat log: {:?}", path))?,
+        )),
+        _ => Box::new(BufReader::new(file)),
+    };
+    Ok(reader)
+}
+
 /// 채팅 로그 파일 한 줄을 파싱합니다.
 /// 형식: `[2025-10-24 18:03:15] 닉네임: 메시지 (user_id)`
-fn parse_chat_line(line: &str) -> Option<ChatMessage> {
+pub fn parse_chat_line(line: &str) -> Option<ChatMessage> {
     let caps = CHAT_LINE_REGEX.captures(line)?;
 
     // 타임스탬프 파싱
@@ -54,11 +143,109 @@ fn parse_chat_line(line: &str) -> Option<ChatMessage> {
     })
 }
 
-/// 단일 채팅 로그 파일을 로드합니다.
-pub fn load_chat_log<P: AsRef<Path>>(path: P) -> Result<ChatLog> {
+/// 채팅 메시지 한 건을 로그 파일 한 줄 형식으로 변환합니다.
+/// `parse_chat_line`이 읽을 수 있도록 줄바꿈은 공백으로 치환합니다.
+/// 형식: `[2025-10-24 18:03:15] 닉네임: 메시지 (user_id)`
+pub fn format_chat_line(
+    timestamp: &DateTime<FixedOffset>,
+    nickname: &str,
+    message: &str,
+    user_id: &str,
+) -> String {
+    let sanitize = |s: &str| s.replace(['\r', '\n'], " ");
+    format!(
+        "[{}] {}: {} ({})",
+        timestamp.format("%Y-%m-%d %H:%M:%S"),
+        sanitize(nickname),
+        sanitize(message),
+        user_id
+    )
+}
+
+/// 채팅 로그 파싱 통계
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ParseStats {
+    /// 빈 줄을 포함한 전체 줄 수
+    pub lines: usize,
+    /// 파싱에 성공한 줄 수
+    pub parsed: usize,
+    /// 형식이 맞지 않거나 UTF-8이 아니어서 건너뛴 줄 수
+    pub malformed: usize,
+    /// JSONL/CSV에서 파일 이름과 video_id가 달라 건너뛴 줄 수
+    pub skipped: usize,
+}
+
+impl ParseStats {
+    pub fn add(&mut self, other: ParseStats) {
+        self.lines += other.lines;
+        self.parsed += other.parsed;
+        self.malformed += other.malformed;
+        self.skipped += other.skipped;
+    }
+}
+
+/// `BufRead`에서 한 줄씩 읽어 `ChatMessage`를 돌려주는 스트리밍 파서
+/// 파일 전체를 메모리에 올리지 않고, 줄 버퍼 하나를 재사용합니다.
+/// 빈 줄은 건너뛰고, 파싱할 수 없는 줄은 `stats().malformed`로 셉니다.
+pub struct ChatLineReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    stats: ParseStats,
+}
+
+impl<R: BufRead> ChatLineReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            stats: ParseStats::default(),
+        }
+    }
+
+    /// 지금까지 읽은 줄의 파싱 통계
+    pub fn stats(&self) -> ParseStats {
+        self.stats
+    }
+}
+
+impl<R: BufRead> Iterator for ChatLineReader<R> {
+    type Item = io::Result<ChatMessage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.buf.clear();
+            match self.reader.read_until(b'\n', &mut self.buf) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
+            }
+            self.stats.lines += 1;
+
+            let Ok(line) = std::str::from_utf8(&self.buf) else {
+                self.stats.malformed += 1;
+                continue;
+            };
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            match parse_chat_line(trimmed) {
+                Some(message) => {
+                    self.stats.parsed += 1;
+                    return Some(Ok(message));
+                }
+                None => self.stats.malformed += 1,
+            }
+        }
+    }
+}
+
+/// 단일 채팅 로그 파일을 스트리밍으로 로드하고, 파싱 통계를 함께 반환합니다.
+/// 확장자에 따라 텍스트, JSONL, CSV 형식으로 읽고, `.gz`, `.zst` 파일은 압축을 풀면서 읽습니다.
+/// JSONL/CSV 파일에서는 파일 이름의 video_id와 같은 줄만 읽고, 나머지는 `ParseStats::skipped`로 셉니다.
+pub fn load_chat_log<P: AsRef<Path>>(path: P) -> Result<(ChatLog, ParseStats)> {
     let path = path.as_ref();
-    let file_content = fs::read_to_string(path)
-        .with_context(|| format!("Failed to read chat log file: {:?}", path))?;
 
     // 파일 이름에서 video_id 추출
     let filename = path
@@ -69,49 +256,24 @@ pub fn load_chat_log<P: AsRef<Path>>(path: P) -> Result<ChatLog> {
         color_eyre::eyre::eyre!("Failed to extract video_id from filename: {}", filename)
     })?;
 
-    // 각 줄을 파싱
-    let messages: Vec<ChatMessage> = file_content
-        .lines()
-        .filter_map(|line| {
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                None
-            } else {
-                parse_chat_line(trimmed)
-            }
-        })
-        .collect();
-
-    Ok(ChatLog { video_id, messages })
-}
-
-/// 캐시 파일에서 ChatLog를 로드합니다.
-fn load_chat_log_from_cache<P: AsRef<Path>>(cache_path: P) -> Result<ChatLog> {
-    let cache_path = cache_path.as_ref();
-    let file = fs::File::open(cache_path)
-        .with_context(|| format!("Failed to open cache file: {:?}", cache_path))?;
-    let reader = BufReader::new(file);
-    ciborium::de::from_reader(reader)
-        .with_context(|| format!("Failed to deserialize cache file: {:?}", cache_path))
-}
+    let format = ChatLogFormat::from_path(path).unwrap_or(ChatLogFormat::Text);
+    let (mut chat_logs, stats) = read_chat_logs(open_chat_log(path)?, format, Some(video_id))
+        .with_context(|| format!("Failed to read chat log file: {:?}", path))?;
+    let chat_log = chat_logs.pop().unwrap_or(ChatLog {
+        video_id,
+        messages: Vec::new(),
+    });
 
-/// ChatLog를 캐시 파일에 저장합니다.
-fn save_chat_log_to_cache<P: AsRef<Path>>(chat_log: &ChatLog, cache_path: P) -> Result<()> {
-    let cache_path = cache_path.as_ref();
-    let file = fs::File::create(cache_path)
-        .with_context(|| format!("Failed to create cache file: {:?}", cache_path))?;
-    let writer = BufWriter::new(file);
-    ciborium::ser::into_writer(chat_log, writer)
-        .with_context(|| format!("Failed to serialize to cache file: {:?}", cache_path))?;
-    Ok(())
+    Ok((chat_log, stats))
 }
 
-/// chat_logs 폴더 내의 모든 채팅 로그 파일을 로드합니다.
+/// chat_logs 폴더 내의 모든 채팅 로그 파일을 로드해서 열 저장소(`ChatStore`)로 반환합니다.
 /// 캐시 디렉토리가 제공되면 CBOR 형식으로 캐싱된 파일을 사용합니다.
+/// 파일마다 `ChatLog`를 바로 열 형식으로 변환하므로 전체 `ChatLog`가 한꺼번에 메모리에 올라가지 않습니다.
 pub fn load_all_chat_logs<P: AsRef<Path>>(
     chat_logs_dir: P,
     cache_dir: Option<&str>,
-) -> Result<Vec<ChatLog>> {
+) -> Result<ChatStore> {
     let chat_logs_dir = chat_logs_dir.as_ref();
     let cache_dir = cache_dir.unwrap_or("../chat_logs_cache");
 
@@ -125,19 +287,33 @@ pub fn load_all_chat_logs<P: AsRef<Path>>(
     let entries = fs::read_dir(chat_logs_dir)
         .with_context(|| format!("Failed to read chat_logs directory: {:?}", chat_logs_dir))?;
 
-    // chatLog-*.log 파일 경로 수집
-    let log_file_paths: Vec<_> = entries
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            let path = entry.path();
-            let filename = path.file_name()?.to_str()?;
-            if filename.starts_with("chatLog-") && filename.ends_with(".log") {
-                Some(path)
-            } else {
-                None
+    // chatLog-*.log, .jsonl, .csv (.gz, .zst 포함) 파일 경로 수집
+    // 같은 video_id의 파일이 여러 개면 `chat_log_source_path`와 같은 순서로 하나만 사용
+    let mut log_file_paths: HashMap<u64, PathBuf> = HashMap::new();
+    for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+        let Some(filename) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if !is_chat_log_filename(filename) {
+            continue;
+        }
+        let Some(video_id) = extract_video_id_from_filename(filename) else {
+            continue;
+        };
+        let priority = |path: &Path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            CHAT_LOG_EXTENSIONS
+                .iter()
+                .position(|extension| name.ends_with(extension))
+        };
+        match log_file_paths.get(&video_id) {
+            Some(existing) if priority(existing) <= priority(&path) => {}
+            _ => {
+                log_file_paths.insert(video_id, path);
             }
-        })
-        .collect();
+        }
+    }
+    let log_file_paths: Vec<(u64, PathBuf)> = log_file_paths.into_iter().collect();
 
     let total_files = log_file_paths.len();
 
@@ -147,59 +323,93 @@ pub fn load_all_chat_logs<P: AsRef<Path>>(
     // 병렬로 파일 로드
     // ProgressBar는 내부적으로 thread-safe하므로 Arc로 감싸지 않아도 됩니다.
     let cache_dir_path = cache_path.clone();
-    let mut chat_logs: Vec<ChatLog> = log_file_paths
+    // 새로 파싱한 파일들의 통계 (캐시에서 읽은 파일은 제외)
+    let parse_stats = Mutex::new(ParseStats::default());
+    let malformed_files = AtomicUsize::new(0);
+    let skipped_files = AtomicUsize::new(0);
+    let rebuilt_caches = AtomicUsize::new(0);
+    let stores: Vec<ChatStore> = log_file_paths
         .par_iter()
-        .filter_map(|path| {
-            // 파일 이름에서 video_id 추출
-            let filename = path.file_name()?.to_str()?;
-            let video_id = extract_video_id_from_filename(filename)?;
-
+        .filter_map(|(video_id, path)| {
             // 캐시 파일 경로 생성
-            let cache_file_name = format!("chatLog-{}.log.cache", video_id);
-            let cache_file_path = cache_dir_path.join(&cache_file_name);
-
-            // 캐시 파일이 있으면 로드, 없으면 파싱 후 저장
-            let chat_log = if cache_file_path.exists() {
-                match load_chat_log_from_cache(&cache_file_path) {
-                    Ok(log) => log,
-                    Err(e) => {
-                        panic!("Failed to load cache {:?}, error: {}", cache_file_path, e);
+            let cache_file_path = cache::cache_file_path(&cache_dir_path, *video_id);
+
+            // 원본과 일치하는 캐시가 있으면 로드, 없거나 오래되었거나 손상되었으면 파싱 후 저장
+            if let Some(log) = cache::load_cached_chat_log(&cache_file_path, path) {
+                pb.inc(1);
+                return Some(ChatStore::from_chat_log(log));
+            }
+            if cache_file_path.exists() {
+                rebuilt_caches.fetch_add(1, Ordering::Relaxed);
+            }
+
+            // 파싱 중에 원본이 바뀌어도 다음 실행에서 다시 만들도록 헤더를 먼저 계산
+            let header = cache::CacheHeader::from_source(path);
+            let chat_log = match load_chat_log(path) {
+                Ok((log, stats)) => {
+                    if stats.malformed > 0 {
+                        malformed_files.fetch_add(1, Ordering::Relaxed);
                     }
-                }
-            } else {
-                // 캐시가 없으면 파싱 후 저장
-                match load_chat_log(path) {
-                    Ok(log) => {
-                        // 캐시 저장 시도 (실패해도 계속 진행)
-                        if let Err(save_err) = save_chat_log_to_cache(&log, &cache_file_path) {
-                            eprintln!(
-                                "Warning: Failed to save cache {:?}: {}",
-                                cache_file_path, save_err
-                            );
-                        }
-                        log
+                    if stats.skipped > 0 {
+                        skipped_files.fetch_add(1, Ordering::Relaxed);
                     }
-                    Err(e) => {
-                        eprintln!("Warning: Failed to load chat log {:?}: {}", path, e);
-                        pb.inc(1);
-                        return None;
+                    parse_stats.lock().unwrap().add(stats);
+
+                    // 캐시 저장 시도 (실패해도 계속 진행)
+                    if let Err(save_err) = header.and_then(|header| {
+                        cache::save_cached_chat_log(&log, &header, &cache_file_path)
+                    }) {
+                        eprintln!(
+                            "Warning: Failed to save cache {:?}: {}",
+                            cache_file_path, save_err
+                        );
                     }
+                    log
+                }
+                Err(e) => {
+                    eprintln!("Warning: Failed to load chat log {:?}: {}", path, e);
+                    pb.inc(1);
+                    return None;
                 }
             };
 
             // ProgressBar는 내부적으로 thread-safe하므로 여러 스레드에서 안전하게 호출 가능
             pb.inc(1);
 
-            Some(chat_log)
+            Some(ChatStore::from_chat_log(chat_log))
         })
         .collect();
 
     pb.finish_with_message("Chat logs loaded!");
 
-    // video_id로 정렬
-    chat_logs.sort_by_key(|log| log.video_id);
+    let rebuilt_caches = rebuilt_caches.into_inner();
+    if rebuilt_caches > 0 {
+        utils::log(format!(
+            "오래되었거나 손상된 캐시 {}개를 다시 만들었습니다",
+            rebuilt_caches
+        ));
+    }
 
-    Ok(chat_logs)
+    let parse_stats = parse_stats.into_inner().unwrap();
+    if parse_stats.lines > 0 {
+        utils::log(format!(
+            "채팅 로그 파싱: {}줄 중 {}줄 성공, 형식 오류 {}줄 ({}개 파일)",
+            parse_stats.lines,
+            parse_stats.parsed,
+            parse_stats.malformed,
+            malformed_files.into_inner()
+        ));
+    }
+    if parse_stats.skipped > 0 {
+        utils::log(format!(
+            "파일 이름과 video_id가 다른 줄 {}줄을 건너뛰었습니다 ({}개 파일)",
+            parse_stats.skipped,
+            skipped_files.into_inner()
+        ));
+    }
+
+    // user_id 번호를 하나로 합치고 video_id로 정렬
+    Ok(ChatStore::from_stores(stores))
 }
 
 #[cfg(test)]
@@ -229,4 +439,113 @@ mod tests {
         assert_eq!(msg.message, "머타타");
         assert_eq!(msg.user_id, "f2959e925442442d133ed215d603786d");
     }
+
+    #[test]
+    fn test_chat_line_reader_counts_malformed_lines() {
+        let mut input = Vec::new();
+        input.extend_from_slice(b"[2025-10-24 18:03:15] a: hello (u1)\n");
+        input.extend_from_slice(b"\n");
+        input.extend_from_slice(b"broken line\n");
+        input.extend_from_slice(b"[2025-10-24 18:03:16] b: \xff\xfe (u2)\n");
+        input.extend_from_slice(b"[2025-10-24 18:03:17] c: bye (u3)");
+
+        let mut reader = ChatLineReader::new(io::Cursor::new(input));
+        let messages: Vec<ChatMessage> = reader.by_ref().map(Result::unwrap).collect();
+
+        assert_eq!(
+            messages
+                .iter()
+                .map(|m| m.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["hello", "bye"]
+        );
+        assert_eq!(
+            reader.stats(),
+            ParseStats {
+                lines: 5,
+                parsed: 2,
+                malformed: 2,
+                skipped: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_compressed_chat_logs() {
+        use std::io::Write;
+
+        let dir =
+            std::env::temp_dir().join(format!("chzzk-chat-compressed-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let content = "[2025-10-24 18:03:15] a: 하나 (u1)\n[2025-10-24 18:03:16] b: 둘 (u2)\n";
+
+        let gz_path = dir.join("chatLog-1.log.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            fs::File::create(&gz_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(content.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let zst_path = dir.join("chatLog-2.log.zst");
+        fs::write(&zst_path, zstd::encode_all(content.as_bytes(), 0).unwrap()).unwrap();
+
+        for (path, video_id) in [(&gz_path, 1), (&zst_path, 2)] {
+            let (chat_log, stats) = load_chat_log(path).unwrap();
+            assert_eq!(chat_log.video_id, video_id);
+            assert_eq!(chat_log.messages[1].message, "둘");
+            assert_eq!(stats.malformed, 0);
+        }
+
+        assert!(is_chat_log_filename("chatLog-1.log.gz"));
+        assert!(!is_chat_log_filename("chatLog-1.log.progress"));
+        assert_eq!(chat_log_source_path(&dir, 2), zst_path);
+
+        let store = load_all_chat_logs(&dir, dir.join("cache").to_str()).unwrap();
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.get(2).unwrap().len(), 2);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_load_donation_times() {
+        let dir = std::env::temp_dir().join(format!("chzzk-chat-donations-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("chatLog-7.donations.jsonl"),
+            concat!(
+                r#"{"time":"2025-10-24 18:03:20","user_id":"u1","kind":"donation"}"#,
+                "\n",
+                "not json\n",
+                r#"{"time":"2025-10-24 18:03:15","user_id":"u2","kind":"subscription"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let base = parse_chat_line("[2025-10-24 18:03:15] a: 하나 (u1)")
+            .unwrap()
+            .timestamp
+            .timestamp();
+        assert_eq!(load_donation_times(&dir, 7).unwrap(), vec![base, base + 5]);
+        // 후원 기록이 없는 VOD
+        assert!(load_donation_times(&dir, 8).unwrap().is_empty());
+        // 후원 기록 파일은 채팅 로그로 읽지 않음
+        assert!(extract_video_id_from_filename("chatLog-7.donations.jsonl").is_none());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_format_chat_line_round_trip() {
+        let line = "[2025-10-24 18:03:15] 1연지: 머타타 (f2959e925442442d133ed215d603786d)";
+        let msg = parse_chat_line(line).unwrap();
+        let formatted = format_chat_line(&msg.timestamp, &msg.nickname, &msg.message, &msg.user_id);
+        assert_eq!(formatted, line);
+
+        let multi_line = format_chat_line(&msg.timestamp, "닉", "첫 줄\n둘째 줄", "abc");
+        let parsed = parse_chat_line(&multi_line).unwrap();
+        assert_eq!(parsed.message, "첫 줄 둘째 줄");
+    }
 }
@@ -0,0 +1,265 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::str::FromStr;
+
+use color_eyre::eyre::{eyre, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// LSH banding 설정
+/// 서명 길이는 `bands * rows`이고, Jaccard 유사도가 s인 쌍은 `1 - (1 - s^rows)^bands` 확률로 후보가 됩니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LshParams {
+    pub bands: usize,
+    pub rows: usize,
+}
+
+impl Default for LshParams {
+    /// 256 bands × 2 rows: 클러스터 기준(Jaccard 0.1)인 쌍은 약 92%, 0.15 이상은 99.7% 이상 후보에 포함하고,
+    /// 대부분을 차지하는 Jaccard 0.02 이하의 쌍은 10% 미만만 남깁니다.
+    fn default() -> Self {
+        Self {
+            bands: 256,
+            rows: 2,
+        }
+    }
+}
+
+impl LshParams {
+    /// Jaccard 유사도가 `similarity`인 쌍이 후보가 될 확률
+    pub fn candidate_probability(&self, similarity: f64) -> f64 {
+        1.0 - (1.0 - similarity.powi(self.rows as i32)).powi(self.bands as i32)
+    }
+}
+
+/// 쌍 유사도 계산 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SimilarityMode {
+    /// 모든 쌍의 교집합을 계산
+    #[default]
+    Exact,
+    /// MinHash LSH로 고른 후보 쌍만 교집합을 계산 (후보가 아닌 쌍은 교집합 0으로 취급)
+    Approx(LshParams),
+}
+
+impl SimilarityMode {
+    /// 비교할 쌍 목록을 구합니다.
+    /// `Exact`면 None(모든 쌍), `Approx`면 i번째에 i보다 큰 후보 번호 목록(정렬)을 반환합니다.
+    pub fn candidates(&self, sets: &[&[u32]]) -> Option<Vec<Vec<usize>>> {
+        match self {
+            SimilarityMode::Exact => None,
+            SimilarityMode::Approx(params) => Some(lsh_candidates(sets, *params)),
+        }
+    }
+}
+
+impl FromStr for SimilarityMode {
+    type Err = color_eyre::eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "exact" => Ok(SimilarityMode::Exact),
+            "approx" => Ok(SimilarityMode::Approx(LshParams::default())),
+            _ => Err(eyre!("Unknown similarity mode: {} (exact, approx)", s)),
+        }
+    }
+}
+
+/// splitmix64 finalizer
+fn mix64(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// 사용자 번호 집합의 MinHash 서명 (해시 함수마다 원소 해시의 최솟값)
+pub fn minhash_signature(users: &[u32], len: usize) -> Vec<u64> {
+    let mut signature = vec![u64::MAX; len];
+    for &user in users {
+        let base = mix64(user as u64);
+        for (k, min) in signature.iter_mut().enumerate() {
+            let hash = mix64(base ^ (k as u64 + 1).wrapping_mul(0x9e3779b97f4a7c15));
+            if hash < *min {
+                *min = hash;
+            }
+        }
+    }
+    signature
+}
+
+/// 두 MinHash 서명으로 Jaccard 유사도를 추정합니다.
+#[allow(dead_code)]
+pub fn estimate_jaccard(a: &[u64], b: &[u64]) -> f64 {
+    let equal = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    equal as f64 / a.len().max(1) as f64
+}
+
+/// MinHash 서명을 band로 나눠, 어느 band든 값이 모두 같은 쌍을 후보로 고릅니다.
+/// i번째 목록은 i보다 큰 후보 번호(정렬, 중복 제거)이며, 빈 집합은 후보가 되지 않습니다.
+pub fn lsh_candidates(sets: &[&[u32]], params: LshParams) -> Vec<Vec<usize>> {
+    let rows = params.rows.max(1);
+    let len = params.bands * rows;
+    let signatures: Vec<Option<Vec<u64>>> = sets
+        .par_iter()
+        .map(|users| (!users.is_empty()).then(|| minhash_signature(users, len)))
+        .collect();
+
+    // band마다 같은 bucket에 들어간 쌍 수집
+    let band_pairs: Vec<Vec<(usize, usize)>> = (0..params.bands)
+        .into_par_iter()
+        .map(|band| {
+            let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+            for (i, signature) in signatures.iter().enumerate() {
+                if let Some(signature) = signature {
+                    let key = signature[band * rows..(band + 1) * rows]
+                        .iter()
+                        .fold(0, |acc, &hash| mix64(acc ^ hash));
+                    buckets.entry(key).or_default().push(i);
+                }
+            }
+
+            let mut pairs = Vec::new();
+            for items in buckets.values() {
+                for (a, &i) in items.iter().enumerate() {
+                    pairs.extend(items[a + 1..].iter().map(|&j| (i, j)));
+                }
+            }
+            pairs
+        })
+        .collect();
+
+    let mut candidates = vec![Vec::new(); sets.len()];
+    for (i, j) in band_pairs.into_iter().flatten() {
+        candidates[i].push(j);
+    }
+    for targets in &mut candidates {
+        targets.sort_unstable();
+        targets.dedup();
+    }
+    candidates
+}
+
+/// 근사 모드 결과의 정확 모드 대비 오차
+/// 근사 모드는 후보 쌍의 교집합을 정확히 계산하므로 오차는 놓친 쌍에서만 생깁니다.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ApproxError {
+    /// 정확 모드에서 찾은 쌍 수
+    pub exact: usize,
+    /// 그중 근사 모드에서도 찾은 쌍 수
+    pub found: usize,
+    /// 놓친 쌍 중 가장 큰 점수
+    pub max_missed: f64,
+    /// 놓친 쌍 점수의 합
+    pub missed_score: f64,
+    /// 정확 모드 쌍 점수의 합
+    pub total_score: f64,
+}
+
+impl ApproxError {
+    /// 정확 모드의 (쌍, 점수) 목록과 근사 모드에서 찾은 쌍 집합을 비교합니다.
+    pub fn compare<K: Eq + Hash, I: IntoIterator<Item = (K, f64)>>(
+        exact: I,
+        approx: &HashSet<K>,
+    ) -> Self {
+        let mut error = Self::default();
+        for (pair, score) in exact {
+            error.exact += 1;
+            error.total_score += score;
+            if approx.contains(&pair) {
+                error.found += 1;
+            } else {
+                error.max_missed = error.max_missed.max(score);
+                error.missed_score += score;
+            }
+        }
+        error
+    }
+
+    /// 찾은 쌍 비율 (정확 모드 쌍이 없으면 1.0)
+    pub fn recall(&self) -> f64 {
+        if self.exact == 0 {
+            1.0
+        } else {
+            self.found as f64 / self.exact as f64
+        }
+    }
+
+    /// 점수로 가중한 찾은 비율
+    pub fn weighted_recall(&self) -> f64 {
+        if self.total_score > 0.0 {
+            1.0 - self.missed_score / self.total_score
+        } else {
+            1.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lsh_candidates_keep_similar_pairs() {
+        let a: Vec<u32> = (0..1000).collect();
+        let b: Vec<u32> = (100..1100).collect(); // a와 Jaccard 약 0.82
+        let c: Vec<u32> = (5000..6000).collect(); // 겹치지 않음
+        let d: Vec<u32> = (950..2000).collect(); // a와 Jaccard 약 0.02
+        let sets: Vec<&[u32]> = vec![&a, &b, &c, &d, &[]];
+
+        let signature_a = minhash_signature(&a, 256);
+        let signature_b = minhash_signature(&b, 256);
+        assert!((estimate_jaccard(&signature_a, &signature_b) - 0.82).abs() < 0.1);
+
+        let candidates = lsh_candidates(&sets, LshParams::default());
+        assert!(candidates[0].contains(&1));
+        assert!(!candidates[0].contains(&2));
+        assert!(!candidates[1].contains(&2));
+        assert!(candidates[4].is_empty());
+        assert!(candidates.iter().all(|targets| !targets.contains(&4)));
+
+        let params = LshParams::default();
+        assert!(params.candidate_probability(0.1) > 0.9);
+        assert!(params.candidate_probability(0.02) < 0.1);
+        assert!(params.candidate_probability(0.0) == 0.0);
+
+        let error = ApproxError::compare([((0, 1), 0.8), ((0, 3), 0.02)], &HashSet::from([(0, 1)]));
+        assert_eq!((error.exact, error.found), (2, 1));
+        assert_eq!(error.max_missed, 0.02);
+        assert!((error.recall() - 0.5).abs() < 1e-9);
+
+        assert_eq!(
+            "approx".parse::<SimilarityMode>().unwrap(),
+            SimilarityMode::Approx(params)
+        );
+    }
+
+    #[test]
+    fn test_default_lsh_keeps_similar_and_prunes_weak_pairs() {
+        // 사용자 300명인 집합 쌍: 앞의 200쌍은 Jaccard 0.2, 뒤의 200쌍은 Jaccard 0.01
+        let pair = |offset: u32, shared: u32| -> (Vec<u32>, Vec<u32>) {
+            let a: Vec<u32> = (offset..offset + 300).collect();
+            let b: Vec<u32> = (offset + 300 - shared..offset + 600 - shared).collect();
+            (a, b)
+        };
+        let mut owned = Vec::new();
+        for k in 0..400u32 {
+            let shared = if k < 200 { 100 } else { 6 };
+            let (a, b) = pair(k * 1000, shared);
+            owned.push(a);
+            owned.push(b);
+        }
+        let sets: Vec<&[u32]> = owned.iter().map(Vec::as_slice).collect();
+
+        let candidates = lsh_candidates(&sets, LshParams::default());
+        let found = |range: std::ops::Range<usize>| {
+            range
+                .filter(|&k| candidates[2 * k].contains(&(2 * k + 1)))
+                .count()
+        };
+        // 기대값: Jaccard 0.2는 약 100%, 0.01은 약 2.5%
+        assert!(found(0..200) >= 196);
+        assert!(found(200..400) <= 15);
+        // 서로 겹치지 않는 집합은 후보가 되지 않음
+        assert!(candidates[0].iter().all(|&j| j == 1));
+    }
+}
@@ -1,14 +1,23 @@
 pub mod analysis;
+pub mod cache;
 pub mod channel_distance;
 pub mod event;
+pub mod event_align;
+pub mod event_config;
+pub mod event_eval;
+pub mod event_summary;
+pub mod format;
 pub mod loader;
+pub mod minhash;
+pub mod online_event;
+pub mod order_stats;
 pub mod replay_cluster;
+pub mod store;
 
 // Re-export commonly used items
 pub use analysis::{analyze_chat_log, filter_chat_logs_by_user_count, print_analysis_summary};
 pub use channel_distance::{
-    calculate_channel_distances, export_channel_distances_json, export_related_channel_links_json,
-    print_top_closest_channels,
+    export_channel_distances_json, export_related_channel_links_json, print_top_closest_channels,
 };
 pub use event::{
     detect_event_intervals, match_events_time_only, print_event_intervals, print_match_result,
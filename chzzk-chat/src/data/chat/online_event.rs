@@ -0,0 +1,351 @@
+use serde::{Deserialize, Serialize};
+
+use crate::data::chat::event::{EventInterval, RollingBaseline};
+use crate::data::chat::event_config::EventDetectionConfig;
+
+/// 실시간 이벤트 알림 단계
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventPhase {
+    /// z-score가 피크 임계값을 넘음 (피크는 아직 진행 중)
+    Started,
+    /// 이벤트의 최고 피크가 갱신됨
+    Peaked,
+    /// 구간이 끝나고 더 이상 병합될 피크가 없음 (최종 구간)
+    Ended,
+}
+
+/// 실시간 이벤트 알림
+/// `Started`/`Peaked`의 `end_sec`은 알림 시점이고, `Ended`의 구간은 오프라인 탐지 결과와 같습니다.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventUpdate {
+    pub phase: EventPhase,
+    pub event: EventInterval,
+}
+
+/// z > z_th 인 연속 구간
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    /// z_end 기준으로 왼쪽으로 확장한 시작 시간
+    expanded_start: i64,
+    start_sec: i64,
+    /// (peak_sec, peak_z_score, peak_count)
+    best: (i64, f64, usize),
+}
+
+/// 병합 중이거나 끝나기를 기다리는 이벤트
+#[derive(Debug, Clone, Copy)]
+struct OpenEvent {
+    start_sec: i64,
+    /// 가장 높은 피크 (첫 run이 끝나기 전에는 None)
+    peak: Option<(i64, f64, usize)>,
+    /// 마지막으로 병합된 피크 시간 (병합 기준)
+    last_peak_sec: Option<i64>,
+    /// 마지막 피크 뒤 z가 z_end 이하로 떨어지기 직전 시간
+    end_sec: Option<i64>,
+}
+
+impl OpenEvent {
+    fn interval(&self, end_sec: i64) -> EventInterval {
+        let (peak_sec, peak_z_score, peak_count) = self.peak.unwrap_or((end_sec, 0.0, 0));
+        EventInterval {
+            start_sec: self.start_sec,
+            end_sec,
+            peak_sec,
+            peak_z_score,
+            peak_count,
+        }
+    }
+}
+
+/// 초 단위 메시지 수를 한 초씩 받아 이벤트를 실시간으로 탐지합니다.
+/// `detect_event_intervals`와 같은 EWMA/median/MAD baseline, 피크 병합, z_end 확장을 쓰며,
+/// 구간이 확정되는 즉시(마지막 피크 뒤 `gap_threshold_sec`이 지나고 z가 z_end 이하로 떨어졌을 때)
+/// `Ended`를 내보냅니다.
+#[derive(Debug, Clone)]
+pub struct OnlineEventDetector {
+    config: EventDetectionConfig,
+    baseline: RollingBaseline,
+    first_sec: Option<i64>,
+    last_sec: Option<i64>,
+    /// 마지막으로 z가 z_end 이하였던 시간
+    last_below_end: Option<i64>,
+    run: Option<Run>,
+    /// 다음 피크가 병합될 수 있는 이벤트
+    current: Option<OpenEvent>,
+    /// 더 병합되지 않지만 구간 끝을 기다리는 이벤트
+    closing: Vec<OpenEvent>,
+}
+
+impl OnlineEventDetector {
+    pub fn new(config: &EventDetectionConfig) -> Self {
+        Self {
+            config: *config,
+            baseline: RollingBaseline::new(config),
+            first_sec: None,
+            last_sec: None,
+            last_below_end: None,
+            run: None,
+            current: None,
+            closing: Vec::new(),
+        }
+    }
+
+    /// 다음 1초(`sec`)의 메시지 수를 넣고 그 초에 생긴 알림을 반환합니다.
+    /// 초는 1초 간격으로 들어온다고 가정합니다 (baseline 윈도우는 들어온 초 수 기준).
+    pub fn push(&mut self, sec: i64, count: usize) -> Vec<EventUpdate> {
+        let z = self.baseline.push(count as f64);
+        let first_sec = *self.first_sec.get_or_insert(sec);
+        self.last_sec = Some(sec);
+        let mut updates = Vec::new();
+
+        if z > self.config.z_th {
+            match &mut self.run {
+                Some(run) => {
+                    if z > run.best.1 || (z == run.best.1 && count > run.best.2) {
+                        run.best = (sec, z, count);
+                    }
+                }
+                None => {
+                    let run = Run {
+                        expanded_start: self.last_below_end.map_or(first_sec, |below| below + 1),
+                        start_sec: sec,
+                        best: (sec, z, count),
+                    };
+                    self.run = Some(run);
+                    // 이전 이벤트에 병합될 수 없으면 바로 새 이벤트 시작
+                    if !self.accepts(sec) {
+                        self.open_event(&run, sec, &mut updates);
+                    }
+                }
+            }
+        } else if let Some(run) = self.run.take() {
+            self.finish_run(run, sec, &mut updates);
+        }
+
+        if z <= self.config.z_end {
+            self.last_below_end = Some(sec);
+            for event in self.current.iter_mut().chain(self.closing.iter_mut()) {
+                if let (Some(last_peak_sec), None) = (event.last_peak_sec, event.end_sec) {
+                    if sec > last_peak_sec {
+                        event.end_sec = Some(sec - 1);
+                    }
+                }
+            }
+        }
+
+        self.close_events(sec, &mut updates);
+        updates
+    }
+
+    /// 스트림이 끝났을 때 남은 이벤트를 마지막 초까지로 끝냅니다.
+    pub fn finish(&mut self) -> Vec<EventUpdate> {
+        let Some(last_sec) = self.last_sec else {
+            return Vec::new();
+        };
+        let mut updates = Vec::new();
+        if let Some(run) = self.run.take() {
+            self.finish_run(run, last_sec, &mut updates);
+        }
+        let mut events: Vec<OpenEvent> = self.closing.drain(..).collect();
+        events.extend(self.current.take());
+        for event in events {
+            updates.push(EventUpdate {
+                phase: EventPhase::Ended,
+                event: event.interval(event.end_sec.unwrap_or(last_sec)),
+            });
+        }
+        updates
+    }
+
+    /// `sec`에 시작한 run의 피크가 현재 이벤트에 병합될 수 있는지
+    fn accepts(&self, sec: i64) -> bool {
+        self.current
+            .and_then(|event| event.last_peak_sec)
+            .is_some_and(|last_peak_sec| sec - last_peak_sec <= self.config.gap_threshold_sec)
+    }
+
+    /// run으로 새 이벤트를 시작합니다. 이전 이벤트는 더 병합되지 않습니다.
+    fn open_event(&mut self, run: &Run, sec: i64, updates: &mut Vec<EventUpdate>) {
+        if let Some(previous) = self.current.take() {
+            self.closing.push(previous);
+        }
+        let event = OpenEvent {
+            start_sec: run.expanded_start,
+            peak: None,
+            last_peak_sec: None,
+            end_sec: None,
+        };
+        let (peak_sec, peak_z_score, peak_count) = run.best;
+        updates.push(EventUpdate {
+            phase: EventPhase::Started,
+            event: EventInterval {
+                start_sec: event.start_sec,
+                end_sec: sec,
+                peak_sec,
+                peak_z_score,
+                peak_count,
+            },
+        });
+        self.current = Some(event);
+    }
+
+    /// run이 끝나 피크가 정해지면 현재 이벤트에 병합하거나 새 이벤트를 시작합니다.
+    fn finish_run(&mut self, run: Run, sec: i64, updates: &mut Vec<EventUpdate>) {
+        let (peak_sec, peak_z_score, _) = run.best;
+        let merges = match self.current {
+            Some(OpenEvent {
+                last_peak_sec: Some(last_peak_sec),
+                ..
+            }) => peak_sec - last_peak_sec <= self.config.gap_threshold_sec,
+            // 피크가 없는 이벤트는 이 run이 시작할 때 연 이벤트
+            Some(_) => true,
+            None => false,
+        };
+        if !merges {
+            self.open_event(&run, sec, updates);
+        }
+
+        let event = self.current.as_mut().expect("open event");
+        event.last_peak_sec = Some(peak_sec);
+        // 병합되면 구간 끝은 새 피크 이후에 다시 정해짐
+        event.end_sec = None;
+        if event.peak.is_none_or(|(_, z, _)| peak_z_score > z) {
+            event.peak = Some(run.best);
+            updates.push(EventUpdate {
+                phase: EventPhase::Peaked,
+                event: event.interval(sec),
+            });
+        }
+    }
+
+    /// 더 병합될 수 없고 구간 끝이 정해진 이벤트를 끝냅니다.
+    fn close_events(&mut self, sec: i64, updates: &mut Vec<EventUpdate>) {
+        if let Some(event) = self.current {
+            if let Some(last_peak_sec) = event.last_peak_sec {
+                let gap = self.config.gap_threshold_sec;
+                // 진행 중인 run의 피크는 run 시작 이후라 run 시작이 멀면 병합되지 않음
+                let mergeable = sec - last_peak_sec <= gap
+                    || self
+                        .run
+                        .is_some_and(|run| run.start_sec - last_peak_sec <= gap);
+                if !mergeable {
+                    self.closing.push(event);
+                    self.current = None;
+                }
+            }
+        }
+
+        let (ended, closing): (Vec<OpenEvent>, Vec<OpenEvent>) = self
+            .closing
+            .drain(..)
+            .partition(|event| event.end_sec.is_some());
+        self.closing = closing;
+        for event in ended {
+            updates.push(EventUpdate {
+                phase: EventPhase::Ended,
+                event: event.interval(event.end_sec.unwrap_or(sec)),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::chat::event::detect_event_intervals;
+    use crate::data::chat::event_config::EventPreset;
+    use crate::data::chat::loader::parse_chat_line;
+    use crate::data::chat::store::ChatStore;
+    use crate::data::models::ChatLog;
+
+    #[test]
+    fn test_online_events_match_offline() {
+        // 40분 분량, 평소 초당 0~3개에 가끔 폭발 구간 (가까운 폭발은 병합)
+        let mut seed = 5u64;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            seed >> 33
+        };
+        let counts: Vec<usize> = (0..2400i64)
+            .map(|sec| {
+                let mut count = (next() % 4) as usize;
+                if sec > 600 && (sec % 500 < 20 || (sec % 500 > 30 && sec % 500 < 40)) {
+                    count += 15 + (next() % 25) as usize;
+                }
+                count
+            })
+            .collect();
+        // 오프라인 탐지는 첫 메시지부터 마지막 메시지까지
+        let first = counts.iter().position(|&count| count > 0).unwrap();
+        let last = counts.iter().rposition(|&count| count > 0).unwrap();
+        let counts = &counts[first..=last];
+
+        let start = chrono::NaiveDate::from_ymd_opt(2025, 1, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let mut messages = Vec::new();
+        for (sec, &count) in counts.iter().enumerate() {
+            let time = start + chrono::Duration::seconds(sec as i64);
+            for _ in 0..count {
+                let line = format!("[{}] n: m (u1)", time.format("%Y-%m-%d %H:%M:%S"));
+                messages.push(parse_chat_line(&line).unwrap());
+            }
+        }
+        let store = ChatStore::from_chat_logs(vec![ChatLog {
+            video_id: 1,
+            messages,
+        }]);
+        let chat_log = store.get(1).unwrap();
+
+        for config in [
+            EventDetectionConfig::default(),
+            EventPreset::Small.detection_config(0.0),
+            EventDetectionConfig {
+                gap_threshold_sec: 40,
+                ..Default::default()
+            },
+        ] {
+            let offline = detect_event_intervals(chat_log, &config).unwrap().events;
+            assert!(!offline.is_empty());
+
+            let mut detector = OnlineEventDetector::new(&config);
+            let mut updates = Vec::new();
+            for (sec, &count) in counts.iter().enumerate() {
+                for update in detector.push(sec as i64, count) {
+                    // 알림은 구간이 지나기 전에 나와야 함
+                    assert!(update.event.end_sec <= sec as i64);
+                    updates.push(update);
+                }
+            }
+            updates.extend(detector.finish());
+
+            let ended: Vec<EventInterval> = updates
+                .iter()
+                .filter(|update| update.phase == EventPhase::Ended)
+                .map(|update| update.event.clone())
+                .collect();
+            assert_eq!(ended, offline);
+
+            // 이벤트마다 끝 알림 전에 시작 알림과 최종 피크 알림이 나옴
+            for event in &offline {
+                let ended_at = updates
+                    .iter()
+                    .position(|update| update.phase == EventPhase::Ended && update.event == *event)
+                    .unwrap();
+                let before = &updates[..ended_at];
+                assert!(before
+                    .iter()
+                    .any(|update| update.phase == EventPhase::Started
+                        && update.event.start_sec == event.start_sec));
+                assert!(before
+                    .iter()
+                    .any(|update| update.phase == EventPhase::Peaked
+                        && update.event.peak_sec == event.peak_sec));
+            }
+        }
+    }
+}
@@ -0,0 +1,292 @@
+/// 빈 자식
+const NIL: usize = usize::MAX;
+
+#[derive(Debug, Clone)]
+struct Node {
+    value: f64,
+    priority: u64,
+    size: usize,
+    left: usize,
+    right: usize,
+}
+
+/// 중복 값을 허용하는 순서 통계 트리 (treap)
+/// 값 추가/삭제와 k번째 값 조회가 평균 O(log n)이라 슬라이딩 윈도우의 median/MAD를 구하는 데 씁니다.
+#[derive(Debug, Clone)]
+pub struct OrderStatisticTree {
+    nodes: Vec<Node>,
+    /// 삭제된 노드 번호 (재사용)
+    free: Vec<usize>,
+    root: usize,
+    seed: u64,
+}
+
+impl Default for OrderStatisticTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderStatisticTree {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: NIL,
+            seed: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.root == NIL
+    }
+
+    /// 값을 추가합니다.
+    pub fn insert(&mut self, value: f64) {
+        // splitmix64
+        self.seed = self.seed.wrapping_add(0x9e3779b97f4a7c15);
+        let mut priority = self.seed;
+        priority = (priority ^ (priority >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        priority = (priority ^ (priority >> 27)).wrapping_mul(0x94d049bb133111eb);
+        priority ^= priority >> 31;
+
+        let node = Node {
+            value,
+            priority,
+            size: 1,
+            left: NIL,
+            right: NIL,
+        };
+        let node = match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+
+        let (less, rest) = self.split(self.root, |v| v.total_cmp(&value).is_lt());
+        let less = self.merge(less, node);
+        self.root = self.merge(less, rest);
+    }
+
+    /// 같은 값 하나를 삭제합니다. 값이 없으면 false를 반환합니다.
+    pub fn remove(&mut self, value: f64) -> bool {
+        let (less, rest) = self.split(self.root, |v| v.total_cmp(&value).is_lt());
+        let (mut equal, greater) = self.split(rest, |v| v.total_cmp(&value).is_le());
+        let removed = equal != NIL;
+        if removed {
+            let node = equal;
+            equal = self.merge(self.nodes[node].left, self.nodes[node].right);
+            self.free.push(node);
+        }
+        let rest = self.merge(equal, greater);
+        self.root = self.merge(less, rest);
+        removed
+    }
+
+    /// k번째(0부터)로 작은 값
+    pub fn select(&self, mut k: usize) -> f64 {
+        assert!(k < self.len(), "select index out of range");
+        let mut t = self.root;
+        loop {
+            let left_size = self.size(self.nodes[t].left);
+            if k < left_size {
+                t = self.nodes[t].left;
+            } else if k == left_size {
+                return self.nodes[t].value;
+            } else {
+                k -= left_size + 1;
+                t = self.nodes[t].right;
+            }
+        }
+    }
+
+    /// `value`보다 작은 값의 수
+    pub fn count_less(&self, value: f64) -> usize {
+        let mut count = 0;
+        let mut t = self.root;
+        while t != NIL {
+            if self.nodes[t].value.total_cmp(&value).is_lt() {
+                count += self.size(self.nodes[t].left) + 1;
+                t = self.nodes[t].right;
+            } else {
+                t = self.nodes[t].left;
+            }
+        }
+        count
+    }
+
+    /// 중앙값 (짝수 개면 가운데 두 값의 평균)
+    pub fn median(&self) -> Option<f64> {
+        let n = self.len();
+        if n == 0 {
+            return None;
+        }
+        let mid = n / 2;
+        Some(if n.is_multiple_of(2) {
+            (self.select(mid - 1) + self.select(mid)) / 2.0
+        } else {
+            self.select(mid)
+        })
+    }
+
+    /// MAD (중앙값과의 절대 편차의 중앙값)
+    /// 중앙값 왼쪽과 오른쪽의 편차가 각각 정렬되어 있으므로 두 정렬 목록의 k번째 값으로 구합니다.
+    pub fn median_absolute_deviation(&self) -> Option<f64> {
+        let median = self.median()?;
+        let n = self.len();
+        let split = self.count_less(median);
+
+        // 왼쪽: 중앙값에 가까운 값부터, 오른쪽: 중앙값 이상인 값을 작은 값부터
+        let left = |t: usize| (self.select(split - 1 - t) - median).abs();
+        let right = |t: usize| (self.select(split + t) - median).abs();
+        let kth = |k: usize| kth_of_two(k, split, n - split, left, right);
+
+        let mid = n / 2;
+        Some(if n.is_multiple_of(2) {
+            (kth(mid - 1) + kth(mid)) / 2.0
+        } else {
+            kth(mid)
+        })
+    }
+
+    fn size(&self, t: usize) -> usize {
+        if t == NIL {
+            0
+        } else {
+            self.nodes[t].size
+        }
+    }
+
+    fn update(&mut self, t: usize) {
+        self.nodes[t].size = 1 + self.size(self.nodes[t].left) + self.size(self.nodes[t].right);
+    }
+
+    /// 두 트리를 합칩니다. `a`의 모든 값이 `b`의 모든 값보다 작거나 같아야 합니다.
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL {
+            return b;
+        }
+        if b == NIL {
+            return a;
+        }
+        if self.nodes[a].priority > self.nodes[b].priority {
+            let merged = self.merge(self.nodes[a].right, b);
+            self.nodes[a].right = merged;
+            self.update(a);
+            a
+        } else {
+            let merged = self.merge(a, self.nodes[b].left);
+            self.nodes[b].left = merged;
+            self.update(b);
+            b
+        }
+    }
+
+    /// 트리를 (`goes_left`인 값, 나머지)로 나눕니다. `goes_left`는 작은 값부터 true여야 합니다.
+    fn split(&mut self, t: usize, goes_left: impl Fn(f64) -> bool + Copy) -> (usize, usize) {
+        if t == NIL {
+            return (NIL, NIL);
+        }
+        if goes_left(self.nodes[t].value) {
+            let (left, right) = self.split(self.nodes[t].right, goes_left);
+            self.nodes[t].right = left;
+            self.update(t);
+            (t, right)
+        } else {
+            let (left, right) = self.split(self.nodes[t].left, goes_left);
+            self.nodes[t].left = right;
+            self.update(t);
+            (left, t)
+        }
+    }
+}
+
+/// 오름차순인 두 목록을 합쳤을 때 k번째(0부터)로 작은 값
+fn kth_of_two(
+    k: usize,
+    left_len: usize,
+    right_len: usize,
+    left: impl Fn(usize) -> f64,
+    right: impl Fn(usize) -> f64,
+) -> f64 {
+    // 왼쪽에서 x개, 오른쪽에서 k + 1 - x개를 고르는 분할을 이분 탐색
+    let take = k + 1;
+    let (mut lo, mut hi) = (take.saturating_sub(right_len), take.min(left_len));
+    loop {
+        let x = (lo + hi) / 2;
+        let y = take - x;
+        if x > 0 && y < right_len && left(x - 1) > right(y) {
+            hi = x - 1;
+        } else if y > 0 && x < left_len && right(y - 1) > left(x) {
+            lo = x + 1;
+        } else {
+            let from_left = if x > 0 { left(x - 1) } else { f64::MIN };
+            let from_right = if y > 0 { right(y - 1) } else { f64::MIN };
+            return from_left.max(from_right);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted_median(values: &[f64]) -> f64 {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let mid = sorted.len() / 2;
+        if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2.0
+        } else {
+            sorted[mid]
+        }
+    }
+
+    #[test]
+    fn test_sliding_window_median_and_mad() {
+        let mut tree = OrderStatisticTree::new();
+        let mut window: std::collections::VecDeque<f64> = Default::default();
+        let mut seed = 7u64;
+
+        for i in 0..3000 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            // 중복이 많도록 작은 범위의 값
+            let value = ((seed >> 33) % 50) as f64 * 0.1;
+            tree.insert(value);
+            window.push_back(value);
+            // 윈도우 크기를 1~120 사이로 바꿈
+            while window.len() > 1 + i % 120 {
+                assert!(tree.remove(window.pop_front().unwrap()));
+            }
+
+            let values: Vec<f64> = window.iter().copied().collect();
+            let median = sorted_median(&values);
+            let deviations: Vec<f64> = values.iter().map(|v| (v - median).abs()).collect();
+            assert_eq!(tree.len(), values.len());
+            assert_eq!(tree.median(), Some(median));
+            assert_eq!(
+                tree.median_absolute_deviation(),
+                Some(sorted_median(&deviations))
+            );
+        }
+
+        assert!(!tree.remove(100.0));
+        while let Some(value) = window.pop_front() {
+            assert!(tree.remove(value));
+        }
+        assert!(tree.is_empty());
+        assert_eq!(tree.median(), None);
+    }
+}
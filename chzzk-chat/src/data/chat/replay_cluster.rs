@@ -1,7 +1,9 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use crate::data::models::{ChannelWithReplays, ChatLog, Replay};
+use crate::data::chat::minhash::SimilarityMode;
+use crate::data::chat::store::{intersection_count, ChatStore};
+use crate::data::models::{ChannelWithReplays, Replay};
 use crate::utils;
 use rayon::prelude::*;
 
@@ -26,7 +28,7 @@ pub struct ReplayCluster {
 fn calculate_replay_similarity(
     a: &ReplayWithChannel,
     b: &ReplayWithChannel,
-    video_viewers: &HashMap<u64, HashSet<String>>,
+    video_viewers: &HashMap<u64, Vec<u32>>,
 ) -> f64 {
     let viewers_a = match video_viewers.get(&a.replay.video_no) {
         Some(v) => v,
@@ -41,14 +43,8 @@ fn calculate_replay_similarity(
         return 0.0;
     }
 
-    // 항상 작은 쪽을 돌면서 큰 쪽에 contains() → 캐시/성능 ↑
-    let (small, large) = if viewers_a.len() <= viewers_b.len() {
-        (viewers_a, viewers_b)
-    } else {
-        (viewers_b, viewers_a)
-    };
-
-    let intersection = small.iter().filter(|v| large.contains(*v)).count();
+    // 정렬된 user 번호 목록을 병합 방식으로 비교 (해시 없음)
+    let intersection = intersection_count(viewers_a, viewers_b);
     if intersection == 0 {
         return 0.0;
     }
@@ -60,27 +56,26 @@ fn calculate_replay_similarity(
 }
 
 /// 다시보기들을 유사도 기반으로 클러스터링합니다 (시청자 수 기준).
+/// `mode`가 `Approx`면 MinHash LSH 후보 쌍만 비교합니다.
 pub fn cluster_similar_replays(
     channels: &[ChannelWithReplays],
-    chat_logs: &[ChatLog],
+    chat_logs: &ChatStore,
     similarity_threshold: f64,
+    mode: SimilarityMode,
 ) -> Vec<ReplayCluster> {
-    // video_id별 시청자 집합 구하기 (먼저 ChatLog가 있는 video_id 집합 생성)
-    let mut video_viewers: HashMap<u64, HashSet<String>> = HashMap::new();
+    // video_id별 시청자 번호 목록 구하기 (먼저 채팅 로그가 있는 video_id 집합 생성)
+    let mut video_viewers: HashMap<u64, Vec<u32>> = HashMap::new();
     let mut video_ids_with_chat_log: HashSet<u64> = HashSet::new();
-    for chat_log in chat_logs {
+    for chat_log in &chat_logs.logs {
         video_ids_with_chat_log.insert(chat_log.video_id);
-        let viewers = video_viewers.entry(chat_log.video_id).or_default();
-        for message in &chat_log.messages {
-            viewers.insert(message.user_id.clone());
-        }
+        video_viewers.insert(chat_log.video_id, chat_log.unique_users());
     }
 
-    // ChatLog가 있는 다시보기만 채널 정보와 함께 수집
+    // 채팅 로그가 있는 다시보기만 채널 정보와 함께 수집
     let mut replays_with_channel: Vec<ReplayWithChannel> = Vec::new();
     for channel in channels {
         for replay in &channel.replays {
-            // ChatLog가 있는 video_no만 포함
+            // 채팅 로그가 있는 video_no만 포함
             if video_ids_with_chat_log.contains(&replay.video_no) {
                 replays_with_channel.push(ReplayWithChannel {
                     replay: replay.clone(),
@@ -114,13 +109,29 @@ pub fn cluster_similar_replays(
         }
     }
 
+    // 비교할 쌍 (근사 모드면 LSH 후보 쌍만)
+    let viewer_sets: Vec<&[u32]> = replays_with_channel
+        .iter()
+        .map(|r| {
+            video_viewers
+                .get(&r.replay.video_no)
+                .map(Vec::as_slice)
+                .unwrap_or(&[])
+        })
+        .collect();
+    let candidates = mode.candidates(&viewer_sets);
+    let candidates = &candidates;
+
     // 유사도 계산을 병렬로 수행 (Arc로 공유)
     let replays_arc = Arc::new(replays_with_channel);
     let video_viewers_arc = Arc::new(video_viewers);
 
     // Progress bar 생성
-    // 총 쌍 수: n * (n-1) / 2
-    let total_pairs = n * (n - 1) / 2;
+    // 총 쌍 수: n * (n-1) / 2 (근사 모드면 후보 쌍 수)
+    let total_pairs = match candidates {
+        Some(candidates) => candidates.iter().map(Vec::len).sum(),
+        None => n * (n - 1) / 2,
+    };
     let pb = utils::create_progress_bar(total_pairs as u64, "Calculating replay similarities...");
 
     let pb = Arc::new(pb);
@@ -133,7 +144,12 @@ pub fn cluster_similar_replays(
             let video_viewers_ref = Arc::clone(&video_viewers_arc);
             let pb = Arc::clone(&pb);
 
-            ((i + 1)..n)
+            let targets: Vec<usize> = match candidates {
+                Some(candidates) => candidates[i].clone(),
+                None => ((i + 1)..n).collect(),
+            };
+            targets
+                .into_iter()
                 .filter_map(move |j| {
                     let similarity = calculate_replay_similarity(
                         &replays_ref[i],
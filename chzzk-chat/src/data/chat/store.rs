@@ -0,0 +1,434 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset, TimeZone};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::data::models::ChatLog;
+
+/// 문자열을 u32 번호로 바꿔 한 번만 저장하는 테이블
+/// 32자리 hex user_id처럼 수백만 번 반복되는 값을 번호로 다루기 위해 사용합니다.
+#[derive(Debug, Default, Clone)]
+pub struct Interner {
+    values: Vec<String>,
+    index: HashMap<String, u32>,
+}
+
+impl Interner {
+    /// 문자열의 번호를 반환합니다. 처음 보는 문자열이면 새 번호를 붙입니다.
+    pub fn intern(&mut self, value: &str) -> u32 {
+        if let Some(&id) = self.index.get(value) {
+            return id;
+        }
+        let id = u32::try_from(self.values.len()).expect("Interner overflow");
+        self.values.push(value.to_string());
+        self.index.insert(value.to_string(), id);
+        id
+    }
+
+    /// 번호에 해당하는 문자열
+    pub fn get(&self, id: u32) -> &str {
+        &self.values[id as usize]
+    }
+
+    /// 이미 등록된 문자열의 번호
+    #[allow(dead_code)]
+    pub fn lookup(&self, value: &str) -> Option<u32> {
+        self.index.get(value).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// 문자열 목록으로만 저장하고, 읽을 때 번호 색인을 다시 만듭니다.
+impl Serialize for Interner {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.values)
+    }
+}
+
+impl<'de> Deserialize<'de> for Interner {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values = Vec::<String>::deserialize(deserializer)?;
+        let index = values
+            .iter()
+            .enumerate()
+            .map(|(id, value)| (value.clone(), id as u32))
+            .collect();
+        Ok(Self { values, index })
+    }
+}
+
+/// 비디오 하나의 채팅을 열(column) 단위로 저장합니다.
+/// 메시지마다 `String` 세 개와 `DateTime`을 두는 대신,
+/// 타임스탬프(초), user/닉네임 번호, 하나로 이어붙인 메시지 본문과 오프셋만 저장합니다.
+#[derive(Debug, Default, Clone)]
+pub struct ChatColumns {
+    pub video_id: u64,
+    /// UNIX 타임스탬프 (초)
+    timestamps: Vec<i64>,
+    /// `ChatStore::users` 번호
+    users: Vec<u32>,
+    /// `ChatStore::nicknames` 번호
+    nicknames: Vec<u32>,
+    /// 모든 메시지 본문을 이어붙인 문자열
+    text: String,
+    /// i번째 메시지 본문은 `text[offsets[i]..offsets[i + 1]]`
+    offsets: Vec<u32>,
+}
+
+impl ChatColumns {
+    fn new(video_id: u64, capacity: usize) -> Self {
+        let mut offsets = Vec::with_capacity(capacity + 1);
+        offsets.push(0);
+        Self {
+            video_id,
+            timestamps: Vec::with_capacity(capacity),
+            users: Vec::with_capacity(capacity),
+            nicknames: Vec::with_capacity(capacity),
+            text: String::new(),
+            offsets,
+        }
+    }
+
+    fn push(&mut self, timestamp: i64, user: u32, nickname: u32, message: &str) {
+        self.timestamps.push(timestamp);
+        self.users.push(user);
+        self.nicknames.push(nickname);
+        self.text.push_str(message);
+        self.offsets
+            .push(u32::try_from(self.text.len()).expect("Chat text too large"));
+    }
+
+    /// 같은 비디오의 다른 채팅 열을 뒤에 이어붙입니다.
+    fn extend(&mut self, other: ChatColumns) {
+        let base = u32::try_from(self.text.len()).expect("Chat text too large");
+        self.timestamps.extend(other.timestamps);
+        self.users.extend(other.users);
+        self.nicknames.extend(other.nicknames);
+        self.text.push_str(&other.text);
+        self.offsets.extend(
+            other.offsets[1..]
+                .iter()
+                .map(|&offset| base.checked_add(offset).expect("Chat text too large")),
+        );
+    }
+
+    /// 메시지 수
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    /// 모든 메시지의 UNIX 타임스탬프 (초)
+    pub fn timestamps(&self) -> &[i64] {
+        &self.timestamps
+    }
+
+    /// 모든 메시지의 user 번호
+    pub fn users(&self) -> &[u32] {
+        &self.users
+    }
+
+    /// i번째 메시지의 UNIX 타임스탬프 (초)
+    pub fn timestamp(&self, i: usize) -> i64 {
+        self.timestamps[i]
+    }
+
+    /// i번째 메시지의 KST 시간
+    pub fn datetime(&self, i: usize) -> DateTime<FixedOffset> {
+        kst_datetime(self.timestamps[i])
+    }
+
+    /// i번째 메시지의 user 번호
+    pub fn user(&self, i: usize) -> u32 {
+        self.users[i]
+    }
+
+    /// i번째 메시지의 닉네임 번호
+    pub fn nickname(&self, i: usize) -> u32 {
+        self.nicknames[i]
+    }
+
+    /// i번째 메시지 본문
+    pub fn message(&self, i: usize) -> &str {
+        &self.text[self.offsets[i] as usize..self.offsets[i + 1] as usize]
+    }
+
+    /// 가장 이른 메시지의 타임스탬프 (초)
+    pub fn first_timestamp(&self) -> Option<i64> {
+        self.timestamps.iter().copied().min()
+    }
+
+    /// 메시지 번호를 시간 순으로 정렬해 반환합니다. (같은 시간이면 기록 순서 유지)
+    pub fn sorted_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.len()).collect();
+        indices.sort_by_key(|&i| self.timestamps[i]);
+        indices
+    }
+
+    /// `[start, end]` 구간(초, 양 끝 포함)의 메시지 번호를 시간 순으로 반환합니다.
+    pub fn indices_between(&self, start: i64, end: i64) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.len())
+            .filter(|&i| (start..=end).contains(&self.timestamps[i]))
+            .collect();
+        indices.sort_by_key(|&i| self.timestamps[i]);
+        indices
+    }
+
+    /// 채팅한 사용자 번호를 정렬하고 중복을 제거해 반환합니다.
+    pub fn unique_users(&self) -> Vec<u32> {
+        let mut users = self.users.clone();
+        users.sort_unstable();
+        users.dedup();
+        users
+    }
+}
+
+/// 채팅 로그 전체를 담는 열 저장소
+/// user_id와 닉네임은 모든 비디오가 같은 테이블을 공유하므로 비디오 간 사용자 비교를 번호로 할 수 있습니다.
+#[derive(Debug, Default)]
+pub struct ChatStore {
+    pub users: Interner,
+    pub nicknames: Interner,
+    /// video_id 순으로 정렬된 비디오별 채팅 (video_id마다 하나)
+    pub logs: Vec<ChatColumns>,
+}
+
+impl ChatStore {
+    /// 채팅 로그 하나로 저장소를 만듭니다. 원본 `ChatLog`는 변환 후 해제됩니다.
+    pub fn from_chat_log(chat_log: ChatLog) -> Self {
+        let mut store = Self::default();
+        let mut columns = ChatColumns::new(chat_log.video_id, chat_log.messages.len());
+        for message in chat_log.messages {
+            let user = store.users.intern(&message.user_id);
+            let nickname = store.nicknames.intern(&message.nickname);
+            columns.push(
+                message.timestamp.timestamp(),
+                user,
+                nickname,
+                &message.message,
+            );
+        }
+        store.logs.push(columns);
+        store
+    }
+
+    /// 여러 채팅 로그로 저장소를 만듭니다.
+    #[allow(dead_code)]
+    pub fn from_chat_logs(chat_logs: Vec<ChatLog>) -> Self {
+        Self::from_stores(chat_logs.into_iter().map(Self::from_chat_log))
+    }
+
+    /// 여러 저장소를 하나로 합칩니다. (비디오 정렬은 마지막에 한 번만 수행)
+    pub fn from_stores<I: IntoIterator<Item = ChatStore>>(stores: I) -> Self {
+        let mut store = Self::default();
+        for other in stores {
+            store.append(other);
+        }
+        store.merge_videos();
+        store
+    }
+
+    /// 다른 저장소의 비디오들을 이 저장소의 번호 체계로 옮겨 합칩니다.
+    /// 이미 있는 video_id의 채팅은 기존 채팅 뒤에 이어붙입니다.
+    #[allow(dead_code)]
+    pub fn merge(&mut self, other: ChatStore) {
+        self.append(other);
+        self.merge_videos();
+    }
+
+    /// 비디오를 video_id 순으로 정렬하고, 같은 video_id의 채팅
+    /// (`.log`와 `.jsonl`처럼 여러 파일로 나뉜 로그)을 하나로 합칩니다.
+    fn merge_videos(&mut self) {
+        self.logs.sort_by_key(|log| log.video_id);
+
+        let mut merged: Vec<ChatColumns> = Vec::with_capacity(self.logs.len());
+        for columns in self.logs.drain(..) {
+            match merged.last_mut() {
+                Some(last) if last.video_id == columns.video_id => last.extend(columns),
+                _ => merged.push(columns),
+            }
+        }
+        self.logs = merged;
+    }
+
+    fn append(&mut self, other: ChatStore) {
+        let user_map: Vec<u32> = other
+            .users
+            .values
+            .iter()
+            .map(|user| self.users.intern(user))
+            .collect();
+        let nickname_map: Vec<u32> = other
+            .nicknames
+            .values
+            .iter()
+            .map(|nickname| self.nicknames.intern(nickname))
+            .collect();
+
+        for mut columns in other.logs {
+            for user in &mut columns.users {
+                *user = user_map[*user as usize];
+            }
+            for nickname in &mut columns.nicknames {
+                *nickname = nickname_map[*nickname as usize];
+            }
+            self.logs.push(columns);
+        }
+    }
+
+    /// video_id의 채팅
+    pub fn get(&self, video_id: u64) -> Option<&ChatColumns> {
+        self.logs
+            .binary_search_by_key(&video_id, |log| log.video_id)
+            .ok()
+            .map(|idx| &self.logs[idx])
+    }
+
+    /// 번호에 해당하는 user_id
+    pub fn user_id(&self, user: u32) -> &str {
+        self.users.get(user)
+    }
+
+    /// 번호에 해당하는 닉네임
+    pub fn nickname(&self, nickname: u32) -> &str {
+        self.nicknames.get(nickname)
+    }
+
+    /// 비디오 수
+    pub fn len(&self) -> usize {
+        self.logs.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.logs.is_empty()
+    }
+}
+
+/// UNIX 타임스탬프(초)를 KST 시간으로 변환합니다.
+pub fn kst_datetime(timestamp: i64) -> DateTime<FixedOffset> {
+    FixedOffset::east_opt(9 * 3600)
+        .unwrap()
+        .timestamp_opt(timestamp, 0)
+        .unwrap()
+}
+
+/// 정렬된 두 사용자 번호 목록의 교집합 크기를 구합니다. (해시 없이 병합 방식으로 비교)
+pub fn intersection_count(a: &[u32], b: &[u32]) -> usize {
+    let (mut i, mut j, mut count) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::chat::loader::parse_chat_line;
+
+    fn chat_log(video_id: u64, lines: &[&str]) -> ChatLog {
+        ChatLog {
+            video_id,
+            messages: lines.iter().map(|l| parse_chat_line(l).unwrap()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_chat_store_interns_users_across_videos() {
+        let store = ChatStore::from_chat_logs(vec![
+            chat_log(
+                2,
+                &[
+                    "[2025-01-01 12:00:05] b: 둘 (user-b)",
+                    "[2025-01-01 12:00:01] a: 하나 (user-a)",
+                ],
+            ),
+            chat_log(
+                1,
+                &[
+                    "[2025-01-01 12:00:00] a: 처음 (user-a)",
+                    "[2025-01-01 12:00:02] c: 셋 (user-c)",
+                ],
+            ),
+        ]);
+
+        assert_eq!(store.users.len(), 3);
+        assert_eq!(
+            store.logs.iter().map(|l| l.video_id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+
+        let log1 = store.get(1).unwrap();
+        let log2 = store.get(2).unwrap();
+        assert_eq!(log2.message(0), "둘");
+        assert_eq!(log2.message(1), "하나");
+        assert_eq!(store.user_id(log2.user(1)), "user-a");
+        assert_eq!(
+            log2.sorted_indices()
+                .iter()
+                .map(|&i| log2.message(i))
+                .collect::<Vec<_>>(),
+            vec!["하나", "둘"]
+        );
+        assert_eq!(
+            log2.datetime(1).format("%Y-%m-%d %H:%M:%S").to_string(),
+            "2025-01-01 12:00:01"
+        );
+
+        // 같은 user_id는 비디오가 달라도 같은 번호
+        assert_eq!(
+            intersection_count(&log1.unique_users(), &log2.unique_users()),
+            1
+        );
+    }
+
+    #[test]
+    fn test_chat_store_merges_duplicate_video_ids() {
+        let mut store = ChatStore::from_chat_logs(vec![
+            chat_log(1, &["[2025-01-01 12:00:00] a: 처음 (user-a)"]),
+            chat_log(
+                1,
+                &[
+                    "[2025-01-01 12:00:03] b: 둘 (user-b)",
+                    "[2025-01-01 12:00:04] a: 셋 (user-a)",
+                ],
+            ),
+        ]);
+        store.merge(ChatStore::from_chat_log(chat_log(
+            1,
+            &["[2025-01-01 12:00:01] c: 늦게 (user-c)"],
+        )));
+
+        // 같은 video_id는 한 비디오로 합쳐져 덮어쓰지 않음
+        assert_eq!(store.len(), 1);
+        let log = store.get(1).unwrap();
+        assert_eq!(
+            log.sorted_indices()
+                .iter()
+                .map(|&i| log.message(i))
+                .collect::<Vec<_>>(),
+            vec!["처음", "늦게", "둘", "셋"]
+        );
+        assert_eq!(log.unique_users().len(), 3);
+    }
+}
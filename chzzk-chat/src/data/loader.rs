@@ -2,7 +2,7 @@ use color_eyre::eyre::{Context, Result};
 use std::fs;
 use std::path::Path;
 
-use crate::data::models::ChannelWithReplays;
+use crate::data::models::{ChannelWithReplays, Replay};
 
 /// JSON 파일에서 채널 및 리플레이 데이터를 로드합니다.
 pub fn load_channel_with_replays<P: AsRef<Path>>(path: P) -> Result<Vec<ChannelWithReplays>> {
@@ -15,10 +15,61 @@ pub fn load_channel_with_replays<P: AsRef<Path>>(path: P) -> Result<Vec<ChannelW
     Ok(channels)
 }
 
+/// 채널 및 리플레이 데이터를 JSON 파일로 저장합니다.
+pub fn save_channel_with_replays<P: AsRef<Path>>(
+    path: P,
+    channels: &[ChannelWithReplays],
+) -> Result<()> {
+    let json = serde_json::to_string(channels)?;
+    fs::write(path.as_ref(), json)
+        .with_context(|| format!("Failed to write file: {:?}", path.as_ref()))
+}
+
+/// 새로 가져온 리플레이를 기존 목록에 합칩니다.
+/// 같은 `video_no`는 새 데이터로 교체하고, 최신 비디오가 앞에 오도록 정렬합니다.
+/// 새로 추가된 리플레이 수를 반환합니다.
+pub fn merge_replays(replays: &mut Vec<Replay>, fetched: Vec<Replay>) -> usize {
+    let mut added = 0;
+    for replay in fetched {
+        match replays.iter_mut().find(|r| r.video_no == replay.video_no) {
+            Some(existing) => *existing = replay,
+            None => {
+                replays.push(replay);
+                added += 1;
+            }
+        }
+    }
+    replays.sort_by_key(|r| std::cmp::Reverse(r.video_no));
+    added
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn replay(video_no: u64, title: &str) -> Replay {
+        Replay {
+            title: title.to_string(),
+            start: "2025-01-01 00:00:00".to_string(),
+            end: "2025-01-01 01:00:00".to_string(),
+            video_no,
+            thumbnail: None,
+            tags: Vec::new(),
+            category_ko: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_replays_without_duplicates() {
+        let mut replays = vec![replay(2, "old"), replay(1, "first")];
+        let added = merge_replays(&mut replays, vec![replay(3, "new"), replay(2, "updated")]);
+
+        assert_eq!(added, 1);
+        let video_nos: Vec<u64> = replays.iter().map(|r| r.video_no).collect();
+        assert_eq!(video_nos, vec![3, 2, 1]);
+        assert_eq!(replays[1].title, "updated");
+    }
+
     #[test]
     fn test_load_channel_with_replays() {
         // 프로젝트 루트 기준으로 web/public/channel_with_replays_0.json 파일 경로
@@ -1,6 +1,9 @@
+pub mod analysis_state;
 pub mod chat;
 pub mod loader;
 pub mod models;
+pub mod parquet_export;
 pub mod timeline;
 pub mod utils;
 pub mod video_analyzer;
+pub mod viewer_counts;
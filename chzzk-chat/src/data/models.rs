@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 /// ====== Channel With Replays JSON 구조체 ======
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Replay {
     pub title: String,
     pub start: String,
@@ -18,7 +18,7 @@ pub struct Replay {
     pub category_ko: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChannelWithReplays {
     pub name: String,
     pub follower: u64,
@@ -52,3 +52,22 @@ pub struct ChatLog {
     #[serde(rename = "m")]
     pub messages: Vec<ChatMessage>,
 }
+
+/// ====== Viewer Count 구조체 ======
+
+/// 라이브 동시 시청자 수 샘플 (`viewerCounts.jsonl` 한 줄)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewerSample {
+    #[serde(rename = "channelId")]
+    pub channel_id: String,
+    #[serde(rename = "liveId")]
+    pub live_id: u64,
+    /// 샘플링 시간 (ISO 8601 형식, KST)
+    pub timestamp: String,
+    #[serde(rename = "concurrentUserCount")]
+    pub concurrent_user_count: u64,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+}
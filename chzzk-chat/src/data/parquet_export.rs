@@ -0,0 +1,393 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use color_eyre::eyre::{Context, Result};
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use parquet::schema::types::Type;
+use rayon::prelude::*;
+
+use crate::data::chat::store::{ChatColumns, ChatStore};
+use crate::data::models::ChannelWithReplays;
+use crate::utils;
+
+/// 채팅 Parquet 파일 스키마
+/// partition 열(channel_id, date)은 파일에 넣지 않고 `channel_id=.../date=...` 디렉토리 이름으로 표현합니다.
+const CHAT_SCHEMA: &str = "
+message chat {
+    REQUIRED INT64 video_id;
+    REQUIRED INT64 timestamp (TIMESTAMP(MILLIS,true));
+    REQUIRED BINARY user_id (STRING);
+    REQUIRED BINARY nickname (STRING);
+    REQUIRED BINARY message (STRING);
+    OPTIONAL BINARY channel_name (STRING);
+    OPTIONAL BINARY replay_title (STRING);
+    OPTIONAL BINARY category (STRING);
+}
+";
+
+/// 채널 정보가 없는 비디오의 channel_id partition 값 (Hive 기본값)
+const UNKNOWN_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// Parquet 내보내기 결과
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ParquetExportSummary {
+    pub videos: usize,
+    pub files: usize,
+    pub rows: usize,
+}
+
+/// 비디오의 채널/다시보기 정보
+struct VideoMeta<'a> {
+    channel_id: &'a str,
+    channel_name: &'a str,
+    title: &'a str,
+    category: Option<&'a str>,
+}
+
+/// 채팅 로그 전체를 채널과 날짜(KST)로 나눈 Parquet 파일로 내보냅니다.
+/// 파일 경로는 `{output_dir}/channel_id={id}/date={YYYY-MM-DD}/part-{video_id}.parquet`이며,
+/// 같은 비디오를 다시 내보내면 해당 파일을 덮어쓰고, 채널이나 날짜가 바뀌어 더 이상 쓰지 않는
+/// 이전 partition의 `part-{video_id}.parquet` 파일은 지웁니다.
+pub fn export_chat_parquet<P: AsRef<Path>>(
+    store: &ChatStore,
+    channels: &[ChannelWithReplays],
+    output_dir: P,
+) -> Result<ParquetExportSummary> {
+    let output_dir = output_dir.as_ref();
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create output directory: {:?}", output_dir))?;
+
+    // video_id -> 채널/다시보기 정보
+    let video_meta: HashMap<u64, VideoMeta> = channels
+        .iter()
+        .flat_map(|channel| {
+            channel.replays.iter().map(move |replay| {
+                (
+                    replay.video_no,
+                    VideoMeta {
+                        channel_id: &channel.channel_id,
+                        channel_name: &channel.name,
+                        title: &replay.title,
+                        category: replay.category_ko.as_deref(),
+                    },
+                )
+            })
+        })
+        .collect();
+
+    let schema = Arc::new(parse_message_type(CHAT_SCHEMA).context("Invalid parquet schema")?);
+    let props = Arc::new(
+        WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build(),
+    );
+
+    // 이전 내보내기에서 쓴 비디오별 part 파일 (다시 쓰지 않은 파일은 지움)
+    let existing = existing_parts(output_dir)?;
+
+    let pb = utils::create_progress_bar(store.len() as u64, "Exporting parquet...");
+    let results: Vec<Result<(usize, usize)>> = store
+        .logs
+        .par_iter()
+        .map(|chat_log| {
+            let result = export_video(
+                store,
+                chat_log,
+                video_meta.get(&chat_log.video_id),
+                output_dir,
+                &schema,
+                &props,
+            )
+            .and_then(|written| {
+                remove_stale_parts(existing.get(&chat_log.video_id), &written)?;
+                Ok((written.len(), chat_log.len()))
+            });
+            pb.inc(1);
+            result
+        })
+        .collect();
+    pb.finish_with_message("Parquet exported!");
+
+    let mut summary = ParquetExportSummary::default();
+    for result in results {
+        let (files, rows) = result?;
+        summary.videos += 1;
+        summary.files += files;
+        summary.rows += rows;
+    }
+    Ok(summary)
+}
+
+/// 비디오 하나의 채팅을 날짜별 파일로 씁니다. 쓴 파일 경로 목록을 반환합니다.
+fn export_video(
+    store: &ChatStore,
+    chat_log: &ChatColumns,
+    meta: Option<&VideoMeta>,
+    output_dir: &Path,
+    schema: &Arc<Type>,
+    props: &Arc<WriterProperties>,
+) -> Result<Vec<PathBuf>> {
+    let channel_id = meta.map(|m| m.channel_id).unwrap_or(UNKNOWN_PARTITION);
+    let indices = chat_log.sorted_indices();
+
+    let mut written = Vec::new();
+    for day in indices
+        .chunk_by(|&a, &b| chat_log.datetime(a).date_naive() == chat_log.datetime(b).date_naive())
+    {
+        let date = chat_log.datetime(day[0]).format("%Y-%m-%d");
+        let path = part_path(output_dir, channel_id, &date.to_string(), chat_log.video_id);
+        write_parquet_file(store, chat_log, day, meta, &path, schema, props)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// 비디오의 partition 파일 경로
+fn part_path(output_dir: &Path, channel_id: &str, date: &str, video_id: u64) -> PathBuf {
+    partition_dir(output_dir, channel_id, date).join(format!("part-{}.parquet", video_id))
+}
+
+/// 출력 디렉토리의 `channel_id=*/date=*/part-{video_id}.parquet` 파일을 video_id별로 모읍니다.
+fn existing_parts(output_dir: &Path) -> Result<HashMap<u64, Vec<PathBuf>>> {
+    let read_dirs = |dir: &Path, prefix: &str| -> Result<Vec<PathBuf>> {
+        Ok(fs::read_dir(dir)
+            .with_context(|| format!("Failed to read directory: {:?}", dir))?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| {
+                path.is_dir()
+                    && path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with(prefix))
+            })
+            .collect())
+    };
+
+    let mut parts: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for channel_dir in read_dirs(output_dir, "channel_id=")? {
+        for date_dir in read_dirs(&channel_dir, "date=")? {
+            let entries = fs::read_dir(&date_dir)
+                .with_context(|| format!("Failed to read directory: {:?}", date_dir))?;
+            for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+                let video_id = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(|n| n.strip_prefix("part-")?.strip_suffix(".parquet"))
+                    .and_then(|id| id.parse().ok());
+                if let Some(video_id) = video_id {
+                    parts.entry(video_id).or_default().push(path);
+                }
+            }
+        }
+    }
+    Ok(parts)
+}
+
+/// 이전에 쓴 part 파일 중 이번에 다시 쓰지 않은 파일을 지우고, 비게 된 partition 디렉토리도 지웁니다.
+fn remove_stale_parts(existing: Option<&Vec<PathBuf>>, written: &[PathBuf]) -> Result<()> {
+    for path in existing.into_iter().flatten() {
+        if written.contains(path) {
+            continue;
+        }
+        fs::remove_file(path)
+            .with_context(|| format!("Failed to remove stale parquet file: {:?}", path))?;
+
+        // 비어 있지 않으면 실패하므로 결과는 무시
+        if let Some(date_dir) = path.parent() {
+            if fs::remove_dir(date_dir).is_ok() {
+                if let Some(channel_dir) = date_dir.parent() {
+                    fs::remove_dir(channel_dir).ok();
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `channel_id=.../date=...` partition 디렉토리 경로
+fn partition_dir(output_dir: &Path, channel_id: &str, date: &str) -> PathBuf {
+    output_dir
+        .join(format!("channel_id={}", channel_id))
+        .join(format!("date={}", date))
+}
+
+/// 메시지 번호 목록을 Parquet 파일 하나(row group 하나)로 씁니다.
+fn write_parquet_file(
+    store: &ChatStore,
+    chat_log: &ChatColumns,
+    indices: &[usize],
+    meta: Option<&VideoMeta>,
+    path: &Path,
+    schema: &Arc<Type>,
+    props: &Arc<WriterProperties>,
+) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+    }
+    let file = fs::File::create(path)
+        .with_context(|| format!("Failed to create parquet file: {:?}", path))?;
+
+    let n = indices.len();
+    let video_ids = vec![chat_log.video_id as i64; n];
+    let timestamps: Vec<i64> = indices
+        .iter()
+        .map(|&i| chat_log.timestamp(i) * 1000)
+        .collect();
+    let user_ids: Vec<ByteArray> = indices
+        .iter()
+        .map(|&i| ByteArray::from(store.user_id(chat_log.user(i))))
+        .collect();
+    let nicknames: Vec<ByteArray> = indices
+        .iter()
+        .map(|&i| ByteArray::from(store.nickname(chat_log.nickname(i))))
+        .collect();
+    let messages: Vec<ByteArray> = indices
+        .iter()
+        .map(|&i| ByteArray::from(chat_log.message(i)))
+        .collect();
+
+    // 비디오 단위로 같은 값인 메타데이터 열 (없으면 null)
+    let optional = |value: Option<&str>| -> (Vec<ByteArray>, Vec<i16>) {
+        match value {
+            Some(value) => (vec![ByteArray::from(value); n], vec![1; n]),
+            None => (Vec::new(), vec![0; n]),
+        }
+    };
+    let optional_columns = [
+        optional(meta.map(|m| m.channel_name)),
+        optional(meta.map(|m| m.title)),
+        optional(meta.and_then(|m| m.category)),
+    ];
+
+    let mut writer = SerializedFileWriter::new(file, schema.clone(), props.clone())?;
+    let mut row_group = writer.next_row_group()?;
+    let mut column_idx = 0;
+    while let Some(mut column) = row_group.next_column()? {
+        match column_idx {
+            0 => {
+                column
+                    .typed::<Int64Type>()
+                    .write_batch(&video_ids, None, None)?;
+            }
+            1 => {
+                column
+                    .typed::<Int64Type>()
+                    .write_batch(&timestamps, None, None)?;
+            }
+            2..=4 => {
+                let values = [&user_ids, &nicknames, &messages][column_idx - 2];
+                column
+                    .typed::<ByteArrayType>()
+                    .write_batch(values, None, None)?;
+            }
+            _ => {
+                let (values, def_levels) = &optional_columns[column_idx - 5];
+                column
+                    .typed::<ByteArrayType>()
+                    .write_batch(values, Some(def_levels), None)?;
+            }
+        }
+        column.close()?;
+        column_idx += 1;
+    }
+    row_group.close()?;
+    writer
+        .close()
+        .with_context(|| format!("Failed to write parquet file: {:?}", path))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::chat::loader::parse_chat_line;
+    use crate::data::models::{ChatLog, Replay};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    #[test]
+    fn test_export_chat_parquet_partitions() {
+        let dir = std::env::temp_dir().join(format!("chzzk-chat-parquet-{}", std::process::id()));
+        let store = ChatStore::from_chat_logs(vec![
+            ChatLog {
+                video_id: 10,
+                messages: [
+                    "[2025-01-01 23:59:59] a: 하나 (u1)",
+                    "[2025-01-02 00:00:01] b: 둘 (u2)",
+                    "[2025-01-02 00:00:02] a: 셋 (u1)",
+                ]
+                .iter()
+                .map(|l| parse_chat_line(l).unwrap())
+                .collect(),
+            },
+            ChatLog {
+                video_id: 11,
+                messages: vec![parse_chat_line("[2025-01-03 12:00:00] c: 넷 (u3)").unwrap()],
+            },
+        ]);
+        let channels = vec![ChannelWithReplays {
+            name: "채널".to_string(),
+            follower: 0,
+            channel_id: "ch1".to_string(),
+            image: None,
+            replays: vec![Replay {
+                title: "방송".to_string(),
+                start: "2025-01-01 23:00:00".to_string(),
+                end: "2025-01-02 01:00:00".to_string(),
+                video_no: 10,
+                thumbnail: None,
+                tags: Vec::new(),
+                category_ko: None,
+            }],
+        }];
+
+        let summary = export_chat_parquet(&store, &channels, &dir).unwrap();
+        assert_eq!((summary.videos, summary.files, summary.rows), (2, 3, 4));
+
+        let num_rows = |path: PathBuf| {
+            let reader = SerializedFileReader::new(fs::File::open(path).unwrap()).unwrap();
+            reader.metadata().file_metadata().num_rows()
+        };
+        assert_eq!(
+            num_rows(partition_dir(&dir, "ch1", "2025-01-01").join("part-10.parquet")),
+            1
+        );
+        assert_eq!(
+            num_rows(partition_dir(&dir, "ch1", "2025-01-02").join("part-10.parquet")),
+            2
+        );
+        assert_eq!(
+            num_rows(partition_dir(&dir, UNKNOWN_PARTITION, "2025-01-03").join("part-11.parquet")),
+            1
+        );
+
+        // 채널 정보가 생긴 비디오를 다시 내보내면 이전 partition의 파일은 지워짐
+        let mut channels = channels;
+        channels[0].replays.push(Replay {
+            title: "다음 방송".to_string(),
+            start: "2025-01-03 11:00:00".to_string(),
+            end: "2025-01-03 13:00:00".to_string(),
+            video_no: 11,
+            thumbnail: None,
+            tags: Vec::new(),
+            category_ko: None,
+        });
+        let summary = export_chat_parquet(&store, &channels, &dir).unwrap();
+        assert_eq!((summary.videos, summary.files, summary.rows), (2, 3, 4));
+        assert!(part_path(&dir, "ch1", "2025-01-03", 11).exists());
+        assert!(!dir
+            .join(format!("channel_id={}", UNKNOWN_PARTITION))
+            .exists());
+        assert!(part_path(&dir, "ch1", "2025-01-01", 10).exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -5,7 +5,7 @@ use std::path::Path;
 use color_eyre::eyre::{Context, Result};
 use serde::Serialize;
 
-use crate::data::models::ChatLog;
+use crate::data::chat::store::{kst_datetime, ChatColumns, ChatStore};
 use crate::utils;
 
 /// 10분 단위 타임라인 데이터
@@ -26,6 +26,7 @@ pub struct VideoChatTimeline {
     pub start_time: String,
     /// 타임라인 데이터 (10분 단위)
     #[serde(skip)]
+    #[allow(dead_code)]
     pub timeline: Vec<TimelineEntry>,
 }
 
@@ -36,27 +37,19 @@ struct VideoChatTimelineJson {
 }
 
 /// 단일 채팅 로그에 대한 10분 단위 타임라인을 계산합니다.
-pub fn calculate_chat_timeline(chat_log: &ChatLog) -> Option<VideoChatTimeline> {
-    if chat_log.messages.is_empty() {
-        return None;
-    }
-
+pub fn calculate_chat_timeline(chat_log: &ChatColumns) -> Option<VideoChatTimeline> {
     const MINUTE_INTERVAL: i64 = 10; // 10분
     const INTERVAL_SECONDS: i64 = MINUTE_INTERVAL * 60; // 600초
 
-    // 메시지를 시간 순으로 정렬
-    let mut sorted_messages: Vec<_> = chat_log.messages.iter().collect();
-    sorted_messages.sort_by_key(|msg| msg.timestamp);
-
     // 첫 메시지 시간을 기준으로 설정
-    let first_time = sorted_messages[0].timestamp;
+    let first_time = chat_log.first_timestamp()?;
 
     // 10분 단위로 메시지 개수 계산
     let mut timeline_map: HashMap<i64, usize> = HashMap::new();
 
-    for message in &sorted_messages {
+    for &timestamp in chat_log.timestamps() {
         // 첫 메시지 시간으로부터 경과 시간 (초)
-        let elapsed_seconds = (message.timestamp - first_time).num_seconds();
+        let elapsed_seconds = timestamp - first_time;
 
         // 10분 단위로 구간 나누기 (0, 600, 1200, ...)
         let time_bucket = (elapsed_seconds / INTERVAL_SECONDS) * INTERVAL_SECONDS;
@@ -73,7 +66,9 @@ pub fn calculate_chat_timeline(chat_log: &ChatLog) -> Option<VideoChatTimeline>
     timeline.sort_by_key(|e| e.time);
 
     // 첫 메시지 시간을 ISO 8601 형식으로 변환
-    let start_time = first_time.format("%Y-%m-%dT%H:%M:%S%z").to_string();
+    let start_time = kst_datetime(first_time)
+        .format("%Y-%m-%dT%H:%M:%S%z")
+        .to_string();
 
     Some(VideoChatTimeline {
         video_id: chat_log.video_id,
@@ -83,8 +78,9 @@ pub fn calculate_chat_timeline(chat_log: &ChatLog) -> Option<VideoChatTimeline>
 }
 
 /// 모든 채팅 로그에 대한 타임라인을 추출합니다.
-pub fn extract_video_chat_timelines(chat_logs: &[ChatLog]) -> Vec<VideoChatTimeline> {
+pub fn extract_video_chat_timelines(chat_logs: &ChatStore) -> Vec<VideoChatTimeline> {
     let mut video_timelines: Vec<VideoChatTimeline> = chat_logs
+        .logs
         .iter()
         .filter_map(calculate_chat_timeline)
         .collect();
@@ -116,7 +112,7 @@ pub fn export_video_chat_timeline_json<P: AsRef<Path>>(
 
 /// 채팅을 10분 단위로 쪼개서 개수를 세고 JSON 파일로 내보냅니다.
 pub fn extract_video_chat_timeline_count<P: AsRef<Path>>(
-    chat_logs: &[ChatLog],
+    chat_logs: &ChatStore,
     output_path: P,
 ) -> Result<()> {
     utils::log("비디오별 채팅 타임라인 추출 중...");
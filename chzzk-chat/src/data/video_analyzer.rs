@@ -1,18 +1,19 @@
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
-use std::sync::Arc;
 
 use chrono::{DateTime, Duration, FixedOffset};
 use color_eyre::eyre::{Context, Result};
 use rayon::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::data::models::{ChannelWithReplays, ChatLog, Replay};
+use crate::data::chat::minhash::SimilarityMode;
+use crate::data::chat::store::{intersection_count, ChatStore};
+use crate::data::models::{ChannelWithReplays, Replay};
 use crate::data::utils::parse_replay_time;
 
 /// 비디오 연관도 정보
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VideoRelation {
     /// 연관된 비디오 번호
     pub video_no: u64,
@@ -53,35 +54,27 @@ fn is_time_range_overlapping(
     target_start_buffered < candidate_end && candidate_start < target_end_buffered
 }
 
-/// 비디오별 채팅 유저 집합을 구합니다.
-fn build_video_user_map(chat_logs: &[ChatLog]) -> HashMap<u64, HashSet<String>> {
-    let mut video_users: HashMap<u64, HashSet<String>> = HashMap::new();
-
-    for chat_log in chat_logs {
-        let users = video_users.entry(chat_log.video_id).or_default();
-
-        for message in &chat_log.messages {
-            users.insert(message.user_id.clone());
-        }
-    }
-
-    video_users
+/// 비디오별 채팅 유저 번호 목록(정렬, 중복 제거)을 구합니다.
+/// 같은 video_id의 로그는 `ChatStore`가 이미 하나로 합쳐 두었습니다.
+fn build_video_user_map(chat_logs: &ChatStore) -> HashMap<u64, Vec<u32>> {
+    chat_logs
+        .logs
+        .par_iter()
+        .map(|chat_log| (chat_log.video_id, chat_log.unique_users()))
+        .collect()
 }
 
 /// 두 비디오 간 유저 겹침 유사도를 계산합니다 (Jaccard 유사도).
-fn calculate_user_overlap_similarity(
-    users_a: &HashSet<String>,
-    users_b: &HashSet<String>,
-) -> (f64, usize) {
+fn calculate_user_overlap_similarity(users_a: &[u32], users_b: &[u32]) -> (f64, usize) {
     if users_a.is_empty() || users_b.is_empty() {
         return (0.0, 0);
     }
 
     // 교집합 계산
-    let intersection = users_a.intersection(users_b).count();
+    let intersection = intersection_count(users_a, users_b);
 
-    // 합집합 계산
-    let union = users_a.union(users_b).count();
+    // 합집합 계산: |A ∪ B| = |A| + |B| - |A ∩ B|
+    let union = users_a.len() + users_b.len() - intersection;
 
     // Jaccard 유사도: intersection / union
     let similarity = if union > 0 {
@@ -106,7 +99,7 @@ fn calculate_user_overlap_similarity(
 pub fn find_related_replays(
     target_video_no: u64,
     channels: &[ChannelWithReplays],
-    chat_logs: &[ChatLog],
+    chat_logs: &ChatStore,
 ) -> Result<Vec<VideoRelation>> {
     // 1. 대상 비디오의 Replay 찾기
     let target_replay = channels
@@ -135,8 +128,8 @@ pub fn find_related_replays(
     // 4. 대상 비디오의 유저 집합
     let target_users = video_users
         .get(&target_video_no)
-        .cloned()
-        .unwrap_or_default();
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
 
     // 5. 모든 리플레이를 순회하며 시간 범위가 겹치는 것 찾기
     let mut related_replays: Vec<VideoRelation> = Vec::new();
@@ -180,12 +173,12 @@ pub fn find_related_replays(
             // 채팅 유저 집합 가져오기
             let candidate_users = video_users
                 .get(&replay.video_no)
-                .cloned()
-                .unwrap_or_default();
+                .map(Vec::as_slice)
+                .unwrap_or(&[]);
 
             // 유저 겹침 유사도 계산
             let (similarity, shared_users) =
-                calculate_user_overlap_similarity(&target_users, &candidate_users);
+                calculate_user_overlap_similarity(target_users, candidate_users);
 
             // 유사도가 0.05 이상인 경우만 추가
             if similarity >= 0.05 {
@@ -243,23 +236,98 @@ pub fn print_related_replays(relations: &[VideoRelation], max_count: Option<usiz
     println!();
 }
 
+/// 주어진 시간 범위와 겹치는 리플레이를 모두 찾습니다.
+/// 연관 비디오와 같은 겹침 기준(앞뒤 10분 여유)을 쓰며, 시간을 파싱할 수 없는 리플레이는 건너뜁니다.
+pub fn find_overlapping_replays(
+    channels: &[ChannelWithReplays],
+    start: DateTime<FixedOffset>,
+    end: DateTime<FixedOffset>,
+) -> Vec<(&ChannelWithReplays, &Replay)> {
+    channels
+        .iter()
+        .flat_map(|channel| channel.replays.iter().map(move |replay| (channel, replay)))
+        .filter(|(_, replay)| {
+            match (
+                parse_replay_time(&replay.start),
+                parse_replay_time(&replay.end),
+            ) {
+                (Ok(replay_start), Ok(replay_end)) => {
+                    is_time_range_overlapping(start, end, replay_start, replay_end)
+                }
+                _ => false,
+            }
+        })
+        .collect()
+}
+
+/// 증분 계산에 사용할 이전 실행의 비디오 연관도
+pub struct PreviousRelations<'a> {
+    /// 이전 실행의 비디오별 연관 비디오
+    pub relations: &'a HashMap<u64, Vec<VideoRelation>>,
+    /// 사용자 집합이나 시간 범위가 바뀌었거나 새로 생긴 비디오
+    pub changed: &'a HashSet<u64>,
+}
+
+/// 대상 비디오와 후보 리플레이의 연관도를 계산합니다.
+/// 시간 범위가 겹치지 않거나 유사도가 0.02 미만이면 None을 반환합니다.
+fn relate_replay(
+    target: (DateTime<FixedOffset>, DateTime<FixedOffset>, &[u32]),
+    channel: &ChannelWithReplays,
+    replay: &Replay,
+    video_users: &HashMap<u64, Vec<u32>>,
+    time_cache: &HashMap<String, DateTime<FixedOffset>>,
+) -> Option<VideoRelation> {
+    let (target_start, target_end, target_users) = target;
+
+    // 시간 범위 파싱
+    let candidate_start = *time_cache.get(replay.start.as_str())?;
+    let candidate_end = *time_cache.get(replay.end.as_str())?;
+
+    // 시간 범위가 겹치는지 확인
+    if !is_time_range_overlapping(target_start, target_end, candidate_start, candidate_end) {
+        return None;
+    }
+
+    // 채팅 유저 집합 가져오기
+    let candidate_users = video_users
+        .get(&replay.video_no)
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+
+    // 유저 겹침 유사도 계산
+    let (similarity, shared_users) =
+        calculate_user_overlap_similarity(target_users, candidate_users);
+
+    // 유사도가 0.02 이상인 경우만 추가
+    (similarity >= 0.02).then(|| VideoRelation {
+        video_no: replay.video_no,
+        similarity,
+        shared_users,
+        title: replay.title.clone(),
+        channel_name: channel.name.clone(),
+    })
+}
+
 /// 모든 비디오 쌍에 대한 연관도를 분석합니다.
+/// `previous`가 있으면 바뀌지 않은 비디오끼리의 연관도는 이전 결과를 쓰고,
+/// 바뀐 비디오가 포함된 쌍만 다시 계산합니다.
 ///
 /// # Arguments
 /// * `channels` - 모든 채널 및 리플레이 데이터
-/// * `chat_logs` - 모든 채팅 로그
+/// * `video_users` - 비디오별 채팅 유저 번호 목록 (정렬, 중복 제거)
+/// * `previous` - 이전 실행 결과 (없으면 전체 계산)
+/// * `mode` - `Approx`면 MinHash LSH 후보 쌍만 비교
 ///
 /// # Returns
 /// 각 비디오별로 연관된 다른 비디오들의 리스트
 pub fn analyze_all_video_relations(
     channels: &[ChannelWithReplays],
-    chat_logs: &[ChatLog],
+    video_users: &HashMap<u64, Vec<u32>>,
+    previous: Option<&PreviousRelations>,
+    mode: SimilarityMode,
 ) -> Result<HashMap<u64, Vec<VideoRelation>>> {
     use crate::utils;
 
-    // 비디오별 채팅 유저 집합 구하기
-    let video_users = build_video_user_map(chat_logs);
-
     // 모든 비디오 번호 수집
     let all_video_nos: Vec<u64> = channels
         .iter()
@@ -272,19 +340,49 @@ pub fn analyze_all_video_relations(
         total_videos
     ));
 
-    // Arc로 감싸서 여러 스레드에서 공유 가능하게 만들기
-    let channels_arc = Arc::new(channels);
-    let video_users_arc = Arc::new(video_users);
-
-    let mut replay_by_video_no: HashMap<u64, &Replay> = HashMap::new();
+    let mut replay_by_video_no: HashMap<u64, (&ChannelWithReplays, &Replay)> = HashMap::new();
     for channel in channels {
         for replay in &channel.replays {
-            replay_by_video_no.insert(replay.video_no, replay);
+            replay_by_video_no.insert(replay.video_no, (channel, replay));
         }
     }
 
+    // 근사 모드면 비디오별 LSH 후보 비디오 (양방향)
+    let mut video_nos_with_users: Vec<u64> = video_users.keys().copied().collect();
+    video_nos_with_users.sort_unstable();
+    let sets: Vec<&[u32]> = video_nos_with_users
+        .iter()
+        .map(|video_no| video_users[video_no].as_slice())
+        .collect();
+    let neighbors: Option<HashMap<u64, HashSet<u64>>> = mode.candidates(&sets).map(|candidates| {
+        let mut neighbors: HashMap<u64, HashSet<u64>> = HashMap::new();
+        for (i, targets) in candidates.into_iter().enumerate() {
+            for j in targets {
+                let (a, b) = (video_nos_with_users[i], video_nos_with_users[j]);
+                neighbors.entry(a).or_default().insert(b);
+                neighbors.entry(b).or_default().insert(a);
+            }
+        }
+        neighbors
+    });
+    let is_candidate = |a: u64, b: u64| {
+        neighbors
+            .as_ref()
+            .is_none_or(|neighbors| neighbors.get(&a).is_some_and(|n| n.contains(&b)))
+    };
+
+    // 다시 계산해야 하는 (바뀐) 리플레이
+    let changed_replays: Vec<(&ChannelWithReplays, &Replay)> = match previous {
+        Some(previous) => channels
+            .iter()
+            .flat_map(|channel| channel.replays.iter().map(move |r| (channel, r)))
+            .filter(|(_, replay)| previous.changed.contains(&replay.video_no))
+            .collect(),
+        None => Vec::new(),
+    };
+
     // 모든 리플레이의 시간 문자열을 사전에 파싱하여 캐싱
-    // 불변 HashMap으로 만들어 Arc로 공유하므로 병렬 구간에서 락 없이 조회 가능
+    // 불변 HashMap으로 만들어 공유하므로 병렬 구간에서 락 없이 조회 가능
     let mut time_cache: HashMap<String, DateTime<FixedOffset>> = HashMap::new();
     for channel in channels {
         for replay in &channel.replays {
@@ -300,107 +398,91 @@ pub fn analyze_all_video_relations(
             }
         }
     }
-    let time_cache_arc = Arc::new(time_cache);
-
-    let empty_set = HashSet::new();
 
     // Progress bar 생성
     let pb = utils::create_progress_bar(total_videos as u64, "Analyzing video relations...");
-    let pb_arc = Arc::new(pb);
 
     // 병렬로 각 비디오에 대해 연관도 계산
     let relations_vec: Vec<(u64, Vec<VideoRelation>)> = all_video_nos
         .par_iter()
         .filter_map(|target_video_no| {
-            let channels_ref = Arc::clone(&channels_arc);
-            let video_users_ref = Arc::clone(&video_users_arc);
-            let time_cache_ref = Arc::clone(&time_cache_arc);
-            let pb_ref = Arc::clone(&pb_arc);
-
             // Progress bar 업데이트
-            pb_ref.inc(1);
+            pb.inc(1);
 
             // 대상 비디오의 Replay 찾기
-            let target_replay = match replay_by_video_no.get(target_video_no) {
-                Some(r) => *r,
-                None => return None,
-            };
+            let (_, target_replay) = replay_by_video_no.get(target_video_no)?;
 
             // 대상 비디오의 시간 범위 파싱
-            let target_start = match time_cache_ref.get(target_replay.start.as_str()) {
-                Some(dt) => *dt,
-                None => return None,
-            };
-
-            let target_end = match time_cache_ref.get(target_replay.end.as_str()) {
-                Some(dt) => *dt,
-                None => return None,
-            };
+            let target_start = *time_cache.get(target_replay.start.as_str())?;
+            let target_end = *time_cache.get(target_replay.end.as_str())?;
 
             // 대상 비디오의 유저 집합
-            let target_users = video_users_ref.get(target_video_no).unwrap_or(&empty_set);
+            let target_users = video_users
+                .get(target_video_no)
+                .map(Vec::as_slice)
+                .unwrap_or(&[]);
 
             if target_users.is_empty() {
                 return None;
             }
 
-            // 다른 모든 비디오와 비교
-            let mut relations: Vec<VideoRelation> = Vec::new();
-
-            for channel in channels_ref.iter() {
-                for replay in &channel.replays {
-                    // 자기 자신은 제외
-                    if replay.video_no == *target_video_no {
-                        continue;
-                    }
-
-                    // 시간 범위 파싱
-                    let candidate_start = match time_cache_ref.get(replay.start.as_str()) {
-                        Some(dt) => *dt,
-                        None => continue,
-                    };
-
-                    let candidate_end = match time_cache_ref.get(replay.end.as_str()) {
-                        Some(dt) => *dt,
-                        None => continue,
-                    };
-
-                    // 시간 범위가 겹치는지 확인
-                    if !is_time_range_overlapping(
-                        target_start,
-                        target_end,
-                        candidate_start,
-                        candidate_end,
-                    ) {
-                        continue;
-                    }
-
-                    // 채팅 유저 집합 가져오기
-                    let candidate_users =
-                        video_users_ref.get(&replay.video_no).unwrap_or(&empty_set);
-
-                    // 유저 겹침 유사도 계산
-                    let (similarity, shared_users) =
-                        calculate_user_overlap_similarity(target_users, candidate_users);
-
-                    // 유사도가 0.01 이상인 경우만 추가
-                    if similarity >= 0.02 {
-                        relations.push(VideoRelation {
-                            video_no: replay.video_no,
-                            similarity,
-                            shared_users,
+            let target = (target_start, target_end, target_users);
+            let relate = |&(channel, replay): &(&ChannelWithReplays, &Replay)| {
+                // 자기 자신은 제외
+                if replay.video_no == *target_video_no {
+                    return None;
+                }
+                relate_replay(target, channel, replay, video_users, &time_cache)
+            };
+
+            let mut relations: Vec<VideoRelation> = match previous {
+                // 대상이 그대로면 바뀌지 않은 비디오와의 연관도는 이전 결과를 사용
+                Some(previous) if !previous.changed.contains(target_video_no) => previous
+                    .relations
+                    .get(target_video_no)
+                    .into_iter()
+                    .flatten()
+                    .filter(|relation| !previous.changed.contains(&relation.video_no))
+                    .filter_map(|relation| {
+                        // 사라진 비디오는 제외하고, 제목/채널 이름은 현재 값으로 갱신
+                        let (channel, replay) = replay_by_video_no.get(&relation.video_no)?;
+                        Some(VideoRelation {
                             title: replay.title.clone(),
                             channel_name: channel.name.clone(),
-                        });
-                    }
-                }
-            }
+                            ..relation.clone()
+                        })
+                    })
+                    .chain(
+                        changed_replays
+                            .iter()
+                            .filter(|(_, replay)| is_candidate(*target_video_no, replay.video_no))
+                            .filter_map(relate),
+                    )
+                    .collect(),
+                // 근사 모드면 LSH 후보 비디오와만 비교
+                _ => match &neighbors {
+                    Some(neighbors) => neighbors
+                        .get(target_video_no)
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|video_no| replay_by_video_no.get(video_no))
+                        .filter_map(relate)
+                        .collect(),
+                    // 다른 모든 비디오와 비교
+                    None => channels
+                        .iter()
+                        .flat_map(|channel| channel.replays.iter().map(move |r| (channel, r)))
+                        .filter_map(|candidate| relate(&candidate))
+                        .collect(),
+                },
+            };
 
-            // 유사도 기준으로 정렬 (내림차순)
+            // 유사도 기준으로 정렬 (내림차순, 같으면 비디오 번호 순)
             relations.sort_by(|a, b| {
                 b.similarity
                     .partial_cmp(&a.similarity)
                     .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(a.video_no.cmp(&b.video_no))
             });
 
             Some((*target_video_no, relations))
@@ -408,7 +490,7 @@ pub fn analyze_all_video_relations(
         .collect();
 
     // Progress bar 완료
-    pb_arc.finish_with_message("Video relations analyzed!");
+    pb.finish_with_message("Video relations analyzed!");
 
     // HashMap으로 변환
     let all_relations: HashMap<u64, Vec<VideoRelation>> = relations_vec.into_iter().collect();
@@ -430,7 +512,7 @@ pub fn print_all_video_relations(
 
     // 연관 비디오가 많은 순으로 정렬
     let mut sorted_videos: Vec<_> = all_relations.iter().collect();
-    sorted_videos.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+    sorted_videos.sort_by_key(|(_, relations)| std::cmp::Reverse(relations.len()));
 
     for (video_no, relations) in sorted_videos.iter().take(20) {
         if relations.is_empty() {
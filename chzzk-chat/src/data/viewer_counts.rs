@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use color_eyre::eyre::{Context, Result};
+use serde::Serialize;
+
+use crate::data::models::ViewerSample;
+use crate::data::utils::parse_replay_time;
+use crate::utils;
+
+/// 시청자 수 타임라인 한 점
+#[derive(Debug, Serialize, Clone)]
+pub struct ViewerCountEntry {
+    /// 경과 시간 (초 단위, 첫 샘플 기준 0초)
+    pub time: i64,
+    /// 동시 시청자 수
+    pub count: u64,
+}
+
+/// 라이브별 시청자 수 타임라인
+#[derive(Debug, Serialize, Clone)]
+pub struct LiveViewerTimeline {
+    #[serde(rename = "channelId")]
+    pub channel_id: String,
+    #[serde(rename = "liveId")]
+    pub live_id: u64,
+    /// 마지막 샘플의 방송 제목
+    pub title: Option<String>,
+    /// 마지막 샘플의 카테고리
+    pub category: Option<String>,
+    /// 첫 샘플 시간 (ISO 8601 형식)
+    pub start_time: String,
+    pub timeline: Vec<ViewerCountEntry>,
+}
+
+/// 시청자 수 타임라인 데이터 집합
+#[derive(Debug, Serialize)]
+struct LiveViewerTimelineJson {
+    lives: Vec<LiveViewerTimeline>,
+}
+
+/// `viewerCounts.jsonl` 파일을 읽습니다. 파싱할 수 없는 줄은 건너뜁니다.
+pub fn load_viewer_samples<P: AsRef<Path>>(path: P) -> Result<Vec<ViewerSample>> {
+    let content = fs::read_to_string(path.as_ref())
+        .with_context(|| format!("Failed to read viewer sample file: {:?}", path.as_ref()))?;
+
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// 샘플들을 (channel_id, live_id)별 타임라인으로 묶습니다.
+pub fn build_viewer_timelines(samples: &[ViewerSample]) -> Vec<LiveViewerTimeline> {
+    let mut grouped: BTreeMap<(String, u64), Vec<&ViewerSample>> = BTreeMap::new();
+    for sample in samples {
+        grouped
+            .entry((sample.channel_id.clone(), sample.live_id))
+            .or_default()
+            .push(sample);
+    }
+
+    let mut timelines: Vec<LiveViewerTimeline> = grouped
+        .into_iter()
+        .filter_map(|((channel_id, live_id), samples)| {
+            let mut points: Vec<_> = samples
+                .iter()
+                .filter_map(|s| Some((parse_replay_time(&s.timestamp).ok()?, *s)))
+                .collect();
+            points.sort_by_key(|(time, _)| *time);
+
+            let (first_time, _) = *points.first()?;
+            let (_, last) = *points.last()?;
+
+            Some(LiveViewerTimeline {
+                channel_id,
+                live_id,
+                title: last.title.clone(),
+                category: last.category.clone(),
+                start_time: first_time.format("%Y-%m-%dT%H:%M:%S%z").to_string(),
+                timeline: points
+                    .iter()
+                    .map(|(time, sample)| ViewerCountEntry {
+                        time: (*time - first_time).num_seconds(),
+                        count: sample.concurrent_user_count,
+                    })
+                    .collect(),
+            })
+        })
+        .collect();
+
+    timelines.sort_by_key(|t| t.live_id);
+    timelines
+}
+
+/// 시청자 수 샘플 파일을 라이브별 타임라인 JSON으로 내보냅니다.
+pub fn export_live_viewer_counts<P: AsRef<Path>, Q: AsRef<Path>>(
+    samples_path: P,
+    output_path: Q,
+) -> Result<()> {
+    utils::log("라이브별 시청자 수 타임라인 추출 중...");
+
+    let samples = load_viewer_samples(&samples_path)?;
+    let lives = build_viewer_timelines(&samples);
+
+    let json_string = serde_json::to_string(&LiveViewerTimelineJson {
+        lives: lives.clone(),
+    })
+    .context("Failed to serialize live viewer counts to JSON")?;
+    fs::write(&output_path, json_string)
+        .with_context(|| format!("Failed to write JSON file: {:?}", output_path.as_ref()))?;
+
+    utils::log(format!(
+        "라이브별 시청자 수 타임라인 추출 완료: {}개 라이브, {}개 샘플, 파일: {:?}",
+        lives.len(),
+        samples.len(),
+        output_path.as_ref()
+    ));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(live_id: u64, timestamp: &str, count: u64, title: &str) -> ViewerSample {
+        ViewerSample {
+            channel_id: "ch".to_string(),
+            live_id,
+            timestamp: timestamp.to_string(),
+            concurrent_user_count: count,
+            category: None,
+            title: Some(title.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_build_viewer_timelines() {
+        let samples = vec![
+            sample(1, "2025-01-01T12:01:00+0900", 150, "b"),
+            sample(1, "2025-01-01T12:00:00+0900", 100, "a"),
+            sample(2, "2025-01-01T13:00:00+0900", 10, "c"),
+        ];
+
+        let timelines = build_viewer_timelines(&samples);
+        assert_eq!(timelines.len(), 2);
+        assert_eq!(timelines[0].start_time, "2025-01-01T12:00:00+0900");
+        assert_eq!(timelines[0].title.as_deref(), Some("b"));
+        let points: Vec<(i64, u64)> = timelines[0]
+            .timeline
+            .iter()
+            .map(|e| (e.time, e.count))
+            .collect();
+        assert_eq!(points, vec![(0, 100), (60, 150)]);
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::time::Duration;
 
 use color_eyre::eyre::Result;
@@ -5,7 +6,12 @@ use mimalloc::MiMalloc;
 use structopt::StructOpt;
 use tokio::time;
 
-use crate::data::models::{ChannelWithReplays, ChatLog};
+use crate::data::chat::channel_distance::{ChannelLink, ChannelNode};
+use crate::data::chat::event_config::EventConfigOpt;
+use crate::data::chat::minhash::{LshParams, SimilarityMode};
+use crate::data::chat::store::{ChatColumns, ChatStore};
+use crate::data::models::ChannelWithReplays;
+use crate::data::video_analyzer::VideoRelation;
 
 mod api;
 mod command;
@@ -24,18 +30,54 @@ pub enum Opt {
     #[structopt(name = "live-chat-test")]
     LiveChatTest,
 
+    /// 실시간 채팅 수집 데몬 모드 (주기적 재스캔, SIGINT/SIGTERM 시 정상 종료)
+    #[structopt(name = "live")]
+    Live(command::live::LiveOpt),
+
     /// 채팅 분석 모드
     #[structopt(name = "analysis-chat")]
     AnalysisChat(AnalysisChatOpt),
 
     /// 실험 모드
     #[structopt(name = "experimental")]
-    Experimental,
+    Experimental(EventConfigOpt),
 
     /// 이벤트 추출 모드
     #[structopt(name = "extract-event")]
     ExtractEvent(command::extract_event::ExtractEventOpt),
 
+    /// 다시보기 채팅 다운로드 모드
+    #[structopt(name = "download-chat")]
+    DownloadChat(command::download_chat::DownloadChatOpt),
+
+    /// 채널 다시보기 목록 수집 모드 (채널 및 리플레이 JSON에 병합)
+    #[structopt(name = "list-replays")]
+    ListReplays(command::list_replays::ListReplaysOpt),
+
+    /// 채팅 로그 캐시 관리 모드 (list, verify, prune)
+    #[structopt(name = "cache")]
+    Cache(command::cache::CacheOpt),
+
+    /// 채팅 로그 형식 변환 모드 (텍스트, JSONL, CSV)
+    #[structopt(name = "convert")]
+    Convert(command::convert::ConvertOpt),
+
+    /// 채팅 로그 Parquet 내보내기 모드 (채널, 날짜별 partition)
+    #[structopt(name = "export-parquet")]
+    ExportParquet(command::export_parquet::ExportParquetOpt),
+
+    /// 유사도 근사(MinHash LSH) 오차 보고 모드 (exact와 approx 결과 비교)
+    #[structopt(name = "similarity-report")]
+    SimilarityReport(command::similarity_report::SimilarityReportOpt),
+
+    /// 이벤트 탐지 파라미터 튜닝 모드 (하이라이트 라벨 대비 precision/recall)
+    #[structopt(name = "tune-events")]
+    TuneEvents(command::tune_events::TuneEventsOpt),
+
+    /// 여러 VOD 이벤트 정렬 모드 (동시 방송의 offset 추정 및 공통 순간 추출)
+    #[structopt(name = "align-events")]
+    AlignEvents(command::align_events::AlignEventsOpt),
+
     /// 인터렉티브 모드
     #[structopt(name = "interactive")]
     Interactive,
@@ -50,6 +92,40 @@ pub struct AnalysisChatOpt {
 
     #[structopt(long)]
     pub enable_experimental: bool,
+
+    /// 증분 분석 상태 파일 경로 (기본값: ../chat_logs_cache/analysis_state.cbor)
+    #[structopt(long)]
+    pub state: Option<String>,
+
+    /// 이전 분석 상태를 무시하고 모든 채널/비디오 쌍을 다시 계산
+    #[structopt(long)]
+    pub full: bool,
+
+    /// 비디오 연관도와 클러스터링의 쌍 유사도 계산 방식
+    /// (exact: 모든 쌍, approx: MinHash LSH 후보 쌍만, 채널 거리는 항상 exact)
+    #[structopt(long, default_value = "exact")]
+    pub similarity: SimilarityMode,
+
+    /// LSH band 수 (approx)
+    #[structopt(long, default_value = "256")]
+    pub lsh_bands: usize,
+
+    /// LSH band당 row 수 (approx)
+    #[structopt(long, default_value = "2")]
+    pub lsh_rows: usize,
+}
+
+impl AnalysisChatOpt {
+    /// `--similarity`와 LSH 옵션으로 유사도 계산 방식을 만듭니다.
+    pub fn similarity_mode(&self) -> SimilarityMode {
+        match self.similarity {
+            SimilarityMode::Exact => SimilarityMode::Exact,
+            SimilarityMode::Approx(_) => SimilarityMode::Approx(LshParams {
+                bands: self.lsh_bands,
+                rows: self.lsh_rows,
+            }),
+        }
+    }
 }
 
 /// ====== 엔트리포인트 ======
@@ -61,9 +137,18 @@ async fn main() -> Result<()> {
 
     match opt {
         Opt::LiveChatTest => run_live_chat_test().await?,
+        Opt::Live(opts) => command::live::run_live(&opts).await?,
         Opt::AnalysisChat(opts) => run_analysis_chat(&opts).await?,
-        Opt::Experimental => run_experimental().await?,
+        Opt::Experimental(config) => run_experimental(&config).await?,
         Opt::ExtractEvent(opts) => command::extract_event::run_extract_event(&opts)?,
+        Opt::DownloadChat(opts) => command::download_chat::run_download_chat(&opts).await?,
+        Opt::ListReplays(opts) => command::list_replays::run_list_replays(&opts).await?,
+        Opt::Cache(opts) => command::cache::run_cache(&opts)?,
+        Opt::Convert(opts) => command::convert::run_convert(&opts)?,
+        Opt::ExportParquet(opts) => command::export_parquet::run_export_parquet(&opts)?,
+        Opt::SimilarityReport(opts) => command::similarity_report::run_similarity_report(&opts)?,
+        Opt::TuneEvents(opts) => command::tune_events::run_tune_events(&opts)?,
+        Opt::AlignEvents(opts) => command::align_events::run_align_events(&opts)?,
         Opt::Interactive => command::interactive::run_interactive().await?,
     }
 
@@ -73,7 +158,7 @@ async fn main() -> Result<()> {
 /// 실시간 채팅 테스트 모드 실행
 async fn run_live_chat_test() -> Result<()> {
     utils::log("실시간 채팅 테스트 모드 시작");
-    api::scan_channels().await?;
+    api::scan_channels(api::min_live_user_from_env()).await?;
 
     // 웹소켓 태스크들이 계속 돌 수 있도록 프로세스를 유지
     loop {
@@ -91,46 +176,76 @@ async fn run_analysis_chat(opts: &AnalysisChatOpt) -> Result<()> {
         "../web/public/video_with_chat_counts.json",
     )?;
 
+    // 라이브별 시청자 수 타임라인 추출 (live 데몬이 기록한 샘플이 있을 때만)
+    let viewer_samples_path = api::viewer_sampler::viewer_samples_path();
+    if viewer_samples_path.exists() {
+        data::viewer_counts::export_live_viewer_counts(
+            &viewer_samples_path,
+            "../web/public/live_viewer_counts.json",
+        )?;
+    }
+
     // 고유 사용자 수 만 명 이상인 chat_log 필터링
     utils::log("고유 사용자 수 기준 필터링 중...");
     let chat_logs = data::chat::filter_chat_logs_by_user_count(chat_logs, 10000);
 
     // 각 채팅 로그 분석
     if opts.enable_experimental {
-        for chat_log in &chat_logs {
-            let analysis = data::chat::analyze_chat_log(chat_log);
+        for chat_log in &chat_logs.logs {
+            let analysis = data::chat::analyze_chat_log(&chat_logs, chat_log);
             data::chat::print_analysis_summary(chat_log, &analysis, &channels);
         }
     }
 
-    run_channel_distance_analysis(&channels, &chat_logs, opts.enable_experimental)?;
+    // 이전 상태와 비교해 바뀐 비디오/채널이 포함된 쌍만 다시 계산
+    let state_path = opts
+        .state
+        .as_deref()
+        .unwrap_or("../chat_logs_cache/analysis_state.cbor");
+    let mut state = if opts.full {
+        data::analysis_state::AnalysisState::new()
+    } else {
+        data::analysis_state::AnalysisState::load(state_path)
+    };
+    let mode = opts.similarity_mode();
+    let output = state.analyze(&chat_logs, &channels, mode)?;
+
+    export_channel_distances(&output.nodes, &output.links, opts.enable_experimental)?;
 
     if opts.enable_experimental {
-        run_cluster_similar_replays(&channels, &chat_logs);
+        run_cluster_similar_replays(&channels, &chat_logs, mode);
     }
 
-    run_find_related_replays(&channels, &chat_logs)?;
+    export_related_replays(&output.relations)?;
+
+    state.save(state_path)?;
+    utils::log(format!("분석 상태 저장: {}", state_path));
 
     // 메모리 해제하는데 너무 많은 시간을 써서 그냥 메모리 정리는 커널에 던지고 종료
     std::process::exit(0);
 }
 
-pub fn load_channels_and_chat_logs(
-    opts: &AnalysisChatOpt,
-) -> Result<(
-    Vec<data::models::ChannelWithReplays>,
-    Vec<data::models::ChatLog>,
-)> {
-    let file_paths = if opts.files.is_empty() {
+/// 채널 및 리플레이 데이터 파일 경로 목록 (지정하지 않으면 기본 파일 사용)
+pub fn channel_files(files: &[String]) -> Vec<String> {
+    if files.is_empty() {
         vec![
             "../web/public/channel_with_replays_0.json".to_string(),
             "../web/public/channel_with_replays_1.json".to_string(),
         ]
     } else {
-        opts.files.clone()
-    };
+        files.to_vec()
+    }
+}
+
+/// 채널 및 리플레이 데이터 파일들을 로드합니다.
+/// 파일 경로를 지정하지 않으면 기본 경로를 사용합니다.
+pub fn load_channels(files: &[String]) -> Result<Vec<data::models::ChannelWithReplays>> {
+    let file_paths = channel_files(files);
 
-    utils::log(format!("채팅 분석 모드 시작: {}개 파일", file_paths.len()));
+    utils::log(format!(
+        "채널 데이터 로드 시작: {}개 파일",
+        file_paths.len()
+    ));
 
     let mut channels = Vec::new();
     for file_path in &file_paths {
@@ -141,30 +256,39 @@ pub fn load_channels_and_chat_logs(
 
     utils::log(format!("로드된 채널 수: {}", channels.len()));
 
+    Ok(channels)
+}
+
+pub fn load_channels_and_chat_logs(
+    opts: &AnalysisChatOpt,
+) -> Result<(Vec<data::models::ChannelWithReplays>, ChatStore)> {
+    let channels = load_channels(&opts.files)?;
+
     let chat_logs_dir = "../chat_logs";
     utils::log(format!("채팅 로그 폴더에서 데이터 로드: {}", chat_logs_dir));
 
     let chat_logs =
         data::chat::loader::load_all_chat_logs(chat_logs_dir, Some("../chat_logs_cache"))?;
-    utils::log(format!("로드된 채팅 로그 수: {}", chat_logs.len()));
+    utils::log(format!(
+        "로드된 채팅 로그 수: {} (고유 사용자 {}명)",
+        chat_logs.len(),
+        chat_logs.users.len()
+    ));
 
     Ok((channels, chat_logs))
 }
 
-fn run_channel_distance_analysis(
-    channels: &[ChannelWithReplays],
-    chat_logs: &[ChatLog],
+/// 채널 거리 결과를 JSON으로 내보냅니다.
+fn export_channel_distances(
+    nodes: &[ChannelNode],
+    links: &[ChannelLink],
     enable_experimental: bool,
 ) -> Result<()> {
-    // 채널 간 distance 계산
-    utils::log("채널 간 거리 계산 중...");
-    let (nodes, links) = data::chat::calculate_channel_distances(chat_logs, channels, None);
-
-    data::chat::export_channel_distances_json(&nodes, &links, "../web/public/data2.json")?;
+    data::chat::export_channel_distances_json(nodes, links, "../web/public/data2.json")?;
 
     // 연관 채널 링크만 별도 JSON으로 내보내기 (각 채널당 최대 6개, distance ≥ 0.1)
     data::chat::export_related_channel_links_json(
-        &links,
+        links,
         "../web/public/related_channels.json",
         0.01,
         32,
@@ -179,61 +303,68 @@ fn run_channel_distance_analysis(
 
     // 채널별로 가장 가까운 채널 상위 5개 출력
     if enable_experimental {
-        data::chat::print_top_closest_channels(&nodes, &links);
+        data::chat::print_top_closest_channels(nodes, links);
     }
 
     Ok(())
 }
 
-fn run_cluster_similar_replays(channels: &[ChannelWithReplays], chat_logs: &[ChatLog]) {
+fn run_cluster_similar_replays(
+    channels: &[ChannelWithReplays],
+    chat_logs: &ChatStore,
+    mode: SimilarityMode,
+) {
     utils::log("유사한 다시보기 클러스터링 중 (시청자 수 기준)...");
-    let clusters = data::chat::cluster_similar_replays(channels, chat_logs, 0.1);
+    let clusters = data::chat::cluster_similar_replays(channels, chat_logs, 0.1, mode);
     data::chat::print_replay_clusters(&clusters, Some(10000));
 }
 
-/// 비디오 연관도 분석 모드 실행
-fn run_find_related_replays(channels: &[ChannelWithReplays], chat_logs: &[ChatLog]) -> Result<()> {
-    // 모든 비디오 간 연관도 분석
-    let all_relations = data::video_analyzer::analyze_all_video_relations(channels, chat_logs)?;
-
+/// 비디오 연관도 결과를 JSON으로 내보냅니다.
+fn export_related_replays(all_relations: &HashMap<u64, Vec<VideoRelation>>) -> Result<()> {
     // JSON 파일로 저장
     data::video_analyzer::export_video_relations_json(
-        &all_relations,
+        all_relations,
         "../web/public/video_related.json",
         20,
     )?;
 
     // 전체 분석 결과 요약 출력
-    data::video_analyzer::print_all_video_relations(&all_relations, Some(20));
+    data::video_analyzer::print_all_video_relations(all_relations, Some(20));
 
     Ok(())
 }
 
-async fn run_experimental() -> Result<()> {
+async fn run_experimental(config: &EventConfigOpt) -> Result<()> {
+    let settings = config.resolve()?;
     let (_, chat_logs) = load_channels_and_chat_logs(&AnalysisChatOpt::default())?;
 
-    let first_chat = chat_logs
-        .iter()
-        .find(|chat_log| chat_log.video_id == 10066814)
-        .unwrap();
-    let second_chat = chat_logs
-        .iter()
-        .find(|chat_log| chat_log.video_id == 10066747)
-        .unwrap();
-
-    let event = data::chat::detect_event_intervals(first_chat).unwrap();
-    let event2 = data::chat::detect_event_intervals(second_chat).unwrap();
+    let first_chat = chat_logs.get(10066814).unwrap();
+    let second_chat = chat_logs.get(10066747).unwrap();
+
+    let event =
+        data::chat::detect_event_intervals(first_chat, &settings.detection_config(first_chat))
+            .unwrap();
+    let event2 =
+        data::chat::detect_event_intervals(second_chat, &settings.detection_config(second_chat))
+            .unwrap();
     // data::chat::print_event_intervals(&event);
 
     data::chat::print_event_intervals(&event);
 
-    let result = data::chat::match_events_time_only(&event, &event2);
+    let result = data::chat::match_events_time_only(&event, &event2, &settings.matching);
 
     data::chat::print_match_result(&result, &event, &event2);
 
     let top_matched = result.matches.first().unwrap();
 
-    print_matched_event_chats(top_matched, &event, &event2, first_chat, second_chat);
+    print_matched_event_chats(
+        top_matched,
+        &event,
+        &event2,
+        &chat_logs,
+        first_chat,
+        second_chat,
+    );
 
     Ok(())
 }
@@ -243,8 +374,9 @@ fn print_matched_event_chats(
     matched: &data::chat::MatchedEvent,
     event_a: &data::chat::EventDetectionResult,
     event_b: &data::chat::EventDetectionResult,
-    chat_a: &ChatLog,
-    chat_b: &ChatLog,
+    store: &ChatStore,
+    chat_a: &ChatColumns,
+    chat_b: &ChatColumns,
 ) {
     println!("\n=== 매칭된 이벤트 구간의 채팅 ===");
 
@@ -261,25 +393,16 @@ fn print_matched_event_chats(
             .format("%Y-%m-%d %H:%M:%S")
     );
 
-    let mut a_messages: Vec<_> = chat_a
-        .messages
-        .iter()
-        .filter(|msg| {
-            let msg_timestamp = msg.timestamp.timestamp();
-            msg_timestamp >= a_start_abs && msg_timestamp <= a_end_abs
-        })
-        .collect();
+    // 시간 순으로 정렬된 구간 메시지
+    let a_messages = chat_a.indices_between(a_start_abs, a_end_abs);
 
-    // 시간 순으로 정렬
-    a_messages.sort_by_key(|msg| msg.timestamp);
-
-    for msg in a_messages.iter().take(1000) {
+    for &i in a_messages.iter().take(1000) {
         // 상위 50개만 출력
         println!(
             "[{}] {}: {}",
-            msg.timestamp.format("%H:%M:%S"),
-            msg.nickname,
-            msg.message
+            chat_a.datetime(i).format("%H:%M:%S"),
+            store.nickname(chat_a.nickname(i)),
+            chat_a.message(i)
         );
     }
     println!("... (총 {}개 메시지)", a_messages.len());
@@ -297,25 +420,16 @@ fn print_matched_event_chats(
             .format("%Y-%m-%d %H:%M:%S")
     );
 
-    let mut b_messages: Vec<_> = chat_b
-        .messages
-        .iter()
-        .filter(|msg| {
-            let msg_timestamp = msg.timestamp.timestamp();
-            msg_timestamp >= b_start_abs && msg_timestamp <= b_end_abs
-        })
-        .collect();
-
-    // 시간 순으로 정렬
-    b_messages.sort_by_key(|msg| msg.timestamp);
+    // 시간 순으로 정렬된 구간 메시지
+    let b_messages = chat_b.indices_between(b_start_abs, b_end_abs);
 
-    for msg in b_messages.iter().take(1000) {
+    for &i in b_messages.iter().take(1000) {
         // 상위 50개만 출력
         println!(
             "[{}] {}: {}",
-            msg.timestamp.format("%H:%M:%S"),
-            msg.nickname,
-            msg.message
+            chat_b.datetime(i).format("%H:%M:%S"),
+            store.nickname(chat_b.nickname(i)),
+            chat_b.message(i)
         );
     }
     println!("... (총 {}개 메시지)", b_messages.len());
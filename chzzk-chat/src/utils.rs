@@ -2,10 +2,25 @@ use chrono::{Duration as ChronoDuration, Utc};
 use dashmap::DashSet;
 use indicatif::{ProgressBar, ProgressStyle};
 use once_cell::sync::Lazy;
+use tokio::sync::watch;
 
 /// Node.js의 `scrapingChannels` Set 대체
 pub static SCRAPING_CHANNELS: Lazy<DashSet<String>> = Lazy::new(DashSet::new);
 
+/// 종료 요청 신호 (true가 되면 모든 스크래핑 세션이 파일을 flush하고 종료)
+pub static SHUTDOWN: Lazy<watch::Sender<bool>> = Lazy::new(|| watch::channel(false).0);
+
+/// 모든 스크래핑 세션에 종료를 요청합니다.
+pub fn request_shutdown() {
+    SHUTDOWN.send_replace(true);
+}
+
+/// 종료 요청이 들어올 때까지 기다립니다. (이미 요청된 상태면 바로 반환)
+pub async fn wait_for_shutdown() {
+    let mut receiver = SHUTDOWN.subscribe();
+    let _ = receiver.wait_for(|requested| *requested).await;
+}
+
 /// ====== 공통 로그 함수 (KST 기준) ======
 pub fn log(msg: impl AsRef<str>) {
     let now = Utc::now() + ChronoDuration::hours(9);